@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn main() {
+    // Prefer an explicitly provided SHA (e.g. from CI or a Docker build arg) over asking git
+    let git_sha = env::var("GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.is_empty())
+        .or_else(|| {
+            Command::new("git")
+                .args(["rev-parse", "--short", "HEAD"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=GIT_SHA={}", git_sha);
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+
+    embed_migrations(Path::new("migrations"), "migrations.rs");
+    embed_migrations(Path::new("migrations/sqlite"), "sqlite_migrations.rs");
+}
+
+/// Up and down scripts found for one migration version
+#[derive(Default)]
+struct Scripts {
+    name: String,
+    up: Option<PathBuf>,
+    down: Option<PathBuf>,
+}
+
+/// Generate `$OUT_DIR/<out_file>`, embedding every `<version>_<name>.{up,down}.sql` script in `dir`
+fn embed_migrations(dir: &Path, out_file: &str) {
+    println!("cargo:rerun-if-changed={}", dir.display());
+
+    let mut migrations: BTreeMap<i64, Scripts> = BTreeMap::new();
+    for entry in fs::read_dir(dir).expect("migrations directory is readable") {
+        let path = entry.expect("migrations directory is readable").path();
+        if !path.is_file() {
+            continue;
+        }
+        let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+
+        let (stem, is_up) = if let Some(stem) = file_name.strip_suffix(".up.sql") {
+            (stem, true)
+        } else if let Some(stem) = file_name.strip_suffix(".down.sql") {
+            (stem, false)
+        } else {
+            continue;
+        };
+
+        let (version, name) = stem
+            .split_once('_')
+            .and_then(|(version, name)| Some((version.parse::<i64>().ok()?, name)))
+            .unwrap_or_else(|| panic!("migration {} must be named <version>_<name>.(up|down).sql", file_name));
+
+        let scripts = migrations.entry(version).or_default();
+        if !scripts.name.is_empty() && scripts.name != name {
+            panic!(
+                "migration version {} is used by both {} and {}",
+                version, scripts.name, name
+            );
+        }
+        scripts.name = name.to_string();
+
+        let path = fs::canonicalize(&path).unwrap();
+        if is_up {
+            scripts.up = Some(path);
+        } else {
+            scripts.down = Some(path);
+        }
+    }
+
+    let mut generated = String::from("&[\n");
+    for (version, scripts) in &migrations {
+        let up = scripts
+            .up
+            .as_ref()
+            .unwrap_or_else(|| panic!("migration {}_{} has no up script", version, scripts.name));
+        let down = match &scripts.down {
+            Some(path) => format!("Some(include_str!({:?}))", path),
+            None => "None".to_string(),
+        };
+        generated.push_str(&format!(
+            "    Migration {{ version: {}, name: {:?}, up: include_str!({:?}), down: {} }},\n",
+            version, scripts.name, up, down
+        ));
+    }
+    generated.push_str("]\n");
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    fs::write(out_dir.join(out_file), generated).unwrap();
+}
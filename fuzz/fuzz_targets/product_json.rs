@@ -0,0 +1,38 @@
+#![no_main]
+
+use axum::Json;
+use axum::body::Body;
+use axum::extract::FromRequest;
+use axum::http::{Request, header};
+use axum::response::IntoResponse;
+use libfuzzer_sys::fuzz_target;
+use product_catalog_api::error::ApiError;
+use product_catalog_api::models::product::{CreateProductRequest, UpdateProductRequest};
+use serde::de::DeserializeOwned;
+use validator::Validate;
+
+// Product request bodies are either accepted or rejected with a 4xx, never a panic or a 5xx
+fuzz_target!(|data: &[u8]| {
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    runtime.block_on(async {
+        check::<CreateProductRequest>(data).await;
+        check::<UpdateProductRequest>(data).await;
+    });
+});
+
+/// Extract `data` with the `Json` extractor, then validate it like the product handlers do
+async fn check<T: DeserializeOwned + Validate>(data: &[u8]) {
+    let request = Request::builder()
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(data.to_vec()))
+        .unwrap();
+
+    let status = match Json::<T>::from_request(request, &()).await {
+        Ok(Json(request)) => match request.validate() {
+            Ok(()) => return,
+            Err(errors) => ApiError::from(errors).into_response().status(),
+        },
+        Err(rejection) => rejection.into_response().status(),
+    };
+    assert!(status.is_client_error(), "rejected with {}", status);
+}
@@ -0,0 +1,33 @@
+#![no_main]
+
+use axum::extract::Query;
+use axum::http::{StatusCode, Uri};
+use axum::response::IntoResponse;
+use libfuzzer_sys::fuzz_target;
+use product_catalog_api::models::facet::Facet;
+use product_catalog_api::models::product::ProductQueryParams;
+
+// Product list query strings are either parsed into in-bounds pagination or rejected with a 400
+fuzz_target!(|query: &str| {
+    let Ok(uri) = format!("/api/products?{}", query).parse::<Uri>() else {
+        return;
+    };
+
+    let params = match Query::<ProductQueryParams>::try_from_uri(&uri) {
+        Ok(Query(params)) => params,
+        Err(rejection) => {
+            assert_eq!(rejection.into_response().status(), StatusCode::BAD_REQUEST);
+            return;
+        }
+    };
+    let Query(pairs) = Query::<Vec<(String, String)>>::try_from_uri(&uri).expect("query pairs always parse");
+    let params = params.with_filters(pairs);
+
+    assert!(params.page() >= 1);
+    assert!((1..=100).contains(&params.page_size()));
+    assert!(params.offset() >= 0);
+
+    if let Some(Err(error)) = params.facets.as_deref().map(Facet::parse_list) {
+        assert_eq!(error.into_response().status(), StatusCode::BAD_REQUEST);
+    }
+});
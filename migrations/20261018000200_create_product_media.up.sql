@@ -0,0 +1,16 @@
+CREATE TABLE product_media (
+    id SERIAL PRIMARY KEY,
+    product_id INTEGER NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    storage_key VARCHAR NOT NULL UNIQUE,
+    file_name VARCHAR,
+    mime_type VARCHAR NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    width INTEGER,
+    height INTEGER,
+    checksum VARCHAR NOT NULL,
+    alt_text TEXT,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX product_media_product_id_idx ON product_media (product_id, sort_order);
@@ -0,0 +1,13 @@
+CREATE TABLE media_derivatives (
+    id SERIAL PRIMARY KEY,
+    media_id INTEGER NOT NULL REFERENCES product_media (id) ON DELETE CASCADE,
+    name VARCHAR NOT NULL,
+    storage_key VARCHAR NOT NULL UNIQUE,
+    mime_type VARCHAR NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    checksum VARCHAR NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (media_id, name)
+);
@@ -0,0 +1,23 @@
+CREATE TABLE attribute_definitions (
+    id SERIAL PRIMARY KEY,
+    category_id INTEGER NOT NULL REFERENCES categories (id) ON DELETE CASCADE,
+    name VARCHAR NOT NULL,
+    value_type VARCHAR NOT NULL,
+    required BOOLEAN NOT NULL DEFAULT false,
+    allowed_values JSONB,
+    unit VARCHAR,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (category_id, name)
+);
+
+CREATE INDEX attribute_definitions_name_idx ON attribute_definitions (name);
+
+CREATE TABLE product_attributes (
+    product_id INTEGER NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    name VARCHAR NOT NULL,
+    value JSONB NOT NULL,
+    PRIMARY KEY (product_id, name)
+);
+
+CREATE INDEX product_attributes_name_value_idx ON product_attributes (name, (value #>> '{}'));
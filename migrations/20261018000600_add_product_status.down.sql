@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS products_status_idx;
+
+ALTER TABLE products
+    DROP COLUMN IF EXISTS unpublish_at,
+    DROP COLUMN IF EXISTS publish_at,
+    DROP COLUMN IF EXISTS archived_at,
+    DROP COLUMN IF EXISTS published_at,
+    DROP COLUMN IF EXISTS status;
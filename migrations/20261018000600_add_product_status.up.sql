@@ -0,0 +1,12 @@
+ALTER TABLE products
+    ADD COLUMN status VARCHAR NOT NULL DEFAULT 'draft'
+        CHECK (status IN ('draft', 'in_review', 'published', 'archived')),
+    ADD COLUMN published_at TIMESTAMPTZ,
+    ADD COLUMN archived_at TIMESTAMPTZ,
+    ADD COLUMN publish_at TIMESTAMPTZ,
+    ADD COLUMN unpublish_at TIMESTAMPTZ;
+
+-- Products created before the publishing workflow were already live
+UPDATE products SET status = 'published', published_at = created_at;
+
+CREATE INDEX products_status_idx ON products (status);
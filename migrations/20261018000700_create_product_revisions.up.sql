@@ -0,0 +1,32 @@
+CREATE TABLE product_revisions (
+    id SERIAL PRIMARY KEY,
+    product_id INTEGER NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    number INTEGER NOT NULL,
+    status VARCHAR NOT NULL CHECK (status IN ('draft', 'published')),
+    base_number INTEGER,
+    name VARCHAR NOT NULL,
+    description TEXT,
+    price DECIMAL(10, 2) NOT NULL,
+    category_ids JSONB NOT NULL,
+    note VARCHAR,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    published_at TIMESTAMPTZ,
+    UNIQUE (product_id, number)
+);
+
+-- Existing products start their history with their current content
+INSERT INTO product_revisions (product_id, number, status, name, description, price, category_ids, created_at, published_at)
+SELECT
+    p.id,
+    1,
+    'published',
+    p.name,
+    p.description,
+    p.price,
+    COALESCE(
+        (SELECT jsonb_agg(pc.category_id ORDER BY pc.category_id) FROM product_categories pc WHERE pc.product_id = p.id),
+        '[]'::jsonb
+    ),
+    p.updated_at,
+    p.updated_at
+FROM products p;
@@ -0,0 +1,21 @@
+DROP INDEX IF EXISTS api_keys_tenant_id_idx;
+
+ALTER TABLE product_categories
+    DROP CONSTRAINT IF EXISTS product_categories_tenant_category_fkey,
+    DROP CONSTRAINT IF EXISTS product_categories_tenant_product_fkey;
+ALTER TABLE products DROP CONSTRAINT IF EXISTS products_tenant_id_id_key;
+ALTER TABLE categories DROP CONSTRAINT IF EXISTS categories_tenant_id_id_key;
+
+-- Restoring global uniqueness fails if two tenants share a name or SKU
+ALTER TABLE tags DROP CONSTRAINT IF EXISTS tags_tenant_id_name_key;
+ALTER TABLE tags ADD CONSTRAINT tags_name_key UNIQUE (name);
+ALTER TABLE products DROP CONSTRAINT IF EXISTS products_tenant_id_sku_key;
+ALTER TABLE products ADD CONSTRAINT products_sku_key UNIQUE (sku);
+ALTER TABLE categories DROP CONSTRAINT IF EXISTS categories_tenant_id_name_key;
+ALTER TABLE categories ADD CONSTRAINT categories_name_key UNIQUE (name);
+
+ALTER TABLE api_keys DROP COLUMN IF EXISTS tenant_id;
+ALTER TABLE tags DROP COLUMN IF EXISTS tenant_id;
+ALTER TABLE product_categories DROP COLUMN IF EXISTS tenant_id;
+ALTER TABLE products DROP COLUMN IF EXISTS tenant_id;
+ALTER TABLE categories DROP COLUMN IF EXISTS tenant_id;
@@ -0,0 +1,25 @@
+-- Every catalog row belongs to a tenant; rows created before tenancy join the default one
+ALTER TABLE categories ADD COLUMN tenant_id VARCHAR NOT NULL DEFAULT 'default';
+ALTER TABLE products ADD COLUMN tenant_id VARCHAR NOT NULL DEFAULT 'default';
+ALTER TABLE product_categories ADD COLUMN tenant_id VARCHAR NOT NULL DEFAULT 'default';
+ALTER TABLE tags ADD COLUMN tenant_id VARCHAR NOT NULL DEFAULT 'default';
+ALTER TABLE api_keys ADD COLUMN tenant_id VARCHAR NOT NULL DEFAULT 'default';
+
+-- Names, SKUs and tags only need to be unique within a tenant
+ALTER TABLE categories DROP CONSTRAINT IF EXISTS categories_name_key;
+ALTER TABLE categories ADD CONSTRAINT categories_tenant_id_name_key UNIQUE (tenant_id, name);
+ALTER TABLE products DROP CONSTRAINT IF EXISTS products_sku_key;
+ALTER TABLE products ADD CONSTRAINT products_tenant_id_sku_key UNIQUE (tenant_id, sku);
+ALTER TABLE tags DROP CONSTRAINT IF EXISTS tags_name_key;
+ALTER TABLE tags ADD CONSTRAINT tags_tenant_id_name_key UNIQUE (tenant_id, name);
+
+-- A product can only be linked to a category of its own tenant
+ALTER TABLE categories ADD CONSTRAINT categories_tenant_id_id_key UNIQUE (tenant_id, id);
+ALTER TABLE products ADD CONSTRAINT products_tenant_id_id_key UNIQUE (tenant_id, id);
+ALTER TABLE product_categories
+    ADD CONSTRAINT product_categories_tenant_product_fkey
+        FOREIGN KEY (tenant_id, product_id) REFERENCES products (tenant_id, id) ON DELETE CASCADE,
+    ADD CONSTRAINT product_categories_tenant_category_fkey
+        FOREIGN KEY (tenant_id, category_id) REFERENCES categories (tenant_id, id) ON DELETE CASCADE;
+
+CREATE INDEX api_keys_tenant_id_idx ON api_keys (tenant_id);
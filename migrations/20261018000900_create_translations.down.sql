@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS products_search_english_idx;
+DROP TABLE IF EXISTS category_translations;
+DROP TABLE IF EXISTS product_translations;
@@ -0,0 +1,32 @@
+-- Product and category text in locales other than the default one, which is stored on the rows
+-- themselves
+CREATE TABLE product_translations (
+    product_id INTEGER NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    locale VARCHAR NOT NULL,
+    name VARCHAR NOT NULL,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (product_id, locale)
+);
+
+CREATE TABLE category_translations (
+    category_id INTEGER NOT NULL REFERENCES categories (id) ON DELETE CASCADE,
+    locale VARCHAR NOT NULL,
+    name VARCHAR NOT NULL,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (category_id, locale)
+);
+
+-- Full-text search indexes for the locales we publish in; each must use the same expression and
+-- text search configuration as the search query to be usable
+CREATE INDEX products_search_english_idx ON products
+    USING GIN (to_tsvector('english', name || ' ' || coalesce(description, '')));
+CREATE INDEX product_translations_search_german_idx ON product_translations
+    USING GIN (to_tsvector('german', name || ' ' || coalesce(description, '')))
+    WHERE locale = 'de';
+CREATE INDEX product_translations_search_french_idx ON product_translations
+    USING GIN (to_tsvector('french', name || ' ' || coalesce(description, '')))
+    WHERE locale = 'fr';
@@ -0,0 +1,16 @@
+-- Responses to POST requests sent with an `Idempotency-Key` header, replayed when a client retries
+-- with the same key. A row without a response status belongs to a request still in flight.
+CREATE TABLE idempotency_keys (
+    tenant_id VARCHAR NOT NULL,
+    scope VARCHAR NOT NULL,
+    idempotency_key VARCHAR NOT NULL,
+    request_hash VARCHAR NOT NULL,
+    response_status INTEGER,
+    response_content_type VARCHAR,
+    response_body BYTEA,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (tenant_id, scope, idempotency_key)
+);
+
+CREATE INDEX idx_idempotency_keys_expires_at ON idempotency_keys (expires_at);
@@ -0,0 +1,2 @@
+ALTER TABLE product_revisions DROP COLUMN IF EXISTS currency;
+ALTER TABLE products DROP COLUMN IF EXISTS currency;
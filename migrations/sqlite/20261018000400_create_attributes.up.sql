@@ -0,0 +1,24 @@
+-- JSON values are stored as text; a JSON column type would give them numeric affinity
+CREATE TABLE attribute_definitions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    category_id INTEGER NOT NULL REFERENCES categories (id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    value_type TEXT NOT NULL,
+    required BOOLEAN NOT NULL DEFAULT false,
+    allowed_values TEXT,
+    unit TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    UNIQUE (category_id, name)
+);
+
+CREATE INDEX attribute_definitions_name_idx ON attribute_definitions (name);
+
+CREATE TABLE product_attributes (
+    product_id INTEGER NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (product_id, name)
+);
+
+CREATE INDEX product_attributes_name_value_idx ON product_attributes (name, json_extract(value, '$'));
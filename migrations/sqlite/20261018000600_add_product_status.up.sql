@@ -0,0 +1,11 @@
+ALTER TABLE products ADD COLUMN status TEXT NOT NULL DEFAULT 'draft'
+    CHECK (status IN ('draft', 'in_review', 'published', 'archived'));
+ALTER TABLE products ADD COLUMN published_at TEXT;
+ALTER TABLE products ADD COLUMN archived_at TEXT;
+ALTER TABLE products ADD COLUMN publish_at TEXT;
+ALTER TABLE products ADD COLUMN unpublish_at TEXT;
+
+-- Products created before the publishing workflow were already live
+UPDATE products SET status = 'published', published_at = created_at;
+
+CREATE INDEX products_status_idx ON products (status);
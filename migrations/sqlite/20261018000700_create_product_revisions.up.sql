@@ -0,0 +1,32 @@
+CREATE TABLE product_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    product_id INTEGER NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    number INTEGER NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('draft', 'published')),
+    base_number INTEGER,
+    name TEXT NOT NULL,
+    description TEXT,
+    price REAL NOT NULL,
+    category_ids TEXT NOT NULL,
+    note TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    published_at TEXT,
+    UNIQUE (product_id, number)
+);
+
+-- Existing products start their history with their current content
+INSERT INTO product_revisions (product_id, number, status, name, description, price, category_ids, created_at, published_at)
+SELECT
+    p.id,
+    1,
+    'published',
+    p.name,
+    p.description,
+    p.price,
+    (
+        SELECT json_group_array(category_id)
+        FROM (SELECT pc.category_id FROM product_categories pc WHERE pc.product_id = p.id ORDER BY pc.category_id)
+    ),
+    p.updated_at,
+    p.updated_at
+FROM products p;
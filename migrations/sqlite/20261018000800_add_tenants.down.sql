@@ -0,0 +1,26 @@
+DROP INDEX IF EXISTS api_keys_tenant_id_idx;
+
+CREATE TABLE product_categories_without_tenant (
+    product_id INTEGER NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    category_id INTEGER NOT NULL REFERENCES categories (id) ON DELETE CASCADE,
+    PRIMARY KEY (product_id, category_id)
+);
+INSERT INTO product_categories_without_tenant SELECT product_id, category_id FROM product_categories;
+DROP TABLE product_categories;
+ALTER TABLE product_categories_without_tenant RENAME TO product_categories;
+
+DROP INDEX IF EXISTS products_tenant_id_id_key;
+DROP INDEX IF EXISTS categories_tenant_id_id_key;
+
+-- Restoring global uniqueness fails if two tenants share a name or SKU
+DROP INDEX IF EXISTS tags_tenant_id_name_key;
+CREATE UNIQUE INDEX tags_name_key ON tags (name);
+DROP INDEX IF EXISTS products_tenant_id_sku_key;
+CREATE UNIQUE INDEX products_sku_key ON products (sku);
+DROP INDEX IF EXISTS categories_tenant_id_name_key;
+CREATE UNIQUE INDEX categories_name_key ON categories (name);
+
+ALTER TABLE api_keys DROP COLUMN tenant_id;
+ALTER TABLE tags DROP COLUMN tenant_id;
+ALTER TABLE products DROP COLUMN tenant_id;
+ALTER TABLE categories DROP COLUMN tenant_id;
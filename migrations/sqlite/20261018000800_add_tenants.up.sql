@@ -0,0 +1,32 @@
+-- Every catalog row belongs to a tenant; rows created before tenancy join the default one
+ALTER TABLE categories ADD COLUMN tenant_id TEXT NOT NULL DEFAULT 'default';
+ALTER TABLE products ADD COLUMN tenant_id TEXT NOT NULL DEFAULT 'default';
+ALTER TABLE tags ADD COLUMN tenant_id TEXT NOT NULL DEFAULT 'default';
+ALTER TABLE api_keys ADD COLUMN tenant_id TEXT NOT NULL DEFAULT 'default';
+
+-- Names, SKUs and tags only need to be unique within a tenant
+DROP INDEX categories_name_key;
+CREATE UNIQUE INDEX categories_tenant_id_name_key ON categories (tenant_id, name);
+DROP INDEX products_sku_key;
+CREATE UNIQUE INDEX products_tenant_id_sku_key ON products (tenant_id, sku);
+DROP INDEX tags_name_key;
+CREATE UNIQUE INDEX tags_tenant_id_name_key ON tags (tenant_id, name);
+
+-- A product can only be linked to a category of its own tenant. SQLite can't add constraints to
+-- an existing table, so product_categories is rebuilt with them.
+CREATE UNIQUE INDEX categories_tenant_id_id_key ON categories (tenant_id, id);
+CREATE UNIQUE INDEX products_tenant_id_id_key ON products (tenant_id, id);
+
+CREATE TABLE product_categories_with_tenant (
+    product_id INTEGER NOT NULL REFERENCES products (id) ON DELETE CASCADE,
+    category_id INTEGER NOT NULL REFERENCES categories (id) ON DELETE CASCADE,
+    tenant_id TEXT NOT NULL DEFAULT 'default',
+    PRIMARY KEY (product_id, category_id),
+    FOREIGN KEY (tenant_id, product_id) REFERENCES products (tenant_id, id) ON DELETE CASCADE,
+    FOREIGN KEY (tenant_id, category_id) REFERENCES categories (tenant_id, id) ON DELETE CASCADE
+);
+INSERT INTO product_categories_with_tenant (product_id, category_id) SELECT product_id, category_id FROM product_categories;
+DROP TABLE product_categories;
+ALTER TABLE product_categories_with_tenant RENAME TO product_categories;
+
+CREATE INDEX api_keys_tenant_id_idx ON api_keys (tenant_id);
@@ -0,0 +1,16 @@
+-- Responses to POST requests sent with an `Idempotency-Key` header, replayed when a client retries
+-- with the same key. A row without a response status belongs to a request still in flight.
+CREATE TABLE idempotency_keys (
+    tenant_id TEXT NOT NULL,
+    scope TEXT NOT NULL,
+    idempotency_key TEXT NOT NULL,
+    request_hash TEXT NOT NULL,
+    response_status INTEGER,
+    response_content_type TEXT,
+    response_body BLOB,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    expires_at TEXT NOT NULL,
+    PRIMARY KEY (tenant_id, scope, idempotency_key)
+);
+
+CREATE INDEX idx_idempotency_keys_expires_at ON idempotency_keys (expires_at);
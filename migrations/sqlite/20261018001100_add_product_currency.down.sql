@@ -0,0 +1,2 @@
+ALTER TABLE product_revisions DROP COLUMN currency;
+ALTER TABLE products DROP COLUMN currency;
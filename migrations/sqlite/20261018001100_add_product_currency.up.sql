@@ -0,0 +1,3 @@
+-- Prices are money in a currency; existing prices were all dollars
+ALTER TABLE products ADD COLUMN currency TEXT NOT NULL DEFAULT 'USD';
+ALTER TABLE product_revisions ADD COLUMN currency TEXT NOT NULL DEFAULT 'USD';
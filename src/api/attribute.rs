@@ -0,0 +1,102 @@
+use axum::Json;
+use axum::extract::{Path, State};
+use tracing::{info, instrument};
+use validator::Validate;
+
+use crate::auth::Editor;
+use crate::error::ApiError;
+use crate::models::attribute::{AttributeResponse, CreateAttributeRequest, UpdateAttributeRequest};
+use crate::repository::attribute::AttributeRepository;
+use crate::request_context::RequestContext;
+use crate::tenant::Tenant;
+
+/// List a category's attribute definitions
+///
+/// GET /api/categories/:id/attributes
+#[instrument(skip(repository, tenant, ctx), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant))]
+pub async fn list_attributes(
+    ctx: RequestContext,
+    tenant: Tenant,
+    State(repository): State<AttributeRepository>,
+    Path(category_id): Path<i32>,
+) -> Result<Json<Vec<AttributeResponse>>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Listing attributes for category ID: {}", category_id);
+
+    let attributes = repository.list_attributes(category_id).await?;
+
+    info!("Found {} attributes", attributes.len());
+    Ok(Json(attributes))
+}
+
+/// Add an attribute definition to a category
+///
+/// POST /api/categories/:id/attributes
+#[instrument(skip(repository, tenant, request, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn create_attribute(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(repository): State<AttributeRepository>,
+    Path(category_id): Path<i32>,
+    Json(request): Json<CreateAttributeRequest>,
+) -> Result<Json<AttributeResponse>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Creating attribute {} for category ID: {}", request.name, category_id);
+
+    // Validate the request
+    request.validate()?;
+
+    let attribute = repository.create_attribute(category_id, request).await?;
+
+    info!("Created attribute with ID: {}", attribute.id);
+    Ok(Json(attribute))
+}
+
+/// Update an attribute definition
+///
+/// PUT /api/categories/:id/attributes/:attribute_id
+#[instrument(skip(repository, tenant, request, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn update_attribute(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(repository): State<AttributeRepository>,
+    Path((category_id, id)): Path<(i32, i32)>,
+    Json(request): Json<UpdateAttributeRequest>,
+) -> Result<Json<AttributeResponse>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Updating attribute {} of category ID: {}", id, category_id);
+
+    // Validate the request
+    request.validate()?;
+
+    let attribute = repository.update_attribute(category_id, id, request).await?;
+
+    info!("Updated attribute: {}", attribute.name);
+    Ok(Json(attribute))
+}
+
+/// Delete an attribute definition
+///
+/// DELETE /api/categories/:id/attributes/:attribute_id
+#[instrument(skip(repository, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn delete_attribute(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(repository): State<AttributeRepository>,
+    Path((category_id, id)): Path<(i32, i32)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Deleting attribute {} of category ID: {}", id, category_id);
+
+    repository.delete_attribute(category_id, id).await?;
+
+    info!("Attribute deleted successfully");
+    Ok(Json(serde_json::json!({ "message": "Attribute deleted successfully" })))
+}
@@ -0,0 +1,14 @@
+use axum::Json;
+use axum::extract::State;
+use tracing::instrument;
+
+use crate::cache::{CacheStats, CatalogCache};
+use crate::request_context::RequestContext;
+
+/// Get response cache hit/miss statistics
+///
+/// GET /api/cache/stats
+#[instrument(skip(cache, ctx), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id))]
+pub async fn cache_stats(ctx: RequestContext, State(cache): State<CatalogCache>) -> Json<CacheStats> {
+    Json(cache.stats())
+}
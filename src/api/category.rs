@@ -1,29 +1,43 @@
+use std::sync::Arc;
+
 use axum::Json;
 use axum::extract::{Path, Query, State};
 use tracing::{info, instrument};
 use validator::Validate;
 
+use crate::auth::Editor;
 use crate::error::ApiError;
+use crate::locale::LocaleChain;
 use crate::models::category::{
     CategoryListResponse, CategoryQueryParams, CategoryResponse, CreateCategoryRequest, UpdateCategoryRequest,
 };
 use crate::models::product::ProductResponse;
-use crate::repository::category::CategoryRepository;
+use crate::repository::store::CategoryStore;
+use crate::request_context::RequestContext;
+use crate::tenant::Tenant;
 
 /// List all categories
 ///
+/// Product counts only include published products for anonymous callers.
+///
 /// GET /api/categories
-#[instrument(skip(repository))]
+#[instrument(skip(store, tenant, locales, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, locale = %locales.locale()))]
 pub async fn list_categories(
-    State(repository): State<CategoryRepository>,
+    ctx: RequestContext,
+    tenant: Tenant,
+    locales: LocaleChain,
+    editor: Option<Editor>,
+    State(store): State<Arc<dyn CategoryStore>>,
     Query(params): Query<CategoryQueryParams>,
 ) -> Result<Json<CategoryListResponse>, ApiError> {
+    let store = store.for_tenant(&tenant).for_locale(&locales);
+
     info!(
         "Listing categories with product count: {}",
         params.include_product_count()
     );
 
-    let response = repository.list_categories(params).await?;
+    let response = store.list_categories(params, editor.is_some()).await?;
 
     info!("Found {} categories", response.categories.len());
     Ok(Json(response))
@@ -32,14 +46,19 @@ pub async fn list_categories(
 /// Get a category by ID
 ///
 /// GET /api/categories/:id
-#[instrument(skip(repository))]
+#[instrument(skip(store, tenant, locales, ctx), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, locale = %locales.locale()))]
 pub async fn get_category(
-    State(repository): State<CategoryRepository>,
+    ctx: RequestContext,
+    tenant: Tenant,
+    locales: LocaleChain,
+    State(store): State<Arc<dyn CategoryStore>>,
     Path(id): Path<i32>,
 ) -> Result<Json<CategoryResponse>, ApiError> {
+    let store = store.for_tenant(&tenant).for_locale(&locales);
+
     info!("Getting category with ID: {}", id);
 
-    let category = repository.get_category(id).await?;
+    let category = store.get_category(id).await?;
 
     info!("Found category: {}", category.name);
     Ok(Json(category))
@@ -48,11 +67,16 @@ pub async fn get_category(
 /// Create a new category
 ///
 /// POST /api/categories
-#[instrument(skip(repository, request))]
+#[instrument(skip(store, tenant, request, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
 pub async fn create_category(
-    State(repository): State<CategoryRepository>,
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(store): State<Arc<dyn CategoryStore>>,
     Json(request): Json<CreateCategoryRequest>,
 ) -> Result<Json<CategoryResponse>, ApiError> {
+    let store = store.for_tenant(&tenant);
+
     info!("Creating new category: {}", request.name);
 
     // Validate the request
@@ -60,7 +84,7 @@ pub async fn create_category(
     request.validate()?;
 
     // Create the category
-    let category = repository.create_category(request).await?;
+    let category = store.create_category(request).await?;
 
     info!("Created category with ID: {}", category.id);
     Ok(Json(category))
@@ -69,19 +93,24 @@ pub async fn create_category(
 /// Update an existing category
 ///
 /// PUT /api/categories/:id
-#[instrument(skip(repository, request))]
+#[instrument(skip(store, tenant, request, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
 pub async fn update_category(
-    State(repository): State<CategoryRepository>,
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(store): State<Arc<dyn CategoryStore>>,
     Path(id): Path<i32>,
     Json(request): Json<UpdateCategoryRequest>,
 ) -> Result<Json<CategoryResponse>, ApiError> {
+    let store = store.for_tenant(&tenant);
+
     info!("Updating category with ID: {}", id);
 
     // Validate the request
     request.validate()?;
 
     // Update the category
-    let category = repository.update_category(id, request).await?;
+    let category = store.update_category(id, request).await?;
 
     info!("Updated category: {}", category.name);
     Ok(Json(category))
@@ -90,14 +119,19 @@ pub async fn update_category(
 /// Delete a category
 ///
 /// DELETE /api/categories/:id
-#[instrument(skip(repository))]
+#[instrument(skip(store, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
 pub async fn delete_category(
-    State(repository): State<CategoryRepository>,
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(store): State<Arc<dyn CategoryStore>>,
     Path(id): Path<i32>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
+    let store = store.for_tenant(&tenant);
+
     info!("Deleting category with ID: {}", id);
 
-    repository.delete_category(id).await?;
+    store.delete_category(id).await?;
 
     info!("Category deleted successfully");
     Ok(Json(serde_json::json!({ "message": "Category deleted successfully" })))
@@ -106,14 +140,20 @@ pub async fn delete_category(
 /// Get products by category ID
 ///
 /// GET /api/categories/:id/products
-#[instrument(skip(repository))]
+#[instrument(skip(store, tenant, locales, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, locale = %locales.locale()))]
 pub async fn get_category_products(
-    State(repository): State<CategoryRepository>,
+    ctx: RequestContext,
+    tenant: Tenant,
+    locales: LocaleChain,
+    editor: Option<Editor>,
+    State(store): State<Arc<dyn CategoryStore>>,
     Path(id): Path<i32>,
 ) -> Result<Json<Vec<ProductResponse>>, ApiError> {
+    let store = store.for_tenant(&tenant).for_locale(&locales);
+
     info!("Getting products for category ID: {}", id);
 
-    let products = repository.get_products_by_category(id).await?;
+    let products = store.get_products_by_category(id, editor.is_some()).await?;
 
     info!("Found {} products in category", products.len());
     Ok(Json(products))
@@ -0,0 +1,219 @@
+use axum::Json;
+use axum::extract::{Multipart, Path, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use bytes::BytesMut;
+use tracing::{info, instrument};
+use validator::Validate;
+
+use crate::auth::Editor;
+use crate::error::ApiError;
+use crate::models::media::{MediaFile, MediaResponse, UploadMediaRequest};
+use crate::repository::media::MediaRepository;
+use crate::request_context::RequestContext;
+use crate::tenant::Tenant;
+
+/// Upload a file for a product
+///
+/// POST /api/products/:id/media
+///
+/// Expects a `multipart/form-data` body with a `file` part and optional `alt_text` and
+/// `sort_order` parts.
+#[instrument(skip(repository, tenant, multipart, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn upload_media(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(repository): State<MediaRepository>,
+    Path(product_id): Path<i32>,
+    multipart: Multipart,
+) -> Result<Json<MediaResponse>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Uploading media for product with ID: {}", product_id);
+
+    let request = read_upload(multipart, repository.max_upload_bytes()).await?;
+    request.validate()?;
+
+    let media = repository.upload_media(product_id, request).await?;
+
+    info!("Stored media with ID: {} ({} bytes)", media.id, media.size_bytes);
+    Ok(Json(media))
+}
+
+/// List a product's media
+///
+/// Products that aren't published are reported as missing to anonymous callers.
+///
+/// GET /api/products/:id/media
+#[instrument(skip(repository, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant))]
+pub async fn list_media(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Option<Editor>,
+    State(repository): State<MediaRepository>,
+    Path(product_id): Path<i32>,
+) -> Result<Json<Vec<MediaResponse>>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Listing media for product with ID: {}", product_id);
+
+    let media = repository.list_media(product_id, editor.is_some()).await?;
+
+    Ok(Json(media))
+}
+
+/// Serve the contents of a media file
+///
+/// Media of products that aren't published is reported as missing to anonymous callers.
+///
+/// GET /api/products/:id/media/:media_id
+#[instrument(skip(repository, tenant, headers, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant))]
+pub async fn get_media_file(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Option<Editor>,
+    State(repository): State<MediaRepository>,
+    Path((product_id, media_id)): Path<(i32, i32)>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    let file = repository
+        .get_media_file(product_id, media_id, editor.is_some())
+        .await?;
+
+    Ok(file_response(file, &headers))
+}
+
+/// Serve a resized derivative of an image, rendering it on first request
+///
+/// Media of products that aren't published is reported as missing to anonymous callers.
+///
+/// GET /api/products/:id/media/:media_id/:derivative
+#[instrument(skip(repository, tenant, headers, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant))]
+pub async fn get_derivative_file(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Option<Editor>,
+    State(repository): State<MediaRepository>,
+    Path((product_id, media_id, derivative)): Path<(i32, i32, String)>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    let file = repository
+        .get_derivative_file(product_id, media_id, &derivative, editor.is_some())
+        .await?;
+
+    Ok(file_response(file, &headers))
+}
+
+/// Delete a media item
+///
+/// DELETE /api/products/:id/media/:media_id
+#[instrument(skip(repository, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn delete_media(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(repository): State<MediaRepository>,
+    Path((product_id, media_id)): Path<(i32, i32)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Deleting media {} of product {}", media_id, product_id);
+
+    repository.delete_media(product_id, media_id).await?;
+
+    info!("Media deleted successfully");
+    Ok(Json(serde_json::json!({ "message": "Media deleted successfully" })))
+}
+
+/// Respond with a stored file, or `304 Not Modified` when the client's copy is current
+///
+/// Stored files never change, so responses are cacheable indefinitely and revalidated by checksum.
+fn file_response(file: MediaFile, headers: &HeaderMap) -> Response {
+    let etag = format!("\"{}\"", file.checksum);
+    let cache_control = "public, max-age=31536000, immutable";
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"));
+    if not_modified {
+        return (
+            StatusCode::NOT_MODIFIED,
+            [(header::ETAG, etag), (header::CACHE_CONTROL, cache_control.to_string())],
+        )
+            .into_response();
+    }
+
+    (
+        [
+            (header::CONTENT_TYPE, file.mime_type),
+            (header::ETAG, etag),
+            (header::CACHE_CONTROL, cache_control.to_string()),
+        ],
+        file.data,
+    )
+        .into_response()
+}
+
+/// Read the upload form, stopping as soon as the file exceeds `max_bytes`
+async fn read_upload(mut multipart: Multipart, max_bytes: usize) -> Result<UploadMediaRequest, ApiError> {
+    let mut file = None;
+    let mut alt_text = None;
+    let mut sort_order = None;
+
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        match field.name() {
+            Some("file") => {
+                let file_name = field.file_name().map(str::to_string);
+                let declared_type = field.content_type().map(str::to_string);
+
+                let mut data = BytesMut::new();
+                while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+                    if data.len() + chunk.len() > max_bytes {
+                        return Err(ApiError::PayloadTooLarge(format!(
+                            "File exceeds the maximum upload size of {} bytes",
+                            max_bytes
+                        )));
+                    }
+                    data.extend_from_slice(&chunk);
+                }
+
+                file = Some((file_name, declared_type, data.freeze()));
+            }
+            Some("alt_text") => alt_text = Some(field.text().await.map_err(multipart_error)?),
+            Some("sort_order") => {
+                let value = field.text().await.map_err(multipart_error)?;
+                sort_order = Some(
+                    value
+                        .trim()
+                        .parse()
+                        .map_err(|_| ApiError::bad_request("sort_order must be an integer"))?,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    let (file_name, declared_type, data) = file.ok_or_else(|| ApiError::bad_request("Missing file part"))?;
+
+    Ok(UploadMediaRequest {
+        file_name,
+        declared_type,
+        alt_text: alt_text.filter(|alt_text: &String| !alt_text.is_empty()),
+        sort_order,
+        data,
+    })
+}
+
+fn multipart_error(error: axum::extract::multipart::MultipartError) -> ApiError {
+    if error.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        ApiError::PayloadTooLarge(error.body_text())
+    } else {
+        ApiError::bad_request(error.body_text())
+    }
+}
@@ -1,45 +1,242 @@
+pub mod attribute;
+pub mod cache;
 pub mod category;
+pub mod media;
 pub mod product;
+pub mod revision;
+pub mod tag;
+pub mod translation;
 
-use axum::Router;
-use axum::routing::{delete, get, post, put};
+use axum::extract::DefaultBodyLimit;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::routing::{MethodRouter, delete, get, post, put};
+use axum::{Router, middleware};
 use sea_orm::DatabaseConnection;
 
+use crate::auth;
+use crate::cache::CatalogCache;
+use crate::config::Config;
 use crate::database::Database;
+use crate::idempotency::{self, Idempotency};
+use crate::rate_limit::{self, RateLimiter};
+use crate::repository::api_key::ApiKeyRepository;
+use crate::repository::attribute::AttributeRepository;
 use crate::repository::category::CategoryRepository;
+use crate::repository::idempotency::IdempotencyRepository;
+use crate::repository::media::MediaRepository;
 use crate::repository::product::ProductRepository;
+use crate::repository::store::{CategoryStore, ProductStore};
+use crate::repository::tag::TagRepository;
+use crate::repository::translation::TranslationRepository;
+use crate::storage::MediaStorage;
+use crate::{locale, tenant};
 
 /// Create all routes for the API
-pub fn routes(conn: DatabaseConnection) -> Router {
+pub fn routes(
+    conn: DatabaseConnection,
+    cache: CatalogCache,
+    storage: MediaStorage,
+    rate_limiter: RateLimiter,
+    config: &Config,
+) -> Router {
     // Create repositories
-    let product_repository = ProductRepository::new(conn.clone());
-    let category_repository = CategoryRepository::new(conn.clone());
+    let product_repository = ProductRepository::new(conn.clone(), cache.clone(), storage.clone(), &config.facets);
+    let category_repository = CategoryRepository::new(conn.clone(), cache.clone());
+    let attribute_repository = AttributeRepository::new(conn.clone(), cache.clone());
+    let tag_repository = TagRepository::new(conn.clone(), cache.clone());
+    let media_repository = MediaRepository::new(conn.clone(), cache.clone(), storage, &config.storage);
+    let translation_repository = TranslationRepository::new(conn.clone(), &config.localization);
+    let api_key_repository = ApiKeyRepository::new(conn.clone());
+
+    // Claims of requests that never finish expire once they would have timed out
+    let idempotency_repository = IdempotencyRepository::new(
+        conn.clone(),
+        &config.idempotency,
+        Duration::from_secs(config.limits.request_timeout_secs),
+    );
+    let idempotency = Idempotency::new(idempotency_repository, config.limits.max_body_bytes);
 
     // Combine all routes
-    Router::new()
-        .merge(product_routes(product_repository))
-        .merge(category_routes(category_repository))
+    let router = Router::new()
+        .merge(product_routes(Arc::new(product_repository.clone()), &idempotency))
+        .merge(revision_routes(product_repository, &idempotency))
+        .merge(category_routes(Arc::new(category_repository.clone()), &idempotency))
+        .merge(attribute_routes(attribute_repository, &idempotency))
+        .merge(tag_routes(tag_repository))
+        .merge(media_routes(media_repository))
+        .merge(translation_routes(translation_repository));
+
+    let router = if config.features.cache_stats {
+        router.merge(cache_routes(cache))
+    } else {
+        router
+    };
+
+    // Limit key guessing by client address, resolve API keys to editors, then the tenant and
+    // locale, then enforce rate limits; the last layer added runs first
+    router
+        .layer(middleware::from_fn_with_state(
+            rate_limiter.clone(),
+            rate_limit::limit_requests,
+        ))
+        .layer(middleware::from_fn_with_state(
+            config.localization.clone(),
+            locale::resolve_locale,
+        ))
+        .layer(middleware::from_fn_with_state(
+            config.tenancy.clone(),
+            tenant::resolve_tenant,
+        ))
+        .layer(middleware::from_fn_with_state(api_key_repository, auth::authenticate))
+        .layer(middleware::from_fn_with_state(
+            rate_limiter,
+            rate_limit::limit_authentication,
+        ))
 }
 
 /// Create product routes
-fn product_routes(repository: ProductRepository) -> Router {
+fn product_routes(store: Arc<dyn ProductStore>, idempotency: &Idempotency) -> Router {
     Router::new()
         .route("/products", get(product::list_products))
-        .route("/products", post(product::create_product))
+        .route("/products", idempotent(post(product::create_product), idempotency))
         .route("/products/:id", get(product::get_product))
         .route("/products/:id", put(product::update_product))
         .route("/products/:id", delete(product::delete_product))
+        .route("/products/:id/submit", post(product::submit_product))
+        .route("/products/:id/reject", post(product::reject_product))
+        .route("/products/:id/publish", post(product::publish_product))
+        .route("/products/:id/archive", post(product::archive_product))
+        .route("/products/:id/restore", post(product::restore_product))
+        .route("/products/:id/schedule", post(product::schedule_product))
+        .with_state(store)
+}
+
+/// Create product revision routes
+fn revision_routes(repository: ProductRepository, idempotency: &Idempotency) -> Router {
+    Router::new()
+        .route("/products/:id/revisions", get(revision::list_revisions))
+        .route(
+            "/products/:id/revisions",
+            idempotent(post(revision::create_revision), idempotency),
+        )
+        .route("/products/:id/revisions/diff", get(revision::diff_revisions))
+        .route(
+            "/products/:id/revisions/:number/publish",
+            post(revision::publish_revision),
+        )
+        .route(
+            "/products/:id/revisions/:number/revert",
+            post(revision::revert_to_revision),
+        )
         .with_state(repository)
 }
 
 /// Create category routes
-fn category_routes(repository: CategoryRepository) -> Router {
+fn category_routes(store: Arc<dyn CategoryStore>, idempotency: &Idempotency) -> Router {
     Router::new()
         .route("/categories", get(category::list_categories))
-        .route("/categories", post(category::create_category))
+        .route("/categories", idempotent(post(category::create_category), idempotency))
         .route("/categories/:id", get(category::get_category))
         .route("/categories/:id", put(category::update_category))
         .route("/categories/:id", delete(category::delete_category))
         .route("/categories/:id/products", get(category::get_category_products))
+        .with_state(store)
+}
+
+/// Create category attribute definition routes
+fn attribute_routes(repository: AttributeRepository, idempotency: &Idempotency) -> Router {
+    Router::new()
+        .route("/categories/:id/attributes", get(attribute::list_attributes))
+        .route(
+            "/categories/:id/attributes",
+            idempotent(post(attribute::create_attribute), idempotency),
+        )
+        .route(
+            "/categories/:id/attributes/:attribute_id",
+            put(attribute::update_attribute),
+        )
+        .route(
+            "/categories/:id/attributes/:attribute_id",
+            delete(attribute::delete_attribute),
+        )
+        .with_state(repository)
+}
+
+/// Create tag routes
+fn tag_routes(repository: TagRepository) -> Router {
+    Router::new()
+        .route("/tags", get(tag::list_tags))
+        .route("/tags/:id", put(tag::rename_tag))
+        .route("/tags/:id/merge", post(tag::merge_tags))
         .with_state(repository)
 }
+
+/// Create product media routes
+fn media_routes(repository: MediaRepository) -> Router {
+    // Uploads get their own body limit, with headroom for the multipart framing
+    let upload_limit = repository.max_upload_bytes() + 64 * 1024;
+
+    Router::new()
+        .route(
+            "/products/:id/media",
+            post(media::upload_media).layer(DefaultBodyLimit::max(upload_limit)),
+        )
+        .route("/products/:id/media", get(media::list_media))
+        .route("/products/:id/media/:media_id", get(media::get_media_file))
+        .route("/products/:id/media/:media_id", delete(media::delete_media))
+        .route(
+            "/products/:id/media/:media_id/:derivative",
+            get(media::get_derivative_file),
+        )
+        .with_state(repository)
+}
+
+/// Create product and category translation routes
+fn translation_routes(repository: TranslationRepository) -> Router {
+    Router::new()
+        .route(
+            "/products/:id/translations",
+            get(translation::list_product_translations),
+        )
+        .route(
+            "/products/:id/translations/:locale",
+            put(translation::put_product_translation),
+        )
+        .route(
+            "/products/:id/translations/:locale",
+            delete(translation::delete_product_translation),
+        )
+        .route(
+            "/categories/:id/translations",
+            get(translation::list_category_translations),
+        )
+        .route(
+            "/categories/:id/translations/:locale",
+            put(translation::put_category_translation),
+        )
+        .route(
+            "/categories/:id/translations/:locale",
+            delete(translation::delete_category_translation),
+        )
+        .with_state(repository)
+}
+
+/// Make a create route replay its response to retries sent with the same `Idempotency-Key`
+fn idempotent<S>(route: MethodRouter<S>, idempotency: &Idempotency) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    route.layer(middleware::from_fn_with_state(
+        idempotency.clone(),
+        idempotency::idempotent,
+    ))
+}
+
+/// Create cache routes
+fn cache_routes(cache: CatalogCache) -> Router {
+    Router::new()
+        .route("/cache/stats", get(cache::cache_stats))
+        .with_state(cache)
+}
@@ -1,29 +1,48 @@
+use std::sync::Arc;
+
 use axum::Json;
 use axum::extract::{Path, Query, State};
 use tracing::{info, instrument};
 use validator::Validate;
 
+use crate::auth::Editor;
 use crate::error::ApiError;
+use crate::locale::LocaleChain;
+use crate::models::lifecycle::{ProductStatus, ScheduleRequest, Transition};
 use crate::models::product::{
     CreateProductRequest, ProductListResponse, ProductQueryParams, ProductResponse, UpdateProductRequest,
 };
-use crate::repository::product::ProductRepository;
+use crate::repository::store::ProductStore;
+use crate::request_context::RequestContext;
+use crate::tenant::Tenant;
 
-/// List all products with pagination
+/// List all products with pagination, optionally filtered by category, `attr.<name>` values and tags
+///
+/// Anonymous callers only see published products; editors see every status and may filter by it.
 ///
 /// GET /api/products
-#[instrument(skip(repository))]
+#[instrument(skip(store, tenant, locales, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, locale = %locales.locale()))]
 pub async fn list_products(
-    State(repository): State<ProductRepository>,
+    ctx: RequestContext,
+    tenant: Tenant,
+    locales: LocaleChain,
+    editor: Option<Editor>,
+    State(store): State<Arc<dyn ProductStore>>,
     Query(params): Query<ProductQueryParams>,
+    Query(pairs): Query<Vec<(String, String)>>,
 ) -> Result<Json<ProductListResponse>, ApiError> {
+    let store = store.for_tenant(&tenant).for_locale(&locales);
+
+    let mut params = params.with_filters(pairs);
+    params.include_unpublished = editor.is_some();
+
     info!(
         "Listing products with params: page={}, page_size={}",
         params.page().to_string(),
         params.page_size().to_string()
     );
 
-    let response = repository.list_products(params).await?;
+    let response = store.list_products(params).await?;
 
     info!("Found {} products", response.total);
     Ok(Json(response))
@@ -31,15 +50,26 @@ pub async fn list_products(
 
 /// Get a product by ID
 ///
+/// Products that aren't published are reported as missing to anonymous callers.
+///
 /// GET /api/products/:id
-#[instrument(skip(repository))]
+#[instrument(skip(store, tenant, locales, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, locale = %locales.locale()))]
 pub async fn get_product(
-    State(repository): State<ProductRepository>,
+    ctx: RequestContext,
+    tenant: Tenant,
+    locales: LocaleChain,
+    editor: Option<Editor>,
+    State(store): State<Arc<dyn ProductStore>>,
     Path(id): Path<i32>,
 ) -> Result<Json<ProductResponse>, ApiError> {
+    let store = store.for_tenant(&tenant).for_locale(&locales);
+
     info!("Getting product with ID: {}", id);
 
-    let product = repository.get_product(id).await?;
+    let product = store.get_product(id).await?;
+    if editor.is_none() && product.lifecycle.status != ProductStatus::Published {
+        return Err(ApiError::not_found_simple("Product not found"));
+    }
 
     info!("Found product: {}", product.name);
     Ok(Json(product))
@@ -48,18 +78,23 @@ pub async fn get_product(
 /// Create a new product
 ///
 /// POST /api/products
-#[instrument(skip(repository, request))]
+#[instrument(skip(store, tenant, request, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
 pub async fn create_product(
-    State(repository): State<ProductRepository>,
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(store): State<Arc<dyn ProductStore>>,
     Json(request): Json<CreateProductRequest>,
 ) -> Result<Json<ProductResponse>, ApiError> {
+    let store = store.for_tenant(&tenant);
+
     info!("Creating new product: {}", request.name);
 
     // Validate the request
     request.validate()?;
 
     // Create the product
-    let product = repository.create_product(request).await?;
+    let product = store.create_product(request).await?;
 
     info!("Created product with ID: {}", product.id);
     Ok(Json(product))
@@ -68,19 +103,24 @@ pub async fn create_product(
 /// Update an existing product
 ///
 /// PUT /api/products/:id
-#[instrument(skip(repository, request))]
+#[instrument(skip(store, tenant, request, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
 pub async fn update_product(
-    State(repository): State<ProductRepository>,
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(store): State<Arc<dyn ProductStore>>,
     Path(id): Path<i32>,
     Json(request): Json<UpdateProductRequest>,
 ) -> Result<Json<ProductResponse>, ApiError> {
+    let store = store.for_tenant(&tenant);
+
     info!("Updating product with ID: {}", id);
 
     // Validate the request
     request.validate()?;
 
     // Update the product
-    let product = repository.update_product(id, request).await?;
+    let product = store.update_product(id, request).await?;
 
     info!("Updated product: {}", product.name);
     Ok(Json(product))
@@ -89,15 +129,131 @@ pub async fn update_product(
 /// Delete a product
 ///
 /// DELETE /api/products/:id
-#[instrument(skip(repository))]
+#[instrument(skip(store, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
 pub async fn delete_product(
-    State(repository): State<ProductRepository>,
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(store): State<Arc<dyn ProductStore>>,
     Path(id): Path<i32>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
+    let store = store.for_tenant(&tenant);
+
     info!("Deleting product with ID: {}", id);
 
-    repository.delete_product(id).await?;
+    store.delete_product(id).await?;
 
     info!("Product deleted successfully");
     Ok(Json(serde_json::json!({ "message": "Product deleted successfully" })))
 }
+
+/// Send a draft for review
+///
+/// POST /api/products/:id/submit
+#[instrument(skip(store, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn submit_product(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(store): State<Arc<dyn ProductStore>>,
+    Path(id): Path<i32>,
+) -> Result<Json<ProductResponse>, ApiError> {
+    transition_product(ctx, tenant, editor, store, id, Transition::Submit).await
+}
+
+/// Return a product under review to draft
+///
+/// POST /api/products/:id/reject
+#[instrument(skip(store, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn reject_product(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(store): State<Arc<dyn ProductStore>>,
+    Path(id): Path<i32>,
+) -> Result<Json<ProductResponse>, ApiError> {
+    transition_product(ctx, tenant, editor, store, id, Transition::Reject).await
+}
+
+/// Publish a product under review
+///
+/// POST /api/products/:id/publish
+#[instrument(skip(store, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn publish_product(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(store): State<Arc<dyn ProductStore>>,
+    Path(id): Path<i32>,
+) -> Result<Json<ProductResponse>, ApiError> {
+    transition_product(ctx, tenant, editor, store, id, Transition::Publish).await
+}
+
+/// Archive a product that isn't archived yet
+///
+/// POST /api/products/:id/archive
+#[instrument(skip(store, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn archive_product(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(store): State<Arc<dyn ProductStore>>,
+    Path(id): Path<i32>,
+) -> Result<Json<ProductResponse>, ApiError> {
+    transition_product(ctx, tenant, editor, store, id, Transition::Archive).await
+}
+
+/// Restore an archived product as a draft
+///
+/// POST /api/products/:id/restore
+#[instrument(skip(store, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn restore_product(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(store): State<Arc<dyn ProductStore>>,
+    Path(id): Path<i32>,
+) -> Result<Json<ProductResponse>, ApiError> {
+    transition_product(ctx, tenant, editor, store, id, Transition::Restore).await
+}
+
+/// Replace a product's publishing schedule
+///
+/// POST /api/products/:id/schedule
+#[instrument(skip(store, tenant, request, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn schedule_product(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(store): State<Arc<dyn ProductStore>>,
+    Path(id): Path<i32>,
+    Json(request): Json<ScheduleRequest>,
+) -> Result<Json<ProductResponse>, ApiError> {
+    let store = store.for_tenant(&tenant);
+
+    info!("Scheduling product with ID: {}", id);
+
+    let product = store.schedule_product(id, request).await?;
+
+    info!("Scheduled product: {}", product.name);
+    Ok(Json(product))
+}
+
+/// Apply a workflow transition on behalf of an editor, within the calling handler's span
+async fn transition_product(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    store: Arc<dyn ProductStore>,
+    id: i32,
+    transition: Transition,
+) -> Result<Json<ProductResponse>, ApiError> {
+    let store = store.for_tenant(&tenant);
+
+    info!("Applying {} to product with ID: {}", transition.as_str(), id);
+
+    let product = store.transition_product(id, transition).await?;
+
+    info!("Product {} is now {}", product.id, product.lifecycle.status);
+    Ok(Json(product))
+}
@@ -0,0 +1,125 @@
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use tracing::{info, instrument};
+use validator::Validate;
+
+use crate::auth::Editor;
+use crate::error::ApiError;
+use crate::models::product::ProductResponse;
+use crate::models::revision::{CreateRevisionRequest, RevisionDiffParams, RevisionDiffResponse, RevisionResponse};
+use crate::repository::product::ProductRepository;
+use crate::request_context::RequestContext;
+use crate::tenant::Tenant;
+
+/// List a product's revisions, newest first
+///
+/// GET /api/products/:id/revisions
+#[instrument(skip(repository, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn list_revisions(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(repository): State<ProductRepository>,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<RevisionResponse>>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Listing revisions of product with ID: {}", id);
+
+    let revisions = repository.list_revisions(id).await?;
+
+    info!("Found {} revisions", revisions.len());
+    Ok(Json(revisions))
+}
+
+/// Compare two revisions of a product
+///
+/// GET /api/products/:id/revisions/diff?from=:from&to=:to
+#[instrument(skip(repository, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn diff_revisions(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(repository): State<ProductRepository>,
+    Path(id): Path<i32>,
+    Query(params): Query<RevisionDiffParams>,
+) -> Result<Json<RevisionDiffResponse>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!(
+        "Comparing revisions {} and {} of product with ID: {}",
+        params.from, params.to, id
+    );
+
+    let diff = repository.diff_revisions(id, params.from, params.to).await?;
+
+    info!("Found {} changed fields", diff.changes.len());
+    Ok(Json(diff))
+}
+
+/// Prepare a draft revision without changing the live product
+///
+/// POST /api/products/:id/revisions
+#[instrument(skip(repository, tenant, request, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn create_revision(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(repository): State<ProductRepository>,
+    Path(id): Path<i32>,
+    Json(request): Json<CreateRevisionRequest>,
+) -> Result<Json<RevisionResponse>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Creating draft revision of product with ID: {}", id);
+
+    // Validate the request
+    request.validate()?;
+
+    let revision = repository.create_revision(id, request).await?;
+
+    info!("Created draft revision {}", revision.number);
+    Ok(Json(revision))
+}
+
+/// Apply a draft revision over the live product
+///
+/// POST /api/products/:id/revisions/:number/publish
+#[instrument(skip(repository, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn publish_revision(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(repository): State<ProductRepository>,
+    Path((id, number)): Path<(i32, i32)>,
+) -> Result<Json<ProductResponse>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Publishing revision {} of product with ID: {}", number, id);
+
+    let product = repository.publish_revision(id, number).await?;
+
+    info!("Published revision {} of product: {}", number, product.name);
+    Ok(Json(product))
+}
+
+/// Restore the content of an earlier revision
+///
+/// POST /api/products/:id/revisions/:number/revert
+#[instrument(skip(repository, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn revert_to_revision(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(repository): State<ProductRepository>,
+    Path((id, number)): Path<(i32, i32)>,
+) -> Result<Json<ProductResponse>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Reverting product with ID {} to revision {}", id, number);
+
+    let product = repository.revert_to_revision(id, number).await?;
+
+    info!("Reverted product {} to revision {}", product.name, number);
+    Ok(Json(product))
+}
@@ -0,0 +1,80 @@
+use axum::Json;
+use axum::extract::{Path, State};
+use tracing::{info, instrument};
+use validator::Validate;
+
+use crate::auth::Editor;
+use crate::error::ApiError;
+use crate::models::tag::{MergeTagsRequest, RenameTagRequest, TagResponse};
+use crate::repository::tag::TagRepository;
+use crate::request_context::RequestContext;
+use crate::tenant::Tenant;
+
+/// List all tags with their usage counts
+///
+/// Only published products are counted for anonymous callers.
+///
+/// GET /api/tags
+#[instrument(skip(repository, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant))]
+pub async fn list_tags(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Option<Editor>,
+    State(repository): State<TagRepository>,
+) -> Result<Json<Vec<TagResponse>>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Listing tags");
+
+    let tags = repository.list_tags(editor.is_some()).await?;
+
+    info!("Found {} tags", tags.len());
+    Ok(Json(tags))
+}
+
+/// Rename a tag
+///
+/// PUT /api/tags/:id
+#[instrument(skip(repository, tenant, request, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn rename_tag(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(repository): State<TagRepository>,
+    Path(id): Path<i32>,
+    Json(request): Json<RenameTagRequest>,
+) -> Result<Json<TagResponse>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Renaming tag with ID: {}", id);
+
+    // Validate the request
+    request.validate()?;
+
+    let tag = repository.rename_tag(id, request).await?;
+
+    info!("Renamed tag to: {}", tag.name);
+    Ok(Json(tag))
+}
+
+/// Merge a tag into another and delete it
+///
+/// POST /api/tags/:id/merge
+#[instrument(skip(repository, tenant, request, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn merge_tags(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(repository): State<TagRepository>,
+    Path(id): Path<i32>,
+    Json(request): Json<MergeTagsRequest>,
+) -> Result<Json<TagResponse>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Merging tag {} into tag {}", id, request.into);
+
+    let tag = repository.merge_tags(id, request.into).await?;
+
+    info!("Merged tag into: {}", tag.name);
+    Ok(Json(tag))
+}
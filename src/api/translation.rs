@@ -0,0 +1,154 @@
+use axum::Json;
+use axum::extract::{Path, State};
+use tracing::{info, instrument};
+use validator::Validate;
+
+use crate::auth::Editor;
+use crate::error::ApiError;
+use crate::models::translation::{CategoryTranslationRequest, ProductTranslationRequest, TranslationResponse};
+use crate::repository::translation::TranslationRepository;
+use crate::request_context::RequestContext;
+use crate::tenant::Tenant;
+
+/// List a product's translations
+///
+/// Products that aren't published are reported as missing to anonymous callers.
+///
+/// GET /api/products/:id/translations
+#[instrument(skip(repository, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant))]
+pub async fn list_product_translations(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Option<Editor>,
+    State(repository): State<TranslationRepository>,
+    Path(product_id): Path<i32>,
+) -> Result<Json<Vec<TranslationResponse>>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Listing translations for product with ID: {}", product_id);
+
+    let translations = repository
+        .list_product_translations(product_id, editor.is_some())
+        .await?;
+
+    info!("Found {} translations", translations.len());
+    Ok(Json(translations))
+}
+
+/// Create or replace a product's translation
+///
+/// PUT /api/products/:id/translations/:locale
+#[instrument(skip(repository, tenant, request, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn put_product_translation(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(repository): State<TranslationRepository>,
+    Path((product_id, locale)): Path<(i32, String)>,
+    Json(request): Json<ProductTranslationRequest>,
+) -> Result<Json<TranslationResponse>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Translating product with ID: {} into {}", product_id, locale);
+
+    // Validate the request
+    request.validate()?;
+
+    let translation = repository.put_product_translation(product_id, &locale, request).await?;
+
+    info!("Stored translation: {}", translation.name);
+    Ok(Json(translation))
+}
+
+/// Delete a product's translation
+///
+/// DELETE /api/products/:id/translations/:locale
+#[instrument(skip(repository, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn delete_product_translation(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(repository): State<TranslationRepository>,
+    Path((product_id, locale)): Path<(i32, String)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Deleting {} translation of product with ID: {}", locale, product_id);
+
+    repository.delete_product_translation(product_id, &locale).await?;
+
+    info!("Translation deleted successfully");
+    Ok(Json(
+        serde_json::json!({ "message": "Translation deleted successfully" }),
+    ))
+}
+
+/// List a category's translations
+///
+/// GET /api/categories/:id/translations
+#[instrument(skip(repository, tenant, ctx), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant))]
+pub async fn list_category_translations(
+    ctx: RequestContext,
+    tenant: Tenant,
+    State(repository): State<TranslationRepository>,
+    Path(category_id): Path<i32>,
+) -> Result<Json<Vec<TranslationResponse>>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Listing translations for category with ID: {}", category_id);
+
+    let translations = repository.list_category_translations(category_id).await?;
+
+    info!("Found {} translations", translations.len());
+    Ok(Json(translations))
+}
+
+/// Create or replace a category's translation
+///
+/// PUT /api/categories/:id/translations/:locale
+#[instrument(skip(repository, tenant, request, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn put_category_translation(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(repository): State<TranslationRepository>,
+    Path((category_id, locale)): Path<(i32, String)>,
+    Json(request): Json<CategoryTranslationRequest>,
+) -> Result<Json<TranslationResponse>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Translating category with ID: {} into {}", category_id, locale);
+
+    // Validate the request
+    request.validate()?;
+
+    let translation = repository
+        .put_category_translation(category_id, &locale, request)
+        .await?;
+
+    info!("Stored translation: {}", translation.name);
+    Ok(Json(translation))
+}
+
+/// Delete a category's translation
+///
+/// DELETE /api/categories/:id/translations/:locale
+#[instrument(skip(repository, tenant, ctx, editor), fields(request_id = %ctx.request_id, trace_id = %ctx.trace_id, tenant = %tenant, editor = %editor.name))]
+pub async fn delete_category_translation(
+    ctx: RequestContext,
+    tenant: Tenant,
+    editor: Editor,
+    State(repository): State<TranslationRepository>,
+    Path((category_id, locale)): Path<(i32, String)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let repository = repository.for_tenant(&tenant);
+
+    info!("Deleting {} translation of category with ID: {}", locale, category_id);
+
+    repository.delete_category_translation(category_id, &locale).await?;
+
+    info!("Translation deleted successfully");
+    Ok(Json(
+        serde_json::json!({ "message": "Translation deleted successfully" }),
+    ))
+}
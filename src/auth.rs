@@ -0,0 +1,72 @@
+use async_trait::async_trait;
+use axum::extract::{FromRequestParts, State};
+use axum::http::request::Parts;
+use axum::http::{Request, header};
+use axum::middleware::Next;
+use axum::response::Response;
+
+use crate::error::ApiError;
+use crate::repository::ApiKeyRepository;
+use crate::tenant::Tenant;
+
+/// Scheme expected in the `Authorization` header
+const BEARER_PREFIX: &str = "Bearer ";
+
+/// A client that presented an active API key
+///
+/// Editors see products in every workflow status and may move them through the workflow;
+/// anonymous clients only see published products. An editor only works on the tenant their key
+/// was issued for.
+#[derive(Clone, Debug)]
+pub struct Editor {
+    pub api_key_id: i32,
+    pub name: String,
+    pub tenant: Tenant,
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Editor {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Editor>()
+            .cloned()
+            .ok_or_else(|| ApiError::Unauthorized("An API key is required".to_string()))
+    }
+}
+
+/// Middleware that resolves `Authorization: Bearer <secret>` to an [`Editor`]
+///
+/// Requests without the header continue anonymously; a secret that is unknown or revoked is
+/// rejected rather than silently downgraded.
+pub async fn authenticate<B>(
+    State(api_keys): State<ApiKeyRepository>,
+    mut request: Request<B>,
+    next: Next<B>,
+) -> Result<Response, ApiError> {
+    let Some(value) = request.headers().get(header::AUTHORIZATION) else {
+        return Ok(next.run(request).await);
+    };
+
+    let secret = value
+        .to_str()
+        .ok()
+        .and_then(|value| value.strip_prefix(BEARER_PREFIX))
+        .ok_or_else(|| {
+            ApiError::Unauthorized("Expected an Authorization header of the form 'Bearer <key>'".to_string())
+        })?;
+    let api_key = api_keys
+        .find_active_by_secret(secret.trim())
+        .await?
+        .ok_or_else(|| ApiError::Unauthorized("Invalid or revoked API key".to_string()))?;
+
+    request.extensions_mut().insert(Editor {
+        api_key_id: api_key.id,
+        name: api_key.name,
+        tenant: api_key.tenant,
+    });
+
+    Ok(next.run(request).await)
+}
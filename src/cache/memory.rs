@@ -0,0 +1,61 @@
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use lru::LruCache;
+
+use super::CacheStore;
+
+/// In-process LRU cache with per-entry expiry
+pub struct MemoryCache {
+    entries: Mutex<LruCache<String, (String, Instant)>>,
+}
+
+impl MemoryCache {
+    /// Create a new in-memory cache holding at most `capacity` entries
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, LruCache<String, (String, Instant)>>> {
+        self.entries.lock().map_err(|_| anyhow!("Memory cache lock poisoned"))
+    }
+}
+
+#[async_trait]
+impl CacheStore for MemoryCache {
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        let mut entries = self.lock()?;
+
+        match entries.get(key) {
+            Some((value, expires_at)) if *expires_at > Instant::now() => Ok(Some(value.clone())),
+            Some(_) => {
+                entries.pop(key);
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn set(&self, key: &str, value: String, ttl: Duration) -> Result<()> {
+        self.lock()?.put(key.to_string(), (value, Instant::now() + ttl));
+        Ok(())
+    }
+
+    async fn delete(&self, keys: &[String]) -> Result<()> {
+        let mut entries = self.lock()?;
+        for key in keys {
+            entries.pop(key);
+        }
+        Ok(())
+    }
+
+    fn backend(&self) -> &'static str {
+        "memory"
+    }
+}
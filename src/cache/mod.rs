@@ -0,0 +1,215 @@
+pub mod memory;
+pub mod redis;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+pub use memory::MemoryCache;
+pub use self::redis::RedisCache;
+
+use crate::config::CacheConfig;
+
+/// Build the response cache selected by the configuration
+pub async fn connect(config: &CacheConfig) -> Result<CatalogCache> {
+    let ttl = Duration::from_secs(config.ttl_secs);
+
+    let cache = match config.backend.as_str() {
+        "memory" => CatalogCache::new(Arc::new(MemoryCache::new(config.capacity)), ttl),
+        "redis" => {
+            let Some(url) = &config.redis_url else {
+                bail!("cache.redis_url must be set for the redis backend");
+            };
+            CatalogCache::new(Arc::new(RedisCache::connect(url, "catalog:").await?), ttl)
+        }
+        "none" => CatalogCache::disabled(),
+        other => bail!("Unknown cache backend: {}", other),
+    };
+
+    Ok(cache)
+}
+
+/// A key/value store used to cache serialized API responses
+#[async_trait]
+pub trait CacheStore: Send + Sync {
+    /// Fetch the value stored under `key`, if present and not expired
+    async fn get(&self, key: &str) -> Result<Option<String>>;
+
+    /// Store `value` under `key` for at most `ttl`
+    async fn set(&self, key: &str, value: String, ttl: Duration) -> Result<()>;
+
+    /// Remove every key in `keys`
+    async fn delete(&self, keys: &[String]) -> Result<()>;
+
+    /// Short name of the backend, used in logs and stats
+    fn backend(&self) -> &'static str;
+}
+
+/// Store used when caching is disabled: every lookup is a miss
+pub struct NoopCache;
+
+#[async_trait]
+impl CacheStore for NoopCache {
+    async fn get(&self, _key: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    async fn set(&self, _key: &str, _value: String, _ttl: Duration) -> Result<()> {
+        Ok(())
+    }
+
+    async fn delete(&self, _keys: &[String]) -> Result<()> {
+        Ok(())
+    }
+
+    fn backend(&self) -> &'static str {
+        "none"
+    }
+}
+
+/// Cache keys for catalog reads, namespaced by tenant so entries are never shared between them
+pub mod keys {
+    use crate::tenant::Tenant;
+
+    pub fn product(tenant: &Tenant, id: i32) -> String {
+        format!("{}:product:{}", tenant, id)
+    }
+
+    pub fn category(tenant: &Tenant, id: i32) -> String {
+        format!("{}:category:{}", tenant, id)
+    }
+
+    /// The category list, whose product counts differ between editors and anonymous callers
+    pub fn category_list(tenant: &Tenant, include_product_count: bool, include_unpublished: bool) -> String {
+        format!("{}:categories:list:{}:{}", tenant, include_product_count, include_unpublished)
+    }
+
+    /// Every variant of the category list
+    pub fn category_lists(tenant: &Tenant) -> Vec<String> {
+        [false, true]
+            .into_iter()
+            .flat_map(|counts| [false, true].map(|unpublished| category_list(tenant, counts, unpublished)))
+            .collect()
+    }
+}
+
+#[derive(Default)]
+struct CacheMetrics {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    errors: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub backend: String,
+    pub hits: u64,
+    pub misses: u64,
+    pub errors: u64,
+    pub invalidations: u64,
+    pub hit_ratio: f64,
+}
+
+/// Typed, instrumented front for a [`CacheStore`], shared by the repositories
+///
+/// Cache failures are logged and treated as misses so a broken cache never fails a request.
+#[derive(Clone)]
+pub struct CatalogCache {
+    store: Arc<dyn CacheStore>,
+    ttl: Duration,
+    metrics: Arc<CacheMetrics>,
+}
+
+impl CatalogCache {
+    /// Create a new cache over the given store
+    pub fn new(store: Arc<dyn CacheStore>, ttl: Duration) -> Self {
+        Self {
+            store,
+            ttl,
+            metrics: Arc::new(CacheMetrics::default()),
+        }
+    }
+
+    /// Create a cache that never stores anything
+    pub fn disabled() -> Self {
+        Self::new(Arc::new(NoopCache), Duration::ZERO)
+    }
+
+    /// Look up and deserialize a cached value
+    pub async fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let cached = match self.store.get(key).await {
+            Ok(cached) => cached,
+            Err(e) => {
+                self.metrics.errors.fetch_add(1, Ordering::Relaxed);
+                tracing::warn!("Cache get failed for {}: {:?}", key, e);
+                None
+            }
+        };
+
+        match cached.and_then(|value| serde_json::from_str(&value).ok()) {
+            Some(value) => {
+                self.metrics.hits.fetch_add(1, Ordering::Relaxed);
+                Some(value)
+            }
+            None => {
+                self.metrics.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Serialize and store a value
+    pub async fn put<T: Serialize>(&self, key: &str, value: &T) {
+        let value = match serde_json::to_string(value) {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::warn!("Failed to serialize cache entry {}: {:?}", key, e);
+                return;
+            }
+        };
+
+        if let Err(e) = self.store.set(key, value, self.ttl).await {
+            self.metrics.errors.fetch_add(1, Ordering::Relaxed);
+            tracing::warn!("Cache set failed for {}: {:?}", key, e);
+        }
+    }
+
+    /// Remove the given keys
+    pub async fn invalidate(&self, keys: Vec<String>) {
+        if keys.is_empty() {
+            return;
+        }
+
+        tracing::debug!("Invalidating cache keys: {:?}", keys);
+        self.metrics
+            .invalidations
+            .fetch_add(keys.len() as u64, Ordering::Relaxed);
+
+        if let Err(e) = self.store.delete(&keys).await {
+            self.metrics.errors.fetch_add(1, Ordering::Relaxed);
+            tracing::warn!("Cache invalidation failed for {:?}: {:?}", keys, e);
+        }
+    }
+
+    /// Snapshot of the hit/miss counters
+    pub fn stats(&self) -> CacheStats {
+        let hits = self.metrics.hits.load(Ordering::Relaxed);
+        let misses = self.metrics.misses.load(Ordering::Relaxed);
+        let lookups = hits + misses;
+
+        CacheStats {
+            backend: self.store.backend().to_string(),
+            hits,
+            misses,
+            errors: self.metrics.errors.load(Ordering::Relaxed),
+            invalidations: self.metrics.invalidations.load(Ordering::Relaxed),
+            hit_ratio: if lookups == 0 { 0.0 } else { hits as f64 / lookups as f64 },
+        }
+    }
+}
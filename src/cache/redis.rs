@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use ::redis::AsyncCommands;
+use ::redis::aio::MultiplexedConnection;
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+
+use super::CacheStore;
+
+/// Cache backed by Redis or any server speaking the Redis protocol
+pub struct RedisCache {
+    conn: MultiplexedConnection,
+    prefix: String,
+}
+
+impl RedisCache {
+    /// Connect to the server at `url`, namespacing every key with `prefix`
+    pub async fn connect(url: &str, prefix: impl Into<String>) -> Result<Self> {
+        let client = ::redis::Client::open(url).map_err(|e| anyhow!("Invalid Redis URL: {:?}", e))?;
+        let conn = client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!("Redis connection error: {:?}", e))?;
+
+        Ok(Self {
+            conn,
+            prefix: prefix.into(),
+        })
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+}
+
+#[async_trait]
+impl CacheStore for RedisCache {
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        let mut conn = self.conn.clone();
+        Ok(conn.get(self.key(key)).await?)
+    }
+
+    async fn set(&self, key: &str, value: String, ttl: Duration) -> Result<()> {
+        let mut conn = self.conn.clone();
+        let ttl_ms = ttl.as_millis().max(1) as u64;
+        conn.pset_ex::<_, _, ()>(self.key(key), value, ttl_ms).await?;
+        Ok(())
+    }
+
+    async fn delete(&self, keys: &[String]) -> Result<()> {
+        let mut conn = self.conn.clone();
+        let keys: Vec<String> = keys.iter().map(|key| self.key(key)).collect();
+        conn.del::<_, ()>(keys).await?;
+        Ok(())
+    }
+
+    fn backend(&self) -> &'static str {
+        "redis"
+    }
+}
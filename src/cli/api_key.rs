@@ -0,0 +1,77 @@
+use anyhow::Result;
+use clap::Subcommand;
+use validator::Validate;
+
+use super::Repositories;
+use super::output::{self, OutputFormat, Table, cell};
+use crate::models::api_key::{ApiKeyResponse, CreateApiKeyRequest};
+use crate::tenant::Tenant;
+
+#[derive(Subcommand, Debug)]
+pub enum ApiKeyCommand {
+    /// Issue a new API key for the `--tenant` catalog; the secret is shown only once
+    Create {
+        /// Who or what the key is for
+        name: String,
+    },
+    /// Revoke an API key
+    Revoke { id: i32 },
+    /// List API keys
+    List,
+}
+
+/// Run an `api-key` subcommand; new keys grant access to `tenant`
+pub async fn run(
+    command: ApiKeyCommand,
+    repositories: &Repositories,
+    tenant: &Tenant,
+    format: OutputFormat,
+) -> Result<()> {
+    match command {
+        ApiKeyCommand::Create { name } => {
+            let request = CreateApiKeyRequest {
+                name,
+                tenant: tenant.clone(),
+            };
+            request.validate()?;
+
+            let created = repositories.api_keys.create_api_key(request).await?;
+            output::print(format, &created, |created| {
+                format!(
+                    "{}\n\nSecret: {}\nStore it now; it cannot be shown again.",
+                    api_key_table(std::slice::from_ref(&created.api_key)),
+                    created.secret
+                )
+            })
+        }
+        ApiKeyCommand::Revoke { id } => {
+            let api_key = repositories.api_keys.revoke_api_key(id).await?;
+            output::print(format, &api_key, |api_key| {
+                format!("Revoked API key {} ({})", api_key.id, api_key.name)
+            })
+        }
+        ApiKeyCommand::List => {
+            let api_keys = repositories.api_keys.list_api_keys().await?;
+            output::print(format, &api_keys, |api_keys| api_key_table(api_keys))
+        }
+    }
+}
+
+fn api_key_table(api_keys: &[ApiKeyResponse]) -> String {
+    api_keys
+        .iter()
+        .fold(
+            Table::new(vec!["ID", "NAME", "TENANT", "PREFIX", "CREATED AT", "REVOKED AT"]),
+            |table, api_key| {
+                table.row(vec![
+                    api_key.id.to_string(),
+                    api_key.name.clone(),
+                    api_key.tenant.to_string(),
+                    api_key.prefix.clone(),
+                    api_key.created_at.format("%Y-%m-%d %H:%M:%S %z").to_string(),
+                    cell(api_key.revoked_at.map(|at| at.format("%Y-%m-%d %H:%M:%S %z"))),
+                ])
+            },
+        )
+        .render()
+}
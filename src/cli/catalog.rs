@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::Repositories;
+use super::output::{self, OutputFormat, Table};
+use crate::models::attribute::{AttributeValues, CreateAttributeRequest, UpdateAttributeRequest};
+use crate::models::category::{CategoryQueryParams, CreateCategoryRequest, UpdateCategoryRequest};
+use crate::models::lifecycle::ProductStatus;
+use crate::models::money::{self, Money};
+use crate::models::product::{CreateProductRequest, ProductQueryParams, UpdateProductRequest};
+
+/// Demo catalog loaded by `seed` when no fixtures file is given
+const DEFAULT_FIXTURES: &str = include_str!("../../fixtures/catalog.json");
+
+/// Largest page the product list endpoint returns
+const EXPORT_PAGE_SIZE: i64 = 100;
+
+/// Portable catalog document read by `import` and written by `export`
+///
+/// Products refer to categories by name, so a catalog can move between databases.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CatalogFile {
+    #[serde(default)]
+    pub categories: Vec<CategoryRecord>,
+    #[serde(default)]
+    pub products: Vec<ProductRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CategoryRecord {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub attributes: Vec<CreateAttributeRequest>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductRecord {
+    pub name: String,
+    pub description: Option<String>,
+    /// The `price` and `currency` fields
+    #[serde(flatten, with = "money::price")]
+    pub price: Money,
+    pub sku: Option<String>,
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub attributes: AttributeValues,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Workflow status to set; new products without one start as drafts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<ProductStatus>,
+}
+
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub categories_created: usize,
+    pub categories_updated: usize,
+    pub products_created: usize,
+    pub products_updated: usize,
+}
+
+/// Import a catalog file
+pub async fn run_import(path: &Path, repositories: &Repositories, format: OutputFormat) -> Result<()> {
+    let catalog = read_catalog(&std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?)?;
+    let summary = import(&catalog, repositories).await?;
+    print_summary(format, &summary)
+}
+
+/// Export the catalog as JSON to `path`, or to stdout
+pub async fn run_export(path: Option<PathBuf>, repositories: &Repositories) -> Result<()> {
+    let json = serde_json::to_string_pretty(&export(repositories).await?)?;
+    match path {
+        Some(path) => std::fs::write(&path, json).with_context(|| format!("writing {}", path.display()))?,
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+/// Load fixture data, by default the bundled demo catalog
+pub async fn run_seed(fixtures: Option<PathBuf>, repositories: &Repositories, format: OutputFormat) -> Result<()> {
+    let contents = match fixtures {
+        Some(path) => std::fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?,
+        None => DEFAULT_FIXTURES.to_string(),
+    };
+    let summary = import(&read_catalog(&contents)?, repositories).await?;
+    print_summary(format, &summary)
+}
+
+/// Parse a catalog document
+pub fn read_catalog(contents: &str) -> Result<CatalogFile> {
+    serde_json::from_str(contents).context("parsing catalog JSON")
+}
+
+/// Create or update every category and product in `catalog`
+///
+/// Categories are matched by name and products by SKU, so re-importing a file updates rather than
+/// duplicates them; products without a SKU are always created. Attribute definitions are matched
+/// by name within their category and are never deleted by an import. Each record is written in its own
+/// transaction, so a failure part-way leaves earlier records imported.
+pub async fn import(catalog: &CatalogFile, repositories: &Repositories) -> Result<ImportSummary> {
+    let mut summary = ImportSummary::default();
+
+    let params = CategoryQueryParams {
+        include_product_count: Some(false),
+    };
+    let mut category_ids: HashMap<String, i32> = repositories
+        .categories
+        .list_categories(params, true)
+        .await?
+        .categories
+        .into_iter()
+        .map(|category| (category.name, category.id))
+        .collect();
+
+    for record in &catalog.categories {
+        let id = match category_ids.get(&record.name) {
+            Some(id) => {
+                let request = UpdateCategoryRequest {
+                    name: None,
+                    description: record.description.clone(),
+                };
+                repositories.categories.update_category(*id, request).await?;
+                summary.categories_updated += 1;
+                *id
+            }
+            None => {
+                let request = CreateCategoryRequest {
+                    name: record.name.clone(),
+                    description: record.description.clone(),
+                };
+                request
+                    .validate()
+                    .with_context(|| format!("category {:?}", record.name))?;
+                let category = repositories.categories.create_category(request).await?;
+                category_ids.insert(category.name, category.id);
+                summary.categories_created += 1;
+                category.id
+            }
+        };
+
+        import_attributes(id, record, repositories).await?;
+    }
+
+    for record in &catalog.products {
+        let ids = record
+            .categories
+            .iter()
+            .map(|name| {
+                category_ids
+                    .get(name)
+                    .copied()
+                    .ok_or_else(|| anyhow!("product {:?} refers to unknown category {:?}", record.name, name))
+            })
+            .collect::<Result<Vec<i32>>>()?;
+
+        let existing = match &record.sku {
+            Some(sku) => repositories.products.find_product_id_by_sku(sku).await?,
+            None => None,
+        };
+
+        match existing {
+            Some(id) => {
+                let request = UpdateProductRequest {
+                    name: Some(record.name.clone()),
+                    description: record.description.clone(),
+                    price: Some(record.price.amount()),
+                    currency: Some(record.price.currency()),
+                    sku: None,
+                    category_ids: Some(ids),
+                    attributes: Some(record.attributes.clone()),
+                    tags: Some(record.tags.clone()),
+                };
+                request
+                    .validate()
+                    .with_context(|| format!("product {:?}", record.name))?;
+                repositories.products.update_product(id, request).await?;
+                if let Some(status) = record.status {
+                    repositories.products.set_status(id, status).await?;
+                }
+                summary.products_updated += 1;
+            }
+            None => {
+                let request = CreateProductRequest {
+                    name: record.name.clone(),
+                    description: record.description.clone(),
+                    price: record.price,
+                    sku: record.sku.clone(),
+                    category_ids: ids,
+                    attributes: record.attributes.clone(),
+                    tags: record.tags.clone(),
+                };
+                request
+                    .validate()
+                    .with_context(|| format!("product {:?}", record.name))?;
+                let product = repositories.products.create_product(request).await?;
+                if let Some(status) = record.status {
+                    repositories.products.set_status(product.id, status).await?;
+                }
+                summary.products_created += 1;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Read the whole catalog into a portable document
+pub async fn export(repositories: &Repositories) -> Result<CatalogFile> {
+    let params = CategoryQueryParams {
+        include_product_count: Some(false),
+    };
+    let categories = repositories.categories.list_categories(params, true).await?.categories;
+
+    let mut category_records = Vec::with_capacity(categories.len());
+    for category in categories {
+        let attributes = repositories
+            .attributes
+            .list_attributes(category.id)
+            .await?
+            .into_iter()
+            .map(|attribute| CreateAttributeRequest {
+                name: attribute.name,
+                value_type: attribute.value_type,
+                required: attribute.required,
+                values: attribute.values,
+                unit: attribute.unit,
+            })
+            .collect();
+
+        category_records.push(CategoryRecord {
+            name: category.name,
+            description: category.description,
+            attributes,
+        });
+    }
+
+    let mut products = Vec::new();
+    for page in 1.. {
+        let params = ProductQueryParams {
+            page: Some(page),
+            page_size: Some(EXPORT_PAGE_SIZE),
+            category_id: None,
+            q: None,
+            status: None,
+            facets: None,
+            attributes: Default::default(),
+            tags: Vec::new(),
+            include_unpublished: true,
+        };
+        let list = repositories.products.list_products(params).await?;
+        let last_page = page * EXPORT_PAGE_SIZE >= list.total;
+
+        products.extend(list.products.into_iter().map(|product| ProductRecord {
+            name: product.name,
+            description: product.description,
+            price: product.price,
+            sku: product.sku,
+            categories: product.categories.into_iter().map(|category| category.name).collect(),
+            attributes: product.attributes,
+            tags: product.tags,
+            status: Some(product.lifecycle.status),
+        }));
+
+        if last_page {
+            break;
+        }
+    }
+
+    Ok(CatalogFile {
+        categories: category_records,
+        products,
+    })
+}
+
+/// Create or update a category's attribute definitions from its record
+async fn import_attributes(category_id: i32, record: &CategoryRecord, repositories: &Repositories) -> Result<()> {
+    let existing: HashMap<String, i32> = repositories
+        .attributes
+        .list_attributes(category_id)
+        .await?
+        .into_iter()
+        .map(|attribute| (attribute.name, attribute.id))
+        .collect();
+
+    for attribute in &record.attributes {
+        let context = || format!("category {:?} attribute {:?}", record.name, attribute.name);
+        match existing.get(&attribute.name) {
+            Some(id) => {
+                let request = UpdateAttributeRequest {
+                    required: Some(attribute.required),
+                    values: attribute.values.clone(),
+                    unit: attribute.unit.clone(),
+                };
+                request.validate().with_context(context)?;
+                repositories
+                    .attributes
+                    .update_attribute(category_id, *id, request)
+                    .await
+                    .with_context(context)?;
+            }
+            None => {
+                attribute.validate().with_context(context)?;
+                repositories
+                    .attributes
+                    .create_attribute(category_id, attribute.clone())
+                    .await
+                    .with_context(context)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_summary(format: OutputFormat, summary: &ImportSummary) -> Result<()> {
+    output::print(format, summary, |summary| {
+        Table::new(vec!["RECORD", "CREATED", "UPDATED"])
+            .row(vec![
+                "categories".to_string(),
+                summary.categories_created.to_string(),
+                summary.categories_updated.to_string(),
+            ])
+            .row(vec![
+                "products".to_string(),
+                summary.products_created.to_string(),
+                summary.products_updated.to_string(),
+            ])
+            .render()
+    })
+}
@@ -0,0 +1,116 @@
+use anyhow::Result;
+use clap::Subcommand;
+use serde::Serialize;
+
+use super::Repositories;
+use super::output::{self, OutputFormat};
+use crate::models::category::CategoryQueryParams;
+use crate::models::money::Money;
+
+#[derive(Subcommand, Debug)]
+pub enum CategoryCommand {
+    /// Show every category with the products it contains
+    Tree,
+    /// Move every product from one category into another, then delete the first
+    Merge {
+        /// Category to merge and delete
+        source: i32,
+
+        /// Category that receives the products
+        #[arg(long)]
+        into: i32,
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryNode {
+    pub id: i32,
+    pub name: String,
+    pub products: Vec<ProductLeaf>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProductLeaf {
+    pub id: i32,
+    pub name: String,
+    #[serde(flatten)]
+    pub price: Money,
+    pub sku: Option<String>,
+}
+
+/// Run a `category` subcommand
+pub async fn run(command: CategoryCommand, repositories: &Repositories, format: OutputFormat) -> Result<()> {
+    match command {
+        CategoryCommand::Tree => {
+            let tree = category_tree(repositories).await?;
+            output::print(format, &tree, |tree| render_tree(tree))
+        }
+        CategoryCommand::Merge { source, into } => {
+            let target = repositories.categories.merge_categories(source, into).await?;
+            output::print(format, &target, |target| {
+                format!("Merged category {} into #{} {}", source, target.id, target.name)
+            })
+        }
+    }
+}
+
+/// Build the category tree, with categories ordered by name and products by ID
+pub async fn category_tree(repositories: &Repositories) -> Result<Vec<CategoryNode>> {
+    let params = CategoryQueryParams {
+        include_product_count: Some(false),
+    };
+
+    let mut tree = Vec::new();
+    for category in repositories.categories.list_categories(params, true).await?.categories {
+        let mut products = repositories
+            .categories
+            .get_products_by_category(category.id, true)
+            .await?;
+        products.sort_by_key(|product| product.id);
+
+        tree.push(CategoryNode {
+            id: category.id,
+            name: category.name,
+            products: products
+                .into_iter()
+                .map(|product| ProductLeaf {
+                    id: product.id,
+                    name: product.name,
+                    price: product.price,
+                    sku: product.sku,
+                })
+                .collect(),
+        });
+    }
+
+    Ok(tree)
+}
+
+fn render_tree(tree: &[CategoryNode]) -> String {
+    let mut lines = Vec::new();
+    for category in tree {
+        lines.push(format!(
+            "#{} {} ({} products)",
+            category.id,
+            category.name,
+            category.products.len()
+        ));
+        for (i, product) in category.products.iter().enumerate() {
+            let branch = if i + 1 == category.products.len() {
+                "└──"
+            } else {
+                "├──"
+            };
+            let sku = product
+                .sku
+                .as_deref()
+                .map(|sku| format!(" [{}]", sku))
+                .unwrap_or_default();
+            lines.push(format!(
+                "{} #{} {} {}{}",
+                branch, product.id, product.name, product.price, sku
+            ));
+        }
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,87 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Subcommand;
+
+use super::output::{self, OutputFormat, Table, cell};
+use crate::config::Config;
+use crate::database;
+use crate::migration::{self, Migrator};
+
+#[derive(Subcommand, Debug)]
+pub enum MigrateCommand {
+    /// Apply all pending migrations
+    Up,
+    /// Revert the most recently applied migrations
+    Down {
+        /// Number of migrations to revert
+        #[arg(long, default_value_t = 1)]
+        steps: usize,
+    },
+    /// Show applied and pending migrations
+    Status,
+    /// Create empty up and down scripts for a new migration, and their SQLite ports
+    New {
+        /// Short description, used in the file names
+        name: String,
+
+        /// Directory holding the migration scripts
+        #[arg(long, default_value = "migrations")]
+        dir: PathBuf,
+    },
+}
+
+/// Run a `migrate` subcommand
+pub async fn run(command: MigrateCommand, config: &Config, format: OutputFormat) -> Result<()> {
+    match command {
+        MigrateCommand::Up => {
+            let applied = connect(config).await?.up().await?;
+            println!("Applied {} migration(s)", applied.len());
+        }
+        MigrateCommand::Down { steps } => {
+            let reverted = connect(config).await?.down(steps).await?;
+            println!("Reverted {} migration(s)", reverted.len());
+        }
+        MigrateCommand::Status => {
+            let statuses = connect(config).await?.status().await?;
+            output::print(format, &statuses, |statuses| {
+                statuses
+                    .iter()
+                    .fold(
+                        Table::new(vec!["VERSION", "STATE", "APPLIED AT", "NAME"]),
+                        |table, status| {
+                            table.row(vec![
+                                status.version.to_string(),
+                                status.state.to_string(),
+                                cell(status.applied_at.map(|at| at.format("%Y-%m-%d %H:%M:%S UTC"))),
+                                status.name.clone(),
+                            ])
+                        },
+                    )
+                    .render()
+            })?;
+        }
+        MigrateCommand::New { name, dir } => {
+            let (up, down) = migration::create_migration_files(&dir, &name)?;
+            println!("Created {}", up.display());
+            println!("Created {}", down.display());
+
+            // Each migration also needs a SQLite port, under the same name
+            let sqlite = dir.join("sqlite");
+            if sqlite.is_dir() {
+                for script in [up, down] {
+                    let port = sqlite.join(script.file_name().unwrap_or_default());
+                    fs::copy(&script, &port)?;
+                    println!("Created {}", port.display());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn connect(config: &Config) -> Result<Migrator> {
+    Ok(Migrator::new(database::connect(&config.database).await?))
+}
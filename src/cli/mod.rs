@@ -0,0 +1,147 @@
+pub mod api_key;
+pub mod catalog;
+pub mod category;
+pub mod migrate;
+pub mod output;
+pub mod product;
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use sea_orm::DatabaseConnection;
+
+use self::output::OutputFormat;
+use crate::cache::{self, CatalogCache};
+use crate::config::{Config, ConfigArgs};
+use crate::database;
+use crate::repository::{ApiKeyRepository, AttributeRepository, CategoryRepository, ProductRepository};
+use crate::storage::{self, MediaStorage};
+use crate::tenant::Tenant;
+
+/// Product catalog API server
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(flatten)]
+    pub config: ConfigArgs,
+
+    /// Print the effective configuration with secrets redacted, then exit
+    #[arg(long)]
+    pub print_config: bool,
+
+    /// Output format for command results
+    #[arg(long, short = 'o', value_enum, default_value_t = OutputFormat::Table, global = true)]
+    pub output: OutputFormat,
+
+    /// Tenant whose catalog commands work on, and that new API keys are issued for
+    #[arg(long, env = "TENANT", default_value_t, global = true)]
+    pub tenant: Tenant,
+
+    /// Run a maintenance command instead of serving
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Manage database migrations
+    Migrate {
+        #[command(subcommand)]
+        command: migrate::MigrateCommand,
+    },
+    /// Manage products
+    Product {
+        #[command(subcommand)]
+        command: product::ProductCommand,
+    },
+    /// Inspect and reorganize categories
+    Category {
+        #[command(subcommand)]
+        command: category::CategoryCommand,
+    },
+    /// Create or update categories and products from a catalog JSON file
+    Import {
+        /// Catalog file, in the format written by `export`
+        file: PathBuf,
+    },
+    /// Write the whole catalog as JSON
+    Export {
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    /// Load demo data; safe to run repeatedly
+    Seed {
+        /// Catalog file to load instead of the bundled demo catalog
+        #[arg(long)]
+        fixtures: Option<PathBuf>,
+    },
+    /// Manage API keys
+    ApiKey {
+        #[command(subcommand)]
+        command: api_key::ApiKeyCommand,
+    },
+}
+
+/// Repositories shared by the catalog maintenance commands
+#[derive(Clone)]
+pub struct Repositories {
+    pub products: ProductRepository,
+    pub categories: CategoryRepository,
+    pub attributes: AttributeRepository,
+    pub api_keys: ApiKeyRepository,
+}
+
+impl Repositories {
+    /// Create repositories over an existing connection, cache and media storage
+    pub fn new(conn: DatabaseConnection, cache: CatalogCache, storage: MediaStorage, config: &Config) -> Self {
+        Self {
+            products: ProductRepository::new(conn.clone(), cache.clone(), storage, &config.facets),
+            categories: CategoryRepository::new(conn.clone(), cache.clone()),
+            attributes: AttributeRepository::new(conn.clone(), cache),
+            api_keys: ApiKeyRepository::new(conn),
+        }
+    }
+
+    /// Connect to the configured database, cache and media storage
+    ///
+    /// Using the configured cache means writes made here invalidate entries a running server
+    /// shares through Redis.
+    pub async fn connect(config: &Config) -> Result<Self> {
+        let conn = database::connect(&config.database).await?;
+        let cache = cache::connect(&config.cache).await?;
+        let storage = storage::connect(&config.storage).await?;
+        Ok(Self::new(conn, cache, storage, config))
+    }
+
+    /// A copy of these repositories working on `tenant`'s catalog
+    ///
+    /// API keys are shared by every tenant, each key naming the one it grants access to.
+    pub fn for_tenant(&self, tenant: &Tenant) -> Self {
+        Self {
+            products: self.products.for_tenant(tenant),
+            categories: self.categories.for_tenant(tenant),
+            attributes: self.attributes.for_tenant(tenant),
+            api_keys: self.api_keys.clone(),
+        }
+    }
+}
+
+/// Run a maintenance command against `tenant`'s catalog
+pub async fn run(command: Command, config: &Config, tenant: &Tenant, format: OutputFormat) -> Result<()> {
+    if let Command::Migrate { command } = command {
+        return migrate::run(command, config, format).await;
+    }
+
+    let repositories = Repositories::connect(config).await?.for_tenant(tenant);
+    match command {
+        Command::Migrate { .. } => unreachable!("handled above"),
+        Command::Product { command } => product::run(command, &repositories, format).await,
+        Command::Category { command } => category::run(command, &repositories, format).await,
+        Command::Import { file } => catalog::run_import(&file, &repositories, format).await,
+        Command::Export { file } => catalog::run_export(file, &repositories).await,
+        Command::Seed { fixtures } => catalog::run_seed(fixtures, &repositories, format).await,
+        Command::ApiKey { command } => api_key::run(command, &repositories, tenant, format).await,
+    }
+}
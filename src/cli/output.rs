@@ -0,0 +1,81 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// How command results are written to stdout
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+}
+
+/// A plain-text table with left-aligned, space-separated columns
+pub struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Create an empty table with the given column headers
+    pub fn new(headers: Vec<&'static str>) -> Self {
+        Self {
+            headers,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Append a row; cells beyond the header count are ignored
+    pub fn row(mut self, cells: Vec<String>) -> Self {
+        self.rows.push(cells);
+        self
+    }
+
+    /// Render the table, one line per row, without a trailing newline
+    pub fn render(&self) -> String {
+        let widths: Vec<usize> = self
+            .headers
+            .iter()
+            .enumerate()
+            .map(|(i, header)| {
+                self.rows
+                    .iter()
+                    .filter_map(|row| row.get(i))
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    .max(header.len())
+            })
+            .collect();
+
+        let header_cells: Vec<String> = self.headers.iter().map(|header| header.to_string()).collect();
+        std::iter::once(&header_cells)
+            .chain(&self.rows)
+            .map(|row| {
+                widths
+                    .iter()
+                    .enumerate()
+                    .map(|(i, width)| format!("{:<width$}", row.get(i).map_or("", String::as_str), width = width))
+                    .collect::<Vec<_>>()
+                    .join("  ")
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Print `value` as pretty JSON, or as the human-readable text produced by `render`
+pub fn print<T: Serialize>(format: OutputFormat, value: &T, render: impl FnOnce(&T) -> String) -> Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+        OutputFormat::Table => println!("{}", render(value)),
+    }
+    Ok(())
+}
+
+/// Render an optional value as a table cell
+pub fn cell<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "-".to_string(), |value| value.to_string())
+}
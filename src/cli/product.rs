@@ -0,0 +1,189 @@
+use anyhow::{Result, anyhow};
+use clap::Subcommand;
+use sea_orm::prelude::Decimal;
+use serde_json::json;
+use validator::Validate;
+
+use super::Repositories;
+use super::output::{self, OutputFormat, Table, cell};
+use crate::models::attribute::AttributeValues;
+use crate::models::lifecycle::ProductStatus;
+use crate::models::money::{self, Currency, Money};
+use crate::models::product::{CreateProductRequest, ProductQueryParams, ProductResponse};
+
+#[derive(Subcommand, Debug)]
+pub enum ProductCommand {
+    /// Create a product
+    Create {
+        #[arg(long)]
+        name: String,
+
+        /// Price with at most two decimal places, such as 19.99
+        #[arg(long, value_parser = money::parse_price)]
+        price: Decimal,
+
+        /// ISO 4217 currency code of the price
+        #[arg(long, default_value_t = Currency::USD)]
+        currency: Currency,
+
+        #[arg(long)]
+        description: Option<String>,
+
+        #[arg(long)]
+        sku: Option<String>,
+
+        /// Category ID; repeat to add the product to several categories
+        #[arg(long = "category", required = true)]
+        category_ids: Vec<i32>,
+
+        /// Attribute value as NAME=VALUE; VALUE is read as JSON, or as text if it isn't valid JSON
+        #[arg(long = "attr", value_parser = parse_attribute)]
+        attributes: Vec<(String, String)>,
+
+        /// Tag; repeat to add several
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    /// Show a product
+    Get { id: i32 },
+    /// List products, a page at a time
+    List {
+        #[arg(long, default_value_t = 1)]
+        page: i64,
+
+        #[arg(long, default_value_t = 10)]
+        page_size: i64,
+
+        /// Only list products in this category
+        #[arg(long)]
+        category: Option<i32>,
+
+        /// Only list products whose attribute equals a value, as NAME=VALUE; repeatable
+        #[arg(long = "attr", value_parser = parse_attribute)]
+        attributes: Vec<(String, String)>,
+
+        /// Only list products with this tag; repeat to require several
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Only list products in this workflow status: draft, in_review, published or archived
+        #[arg(long)]
+        status: Option<ProductStatus>,
+    },
+    /// Delete a product
+    Delete { id: i32 },
+}
+
+/// Run a `product` subcommand
+pub async fn run(command: ProductCommand, repositories: &Repositories, format: OutputFormat) -> Result<()> {
+    match command {
+        ProductCommand::Create {
+            name,
+            price,
+            currency,
+            description,
+            sku,
+            category_ids,
+            attributes,
+            tags,
+        } => {
+            let request = CreateProductRequest {
+                name,
+                description,
+                price: Money::new(price, currency).map_err(|error| anyhow!(error))?,
+                sku,
+                category_ids,
+                attributes: attributes
+                    .into_iter()
+                    .map(|(name, value)| {
+                        let value = serde_json::from_str(&value).unwrap_or(serde_json::Value::String(value));
+                        (name, value)
+                    })
+                    .collect::<AttributeValues>(),
+                tags,
+            };
+            request.validate()?;
+
+            let product = repositories.products.create_product(request).await?;
+            output::print(format, &product, |product| product_table(std::slice::from_ref(product)))
+        }
+        ProductCommand::Get { id } => {
+            let product = repositories.products.get_product(id).await?;
+            output::print(format, &product, |product| product_table(std::slice::from_ref(product)))
+        }
+        ProductCommand::List {
+            page,
+            page_size,
+            category,
+            attributes,
+            tags,
+            status,
+        } => {
+            let filters = attributes
+                .into_iter()
+                .map(|(name, value)| (format!("attr.{}", name), value))
+                .chain(tags.into_iter().map(|tag| ("tag".to_string(), tag)))
+                .collect();
+            let params = ProductQueryParams {
+                page: Some(page),
+                page_size: Some(page_size),
+                category_id: category,
+                q: None,
+                status,
+                facets: None,
+                attributes: Default::default(),
+                tags: Vec::new(),
+                include_unpublished: true,
+            }
+            .with_filters(filters);
+            let list = repositories.products.list_products(params).await?;
+            output::print(format, &list, |list| {
+                format!(
+                    "{}\n\nPage {} of {} products ({} per page)",
+                    product_table(&list.products),
+                    list.page,
+                    list.total,
+                    list.page_size
+                )
+            })
+        }
+        ProductCommand::Delete { id } => {
+            repositories.products.delete_product(id).await?;
+            output::print(format, &json!({ "id": id, "deleted": true }), |_| {
+                format!("Deleted product {}", id)
+            })
+        }
+    }
+}
+
+/// Parse a `NAME=VALUE` attribute argument
+fn parse_attribute(arg: &str) -> Result<(String, String)> {
+    let (name, value) = arg
+        .split_once('=')
+        .ok_or_else(|| anyhow!("expected NAME=VALUE, got {:?}", arg))?;
+    Ok((name.to_string(), value.to_string()))
+}
+
+fn product_table(products: &[ProductResponse]) -> String {
+    products
+        .iter()
+        .fold(
+            Table::new(vec!["ID", "NAME", "PRICE", "SKU", "STATUS", "CATEGORIES"]),
+            |table, product| {
+                table.row(vec![
+                    product.id.to_string(),
+                    product.name.clone(),
+                    product.price.to_string(),
+                    cell(product.sku.as_ref()),
+                    product.lifecycle.status.to_string(),
+                    product
+                        .categories
+                        .iter()
+                        .map(|category| category.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ])
+            },
+        )
+        .render()
+}
@@ -1,19 +1,47 @@
 use std::time::Duration;
 
 use anyhow::{Result, anyhow};
+use sea_orm::{ConnectionTrait, DbBackend, sqlx};
+use serde::Serialize;
+
+use crate::config::DatabaseConfig;
 // Re-export Sea-ORM types for future use
 pub use sea_orm::{ConnectOptions, Database, DatabaseConnection, DbErr, TransactionTrait};
 
 /// Create a new database connection pool
-pub async fn connect(database_url: &str) -> Result<DatabaseConnection> {
-    let mut opt = ConnectOptions::new(database_url);
-    opt.max_connections(5)
-        .min_connections(1)
-        .connect_timeout(Duration::from_secs(3))
-        .idle_timeout(Duration::from_secs(60))
-        .sqlx_logging(true);
+pub async fn connect(config: &DatabaseConfig) -> Result<DatabaseConnection> {
+    let mut opt = ConnectOptions::new(&config.url);
+    opt.max_connections(config.max_connections)
+        .min_connections(config.min_connections)
+        .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
+        .idle_timeout(Duration::from_secs(config.idle_timeout_secs))
+        .sqlx_logging(config.log_statements);
 
     Database::connect(opt)
         .await
         .map_err(|sea_err| anyhow!("Database connection error: {:?}", sea_err))
 }
+
+/// Snapshot of connection pool usage
+#[derive(Debug, Serialize)]
+pub struct PoolStats {
+    pub size: u32,
+    pub idle: u32,
+    pub max: u32,
+}
+
+/// Read the current connection pool usage
+pub fn pool_stats(conn: &DatabaseConnection) -> PoolStats {
+    match conn.get_database_backend() {
+        DbBackend::Sqlite => stats_of(conn.get_sqlite_connection_pool()),
+        _ => stats_of(conn.get_postgres_connection_pool()),
+    }
+}
+
+fn stats_of<DB: sqlx::Database>(pool: &sqlx::Pool<DB>) -> PoolStats {
+    PoolStats {
+        size: pool.size(),
+        idle: pool.num_idle() as u32,
+        max: pool.options().get_max_connections(),
+    }
+}
@@ -0,0 +1,75 @@
+use std::io::Cursor;
+
+use anyhow::{Result, bail};
+use bytes::Bytes;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{ImageFormat, ImageReader, Limits};
+
+use crate::config::DerivativeConfig;
+
+/// Quality used for JPEG derivatives
+const JPEG_QUALITY: u8 = 85;
+
+/// Largest decoded image accepted, guarding against decompression bombs
+const MAX_DECODE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// A derivative image ready to store
+pub struct Rendered {
+    pub data: Bytes,
+    pub mime_type: &'static str,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Scale `original` down to fit the derivative's width and re-encode it in its format
+///
+/// Images narrower than the derivative keep their size. Decoding is CPU-bound, so async
+/// callers should run this on a blocking thread.
+pub fn render(original: &[u8], spec: &DerivativeConfig) -> Result<Rendered> {
+    let mut limits = Limits::default();
+    limits.max_alloc = Some(MAX_DECODE_BYTES);
+    let mut reader = ImageReader::new(Cursor::new(original)).with_guessed_format()?;
+    reader.limits(limits);
+    let mut image = reader.decode()?;
+
+    if image.width() > spec.width {
+        image = image.resize(spec.width, u32::MAX, FilterType::Lanczos3);
+    }
+
+    let mut data = Vec::new();
+    match spec.format.as_str() {
+        "webp" => image
+            .to_rgba8()
+            .write_to(&mut Cursor::new(&mut data), ImageFormat::WebP)?,
+        "jpeg" => image
+            .to_rgb8()
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut data, JPEG_QUALITY))?,
+        "png" => image.write_to(&mut Cursor::new(&mut data), ImageFormat::Png)?,
+        other => bail!("Unsupported derivative format: {}", other),
+    }
+
+    Ok(Rendered {
+        data: Bytes::from(data),
+        mime_type: mime_type(&spec.format),
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+/// Storage key of a derivative, next to the original
+///
+/// The key encodes the width and format, so changing a derivative's configuration produces a new
+/// object instead of serving the stale one.
+pub fn storage_key(original_key: &str, spec: &DerivativeConfig) -> String {
+    let stem = original_key.rsplit_once('.').map_or(original_key, |(stem, _)| stem);
+    format!("{}/{}-{}w.{}", stem, spec.name, spec.width, spec.format)
+}
+
+fn mime_type(format: &str) -> &'static str {
+    match format {
+        "webp" => "image/webp",
+        "jpeg" => "image/jpeg",
+        _ => "image/png",
+    }
+}
@@ -8,20 +8,36 @@ use serde::{Deserialize, Serialize};
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
-    #[sea_orm(unique)]
     pub name: String,
     #[sea_orm(column_type = "Text", nullable)]
     pub description: Option<String>,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
+    pub tenant_id: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::attribute_definitions::Entity")]
+    AttributeDefinitions,
+    #[sea_orm(has_many = "super::category_translations::Entity")]
+    CategoryTranslations,
     #[sea_orm(has_many = "super::product_categories::Entity")]
     ProductCategories,
 }
 
+impl Related<super::attribute_definitions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AttributeDefinitions.def()
+    }
+}
+
+impl Related<super::category_translations::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CategoryTranslations.def()
+    }
+}
+
 impl Related<super::product_categories::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::ProductCategories.def()
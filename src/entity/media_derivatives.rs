@@ -0,0 +1,39 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "media_derivatives")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub media_id: i32,
+    pub name: String,
+    #[sea_orm(unique)]
+    pub storage_key: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub width: i32,
+    pub height: i32,
+    pub checksum: String,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::product_media::Entity",
+        from = "Column::MediaId",
+        to = "super::product_media::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    ProductMedia,
+}
+
+impl Related<super::product_media::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProductMedia.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
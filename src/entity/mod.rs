@@ -1,17 +1,68 @@
+pub mod api_keys;
+pub mod attribute_definitions;
 pub mod categories;
+pub mod category_translations;
+pub mod idempotency_keys;
+pub mod media_derivatives;
+pub mod product_attributes;
 pub mod product_categories;
+pub mod product_media;
+pub mod product_revisions;
+pub mod product_tags;
+pub mod product_translations;
 pub mod products;
+pub mod tags;
 
 // Re-export with singular names for readability and domain semantics
+pub use api_keys::{ActiveModel as ApiKeyActiveModel, Column as ApiKeyColumn, Entity as ApiKey, Model as ApiKeyModel};
+pub use attribute_definitions::{
+    ActiveModel as AttributeDefinitionActiveModel, Column as AttributeDefinitionColumn, Entity as AttributeDefinition,
+    Model as AttributeDefinitionModel,
+};
 pub use categories::{
     ActiveModel as CategoryActiveModel, Column as CategoryColumn, Entity as Category, Model as CategoryModel,
     Relation as CategoryRelation,
 };
+pub use category_translations::{
+    ActiveModel as CategoryTranslationActiveModel, Column as CategoryTranslationColumn, Entity as CategoryTranslation,
+    Model as CategoryTranslationModel,
+};
+pub use idempotency_keys::{
+    ActiveModel as IdempotencyKeyActiveModel, Column as IdempotencyKeyColumn, Entity as IdempotencyKey,
+    Model as IdempotencyKeyModel,
+};
+pub use media_derivatives::{
+    ActiveModel as MediaDerivativeActiveModel, Column as MediaDerivativeColumn, Entity as MediaDerivative,
+    Model as MediaDerivativeModel,
+};
+pub use product_attributes::{
+    ActiveModel as ProductAttributeActiveModel, Column as ProductAttributeColumn, Entity as ProductAttribute,
+    Model as ProductAttributeModel,
+};
 pub use product_categories::{
     ActiveModel as ProductCategoryActiveModel, Column as ProductCategoryColumn, Entity as ProductCategory,
     Model as ProductCategoryModel, Relation as ProductCategoryRelation,
 };
+pub use product_media::{
+    ActiveModel as ProductMediaActiveModel, Column as ProductMediaColumn, Entity as ProductMedia,
+    Model as ProductMediaModel,
+};
+pub use product_revisions::{
+    ActiveModel as ProductRevisionActiveModel, Column as ProductRevisionColumn, Entity as ProductRevision,
+    Model as ProductRevisionModel,
+};
+pub use product_tags::{
+    ActiveModel as ProductTagActiveModel, Column as ProductTagColumn, Entity as ProductTag, Model as ProductTagModel,
+    Relation as ProductTagRelation,
+};
+pub use product_translations::{
+    ActiveModel as ProductTranslationActiveModel, Column as ProductTranslationColumn, Entity as ProductTranslation,
+    Model as ProductTranslationModel,
+};
 pub use products::{
     ActiveModel as ProductActiveModel, Column as ProductColumn, Entity as Product, Model as ProductModel,
     Relation as ProductRelation,
 };
+pub use tags::{
+    ActiveModel as TagActiveModel, Column as TagColumn, Entity as Tag, Model as TagModel, Relation as TagRelation,
+};
@@ -10,6 +10,7 @@ pub struct Model {
     pub product_id: i32,
     #[sea_orm(primary_key, auto_increment = false)]
     pub category_id: i32,
+    pub tenant_id: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
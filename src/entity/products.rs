@@ -13,16 +13,40 @@ pub struct Model {
     pub description: Option<String>,
     #[sea_orm(column_type = "Decimal(Some((10, 2)))")]
     pub price: Decimal,
-    #[sea_orm(unique)]
+    pub currency: String,
     pub sku: Option<String>,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
+    pub status: String,
+    pub published_at: Option<DateTimeWithTimeZone>,
+    pub archived_at: Option<DateTimeWithTimeZone>,
+    pub publish_at: Option<DateTimeWithTimeZone>,
+    pub unpublish_at: Option<DateTimeWithTimeZone>,
+    pub tenant_id: String,
 }
 
+// Variants are named after the related tables, which all belong to products
+#[allow(clippy::enum_variant_names)]
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::product_attributes::Entity")]
+    ProductAttributes,
     #[sea_orm(has_many = "super::product_categories::Entity")]
     ProductCategories,
+    #[sea_orm(has_many = "super::product_media::Entity")]
+    ProductMedia,
+    #[sea_orm(has_many = "super::product_revisions::Entity")]
+    ProductRevisions,
+    #[sea_orm(has_many = "super::product_tags::Entity")]
+    ProductTags,
+    #[sea_orm(has_many = "super::product_translations::Entity")]
+    ProductTranslations,
+}
+
+impl Related<super::product_attributes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProductAttributes.def()
+    }
 }
 
 impl Related<super::product_categories::Entity> for Entity {
@@ -31,6 +55,30 @@ impl Related<super::product_categories::Entity> for Entity {
     }
 }
 
+impl Related<super::product_media::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProductMedia.def()
+    }
+}
+
+impl Related<super::product_revisions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProductRevisions.def()
+    }
+}
+
+impl Related<super::product_tags::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProductTags.def()
+    }
+}
+
+impl Related<super::product_translations::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProductTranslations.def()
+    }
+}
+
 impl Related<super::categories::Entity> for Entity {
     fn to() -> RelationDef {
         super::product_categories::Relation::Categories.def()
@@ -41,4 +89,14 @@ impl Related<super::categories::Entity> for Entity {
     }
 }
 
+impl Related<super::tags::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::product_tags::Relation::Tags.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::product_tags::Relation::Products.def().rev())
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
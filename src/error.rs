@@ -1,9 +1,12 @@
 use axum::Json;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
+use sea_orm::{DbErr, SqlErr};
 use serde_json::json;
 use thiserror::Error;
 
+use crate::metrics;
+
 #[derive(Error, Debug)]
 pub enum ApiError {
     #[error("Database error: {0}")]
@@ -26,21 +29,42 @@ pub enum ApiError {
 
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
+    #[error("Unsupported media type: {0}")]
+    UnsupportedMediaType(String),
+
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
+
+    #[error("Gateway timeout: {0}")]
+    GatewayTimeout(String),
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
-            Self::Database(ref e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+            Self::Database(ref e) => database_error_status(e),
             Self::NotFound(ref message) => (StatusCode::NOT_FOUND, message.clone()),
             Self::BadRequest(ref message) => (StatusCode::BAD_REQUEST, message.clone()),
             Self::Internal(ref message) => (StatusCode::INTERNAL_SERVER_ERROR, message.clone()),
             Self::Validation(ref message) => (StatusCode::UNPROCESSABLE_ENTITY, message.clone()),
             Self::Conflict(ref message) => (StatusCode::CONFLICT, message.clone()),
             Self::Unauthorized(ref message) => (StatusCode::UNAUTHORIZED, message.clone()),
+            Self::Forbidden(ref message) => (StatusCode::FORBIDDEN, message.clone()),
+            Self::PayloadTooLarge(ref message) => (StatusCode::PAYLOAD_TOO_LARGE, message.clone()),
+            Self::UnsupportedMediaType(ref message) => (StatusCode::UNSUPPORTED_MEDIA_TYPE, message.clone()),
+            Self::TooManyRequests(ref message) => (StatusCode::TOO_MANY_REQUESTS, message.clone()),
+            Self::GatewayTimeout(ref message) => (StatusCode::GATEWAY_TIMEOUT, message.clone()),
         };
 
         tracing::error!("API error: {}", error_message);
+        metrics::record_api_error(self.kind());
 
         let body = Json(json!({
             "error": {
@@ -53,8 +77,40 @@ impl IntoResponse for ApiError {
     }
 }
 
+/// Status and message for a database error
+///
+/// Constraint violations are caused by the request rather than the server: a value that must be
+/// unique, such as a SKU, is already taken (409), or a referenced row doesn't exist (400).
+fn database_error_status(e: &DbErr) -> (StatusCode, String) {
+    match e.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(message)) => (StatusCode::CONFLICT, message),
+        Some(SqlErr::ForeignKeyConstraintViolation(message)) => (StatusCode::BAD_REQUEST, message),
+        // Every pooled connection stayed busy; the request may succeed once load drops
+        _ if matches!(e, DbErr::ConnectionAcquire(_)) => (StatusCode::SERVICE_UNAVAILABLE, e.to_string()),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
 // Utility methods for common errors
 impl ApiError {
+    /// Short, stable name of the error variant, used as a metrics label
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Database(_) => "database",
+            Self::NotFound(_) => "not_found",
+            Self::BadRequest(_) => "bad_request",
+            Self::Internal(_) => "internal",
+            Self::Validation(_) => "validation",
+            Self::Conflict(_) => "conflict",
+            Self::Unauthorized(_) => "unauthorized",
+            Self::Forbidden(_) => "forbidden",
+            Self::PayloadTooLarge(_) => "payload_too_large",
+            Self::UnsupportedMediaType(_) => "unsupported_media_type",
+            Self::TooManyRequests(_) => "too_many_requests",
+            Self::GatewayTimeout(_) => "gateway_timeout",
+        }
+    }
+
     pub fn not_found(resource: &str, id: impl std::fmt::Display) -> Self {
         Self::NotFound(format!("{} with ID {} not found", resource, id))
     }
@@ -73,7 +129,19 @@ impl ApiError {
 }
 
 impl From<validator::ValidationErrors> for ApiError {
-    fn from(_errors: validator::ValidationErrors) -> Self {
-        todo!("Convert validation errors to ApiError::Validation");
+    fn from(errors: validator::ValidationErrors) -> Self {
+        let mut messages: Vec<String> = errors
+            .field_errors()
+            .into_iter()
+            .flat_map(|(field, field_errors)| {
+                field_errors.iter().map(move |error| match &error.message {
+                    Some(message) => format!("{}: {}", field, message),
+                    None => format!("{}: {}", field, error.code),
+                })
+            })
+            .collect();
+        messages.sort();
+
+        Self::BadRequest(messages.join("; "))
     }
 }
@@ -0,0 +1,185 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+
+use crate::database::{self, DatabaseConnection, PoolStats};
+use crate::shutdown::Shutdown;
+
+/// Shared state behind the health probes
+#[derive(Clone)]
+pub struct Health {
+    conn: DatabaseConnection,
+    db_timeout: Duration,
+    started_at: Instant,
+    schema_ready: Arc<AtomicBool>,
+    shutdown: Shutdown,
+}
+
+impl Health {
+    /// Create the probe state; readiness fails until [`Health::mark_schema_ready`] is called
+    /// and again as soon as `shutdown` is triggered
+    pub fn new(conn: DatabaseConnection, db_timeout: Duration, shutdown: Shutdown) -> Self {
+        Self {
+            conn,
+            db_timeout,
+            started_at: Instant::now(),
+            schema_ready: Arc::new(AtomicBool::new(false)),
+            shutdown,
+        }
+    }
+
+    /// Record that the database schema is in place
+    pub fn mark_schema_ready(&self) {
+        self.schema_ready.store(true, Ordering::SeqCst);
+    }
+
+    fn build_info(&self) -> BuildInfo {
+        BuildInfo {
+            version: env!("CARGO_PKG_VERSION"),
+            git_sha: env!("GIT_SHA"),
+            uptime_secs: self.started_at.elapsed().as_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Ok,
+    Unavailable,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_sha: &'static str,
+    pub uptime_secs: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComponentHealth {
+    pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool: Option<PoolStats>,
+}
+
+impl ComponentHealth {
+    fn ok() -> Self {
+        Self {
+            status: Status::Ok,
+            message: None,
+            latency_ms: None,
+            pool: None,
+        }
+    }
+
+    fn unavailable(message: impl Into<String>) -> Self {
+        Self {
+            status: Status::Unavailable,
+            message: Some(message.into()),
+            ..Self::ok()
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Checks {
+    pub database: ComponentHealth,
+    pub schema: ComponentHealth,
+    pub server: ComponentHealth,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthResponse {
+    pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checks: Option<Checks>,
+    pub build: BuildInfo,
+}
+
+/// Create health probe routes
+pub fn routes(health: Health) -> Router {
+    Router::new()
+        .route("/health/live", get(live))
+        .route("/health/ready", get(ready))
+        .with_state(health)
+}
+
+/// Liveness probe: the process is up and serving requests
+///
+/// GET /health/live
+async fn live(State(health): State<Health>) -> Json<HealthResponse> {
+    Json(HealthResponse {
+        status: Status::Ok,
+        checks: None,
+        build: health.build_info(),
+    })
+}
+
+/// Readiness probe: the service can handle traffic
+///
+/// GET /health/ready
+async fn ready(State(health): State<Health>) -> Response {
+    let checks = Checks {
+        database: check_database(&health).await,
+        schema: if health.schema_ready.load(Ordering::SeqCst) {
+            ComponentHealth::ok()
+        } else {
+            ComponentHealth::unavailable("Database migrations have not completed")
+        },
+        server: if health.shutdown.is_triggered() {
+            ComponentHealth::unavailable("Server is shutting down")
+        } else {
+            ComponentHealth::ok()
+        },
+    };
+
+    let status = if [&checks.database, &checks.schema, &checks.server]
+        .iter()
+        .all(|check| check.status == Status::Ok)
+    {
+        Status::Ok
+    } else {
+        Status::Unavailable
+    };
+    let code = match status {
+        Status::Ok => StatusCode::OK,
+        Status::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+    };
+
+    let body = HealthResponse {
+        status,
+        checks: Some(checks),
+        build: health.build_info(),
+    };
+
+    (code, Json(body)).into_response()
+}
+
+/// Ping the database within the configured timeout
+async fn check_database(health: &Health) -> ComponentHealth {
+    let started = Instant::now();
+    let mut component = match tokio::time::timeout(health.db_timeout, health.conn.ping()).await {
+        Ok(Ok(())) => ComponentHealth::ok(),
+        Ok(Err(e)) => ComponentHealth::unavailable(format!("Database ping failed: {}", e)),
+        Err(_) => ComponentHealth::unavailable(format!(
+            "Database ping timed out after {}ms",
+            health.db_timeout.as_millis()
+        )),
+    };
+
+    component.latency_ms = Some(started.elapsed().as_millis());
+    component.pool = Some(database::pool_stats(&health.conn));
+    component
+}
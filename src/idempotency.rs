@@ -0,0 +1,191 @@
+use std::time::Duration;
+
+use axum::body::{Body, Bytes, Full, HttpBody, boxed};
+use axum::extract::State;
+use axum::http::{HeaderValue, Method, Request, StatusCode, Uri, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use bytes::BytesMut;
+use sha2::{Digest, Sha256};
+use tokio::time::MissedTickBehavior;
+
+use crate::auth::Editor;
+use crate::error::ApiError;
+use crate::repository::IdempotencyRepository;
+use crate::repository::idempotency::{Claim, StoredResponse};
+use crate::shutdown::Shutdown;
+use crate::tenant::Tenant;
+
+/// Header carrying a client-chosen key that makes retrying a request safe
+pub const IDEMPOTENCY_KEY: &str = "idempotency-key";
+
+/// Header marking a response replayed from an earlier request with the same key
+pub const IDEMPOTENT_REPLAYED: &str = "idempotent-replayed";
+
+/// Longest idempotency key accepted
+const MAX_KEY_LEN: usize = 255;
+
+/// State of the [`idempotent`] middleware
+#[derive(Clone)]
+pub struct Idempotency {
+    keys: IdempotencyRepository,
+    max_body_bytes: usize,
+}
+
+impl Idempotency {
+    /// Record keys in `keys`, buffering request bodies of up to `max_body_bytes`
+    pub fn new(keys: IdempotencyRepository, max_body_bytes: usize) -> Self {
+        Self { keys, max_body_bytes }
+    }
+}
+
+/// Middleware replaying the response to an editor's request that carries an `Idempotency-Key`
+///
+/// The first request with a key runs normally and its response is recorded, unless it failed with
+/// a server error, which leaves the key free for a retry. Later requests with the same key get the
+/// recorded response with an `Idempotent-Replayed` header, `409` while the first is still running,
+/// or `422` if their method, path or body differ. Requests without a key pass through untouched;
+/// anonymous requests with one are rejected with `401`, since keys are scoped to an API key.
+pub async fn idempotent(
+    State(idempotency): State<Idempotency>,
+    tenant: Tenant,
+    editor: Option<Editor>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Result<Response, ApiError> {
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY) else {
+        return Ok(next.run(request).await);
+    };
+    let editor = editor.ok_or_else(|| ApiError::Unauthorized("Idempotency-Key requires an API key".to_string()))?;
+    let key = key
+        .to_str()
+        .ok()
+        .filter(|key| (1..=MAX_KEY_LEN).contains(&key.len()) && key.bytes().all(|b| b.is_ascii_graphic()))
+        .ok_or_else(|| {
+            ApiError::bad_request(format!(
+                "Idempotency-Key must be 1 to {} printable ASCII characters",
+                MAX_KEY_LEN
+            ))
+        })?
+        .to_string();
+    let scope = format!("key:{}", editor.api_key_id);
+    let keys = idempotency.keys.for_tenant(&tenant);
+
+    let (parts, body) = request.into_parts();
+    let body = read_body(body, idempotency.max_body_bytes).await?;
+    let hash = request_hash(&parts.method, &parts.uri, &body);
+
+    match keys.claim(&scope, &key, &hash).await? {
+        Claim::Acquired => {}
+        Claim::InProgress => {
+            return Err(ApiError::Conflict(
+                "A request with this Idempotency-Key is still in progress".to_string(),
+            ));
+        }
+        Claim::Mismatch => {
+            return Err(ApiError::Validation(
+                "Idempotency-Key was already used for a different request".to_string(),
+            ));
+        }
+        Claim::Completed(stored) => return Ok(replay(stored)),
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    if response.status().is_server_error() {
+        release(&keys, &scope, &key).await;
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match hyper::body::to_bytes(body).await {
+        Ok(body) => body,
+        Err(e) => {
+            release(&keys, &scope, &key).await;
+            return Err(ApiError::internal_server_error(format!(
+                "Failed to read response: {}",
+                e
+            )));
+        }
+    };
+    let stored = StoredResponse {
+        status: parts.status.as_u16(),
+        content_type: parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+        body: body.to_vec(),
+    };
+    if let Err(e) = keys.complete(&scope, &key, &stored).await {
+        tracing::warn!("Failed to record response for idempotency key {}: {}", key, e);
+        release(&keys, &scope, &key).await;
+    }
+
+    Ok(Response::from_parts(parts, boxed(Full::from(body))))
+}
+
+/// Hash identifying a request, so a key reused for a different one can be detected
+pub fn request_hash(method: &Method, uri: &Uri, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str());
+    hasher.update(b"\n");
+    hasher.update(uri.path_and_query().map_or(uri.path(), |path| path.as_str()));
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+/// Delete expired idempotency keys every `interval` until shutdown begins
+pub async fn purge(keys: IdempotencyRepository, interval: Duration, shutdown: Shutdown) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = shutdown.wait() => return,
+        }
+
+        match keys.purge_expired().await {
+            Ok(0) => {}
+            Ok(purged) => tracing::info!("Purged {} expired idempotency keys", purged),
+            Err(e) => tracing::warn!("Failed to purge expired idempotency keys: {}", e),
+        }
+    }
+}
+
+/// Buffer a request body so it can be hashed, refusing bodies over `limit` bytes
+async fn read_body(mut body: Body, limit: usize) -> Result<Bytes, ApiError> {
+    let mut bytes = BytesMut::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|e| ApiError::bad_request(format!("Failed to read request body: {}", e)))?;
+        if bytes.len() + chunk.len() > limit {
+            return Err(ApiError::PayloadTooLarge(format!(
+                "Request body exceeds {} bytes",
+                limit
+            )));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(bytes.freeze())
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let status = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let mut response = (status, stored.body).into_response();
+    let headers = response.headers_mut();
+    headers.remove(header::CONTENT_TYPE);
+    if let Some(content_type) = stored.content_type.and_then(|value| HeaderValue::from_str(&value).ok()) {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    headers.insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+
+    response
+}
+
+async fn release(keys: &IdempotencyRepository, scope: &str, key: &str) {
+    if let Err(e) = keys.release(scope, key).await {
+        tracing::warn!("Failed to release idempotency key {}: {}", key, e);
+    }
+}
@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use axum::BoxError;
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{HeaderMap, HeaderName, Method, Request, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use hyper::body::HttpBody;
+use tower_http::compression::CompressionLayer;
+use tower_http::compression::predicate::{And, NotForContentType, Predicate, SizeAbove};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use tower_http::decompression::{DecompressionBody, RequestDecompressionLayer};
+
+use crate::config::{CompressionConfig, CorsConfig};
+use crate::error::ApiError;
+use crate::rate_limit;
+
+/// Build the CORS layer, or `None` when no origins are allowed
+pub fn cors(config: &CorsConfig) -> Option<CorsLayer> {
+    if config.allowed_origins.is_empty() {
+        return None;
+    }
+
+    // Origins, methods and headers are checked by `Config::validate`
+    let origins = if config.allowed_origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::from(Any)
+    } else {
+        AllowOrigin::list(config.allowed_origins.iter().filter_map(|origin| origin.parse().ok()))
+    };
+    let methods: Vec<Method> = config
+        .allowed_methods
+        .iter()
+        .filter_map(|method| method.parse().ok())
+        .collect();
+    let headers: Vec<HeaderName> = config
+        .allowed_headers
+        .iter()
+        .filter_map(|header| header.parse().ok())
+        .collect();
+
+    Some(
+        CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(methods)
+            .allow_headers(headers)
+            .expose_headers(rate_limit::HEADERS.map(HeaderName::from_static))
+            .max_age(Duration::from_secs(config.max_age_secs)),
+    )
+}
+
+/// Which responses are worth compressing: large enough, and not already compressed images
+type CompressionPredicate = And<SizeAbove, NotForContentType>;
+
+/// Build the response compression layer, negotiating the configured algorithms with
+/// `Accept-Encoding`
+///
+/// With no algorithms configured every response is sent as is.
+pub fn compression(config: &CompressionConfig) -> CompressionLayer<CompressionPredicate> {
+    let enabled = |algorithm: &str| config.algorithms.iter().any(|enabled| enabled == algorithm);
+    let predicate = SizeAbove::new(config.min_size_bytes).and(NotForContentType::IMAGES);
+
+    CompressionLayer::new()
+        .gzip(enabled("gzip"))
+        .br(enabled("br"))
+        .zstd(enabled("zstd"))
+        .compress_when(predicate)
+}
+
+/// Build the request decompression layer for the configured algorithms
+///
+/// Bodies in other encodings are rejected with `415`, unless decompression is disabled, in which
+/// case every body is passed through untouched.
+pub fn decompression(config: &CompressionConfig) -> RequestDecompressionLayer {
+    let enabled =
+        |algorithm: &str| config.decompress_requests && config.algorithms.iter().any(|enabled| enabled == algorithm);
+
+    RequestDecompressionLayer::new()
+        .gzip(enabled("gzip"))
+        .br(enabled("br"))
+        .zstd(enabled("zstd"))
+        .pass_through_unaccepted(!config.decompress_requests)
+}
+
+/// Turn a decompressed request body back into the body type our handlers take
+pub fn decompressed_body(body: DecompressionBody<Body>) -> Body {
+    let chunks = futures_util::stream::unfold(Box::pin(body), |mut body| async move {
+        body.data().await.map(|chunk| (chunk, body))
+    });
+
+    Body::wrap_stream(chunks)
+}
+
+/// Answer a request whose decompression failed unexpectedly
+pub async fn decompression_error(error: BoxError) -> ApiError {
+    ApiError::internal_server_error(format!("Failed to decompress request: {}", error))
+}
+
+/// Middleware abandoning requests that take longer than `timeout` with `504 Gateway Timeout`
+pub async fn enforce_timeout<B>(
+    State(timeout): State<Duration>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Result<Response, ApiError> {
+    tokio::time::timeout(timeout, next.run(request))
+        .await
+        .map_err(|_| ApiError::GatewayTimeout(format!("Request did not complete within {}s", timeout.as_secs_f64())))
+}
+
+/// Middleware wrapping plain-text `413` and `415` responses in the [`ApiError`] envelope
+///
+/// Body size limits, content types and content encodings are enforced by extractors and layers
+/// that answer in plain text before our handlers run.
+pub async fn envelope_rejections<B>(request: Request<B>, next: Next<B>) -> Response {
+    let response = next.run(request).await;
+    let status = response.status();
+    if !matches!(
+        status,
+        StatusCode::PAYLOAD_TOO_LARGE | StatusCode::UNSUPPORTED_MEDIA_TYPE
+    ) || is_json(response.headers())
+    {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let message = hyper::body::to_bytes(body)
+        .await
+        .map(|body| String::from_utf8_lossy(&body).trim().to_string())
+        .ok()
+        .filter(|message| !message.is_empty())
+        .unwrap_or_else(|| status.canonical_reason().unwrap_or_default().to_string());
+
+    let mut response = if status == StatusCode::PAYLOAD_TOO_LARGE {
+        ApiError::PayloadTooLarge(message).into_response()
+    } else {
+        ApiError::UnsupportedMediaType(message).into_response()
+    };
+
+    // Keep headers set on the way out, such as rate limits, but describe the new body
+    for (name, value) in &parts.headers {
+        if name != header::CONTENT_TYPE && name != header::CONTENT_LENGTH {
+            response.headers_mut().append(name, value.clone());
+        }
+    }
+
+    response
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("application/json"))
+}
@@ -0,0 +1,117 @@
+#![allow(unused)]
+
+pub mod api;
+pub mod auth;
+pub mod cache;
+pub mod cli;
+pub mod config;
+pub mod database;
+pub mod derivative;
+pub mod entity;
+pub mod error;
+pub mod health;
+pub mod idempotency;
+pub mod layers;
+pub mod locale;
+pub mod metrics;
+pub mod migration;
+pub mod models;
+pub mod rate_limit;
+pub mod repository;
+pub mod request_context;
+pub mod scheduler;
+pub mod shutdown;
+pub mod storage;
+pub mod telemetry;
+pub mod tenant;
+pub mod validation;
+
+#[cfg(test)]
+mod tests;
+
+use std::net::{SocketAddr, TcpListener};
+use std::time::Duration;
+
+use axum::error_handling::HandleErrorLayer;
+use axum::extract::DefaultBodyLimit;
+use axum::{Router, middleware};
+use cache::CatalogCache;
+use config::Config;
+use database::DatabaseConnection;
+use health::Health;
+use rate_limit::RateLimiter;
+use shutdown::Shutdown;
+use storage::MediaStorage;
+use tower::ServiceBuilder;
+use tower_http::map_request_body::MapRequestBodyLayer;
+
+/// Build the application router
+pub fn app(
+    config: &Config,
+    db: DatabaseConnection,
+    cache: CatalogCache,
+    storage: MediaStorage,
+    rate_limiter: RateLimiter,
+    health: Health,
+) -> Router {
+    let mut router = Router::new()
+        .nest("/api", api::routes(db.clone(), cache, storage, rate_limiter, config))
+        .merge(health::routes(health));
+
+    if config.features.metrics {
+        router = router.merge(metrics::routes(db));
+    }
+
+    // The body limit applies to decompressed bodies; the last layer added runs first
+    let router = router
+        .route_layer(middleware::from_fn(metrics::track_requests))
+        .layer(DefaultBodyLimit::max(config.limits.max_body_bytes))
+        .layer(middleware::from_fn_with_state(
+            Duration::from_secs(config.limits.request_timeout_secs),
+            layers::enforce_timeout,
+        ))
+        .layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(layers::decompression_error))
+                .layer(layers::decompression(&config.compression))
+                .layer(MapRequestBodyLayer::new(layers::decompressed_body)),
+        )
+        .layer(middleware::from_fn(layers::envelope_rejections))
+        .layer(layers::compression(&config.compression))
+        .layer(middleware::from_fn(request_context::propagate_request_context));
+
+    match layers::cors(&config.cors) {
+        Some(cors) => router.layer(cors),
+        None => router,
+    }
+}
+
+/// Serve `app` until `shutdown` is triggered, then drain in-flight requests for up to `drain_timeout`
+///
+/// Connections still open once the drain timeout elapses are abandoned and end with the process.
+pub async fn serve(
+    listener: TcpListener,
+    app: Router,
+    shutdown: Shutdown,
+    drain_timeout: Duration,
+) -> anyhow::Result<()> {
+    let server = axum::Server::from_tcp(listener)?
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown({
+            let shutdown = shutdown.clone();
+            async move { shutdown.wait().await }
+        });
+
+    let drain_deadline = async {
+        shutdown.wait().await;
+        tracing::info!("Draining in-flight requests for up to {}s", drain_timeout.as_secs());
+        tokio::time::sleep(drain_timeout).await;
+    };
+
+    tokio::select! {
+        result = server => result?,
+        _ = drain_deadline => tracing::warn!("Drain timeout elapsed, dropping remaining connections"),
+    }
+
+    Ok(())
+}
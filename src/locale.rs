@@ -0,0 +1,274 @@
+use std::fmt;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use axum::extract::{FromRequestParts, Query, State};
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, HeaderValue, Request, header};
+use axum::middleware::Next;
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+
+use crate::config::LocalizationConfig;
+use crate::error::ApiError;
+
+/// Locale of requests that don't ask for one, and of untranslated product and category text
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// A language tag such as `de` or `de-ch`, stored lowercase
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Locale(String);
+
+impl Locale {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The primary language subtag, e.g. `de` for `de-ch`
+    pub fn language(&self) -> &str {
+        self.0.split('-').next().unwrap_or_default()
+    }
+
+    /// Postgres text search configuration used to index and search text in this locale
+    ///
+    /// Languages without a stemming configuration of their own use `simple`, which only
+    /// lowercases words.
+    pub fn text_search_config(&self) -> &'static str {
+        match self.language() {
+            "ar" => "arabic",
+            "da" => "danish",
+            "de" => "german",
+            "el" => "greek",
+            "en" => "english",
+            "es" => "spanish",
+            "fi" => "finnish",
+            "fr" => "french",
+            "hu" => "hungarian",
+            "id" => "indonesian",
+            "it" => "italian",
+            "nb" | "nn" | "no" => "norwegian",
+            "nl" => "dutch",
+            "pt" => "portuguese",
+            "ro" => "romanian",
+            "ru" => "russian",
+            "sv" => "swedish",
+            "tr" => "turkish",
+            _ => "simple",
+        }
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self(DEFAULT_LOCALE.to_string())
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.0)
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    /// Parse a language tag, accepting `_` as a separator and any case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tag = s.trim().to_ascii_lowercase().replace('_', "-");
+        let mut subtags = tag.split('-');
+        let valid = subtags.next().is_some_and(|language| {
+            (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_lowercase())
+        }) && subtags
+            .all(|subtag| (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric()));
+        if valid {
+            Ok(Self(tag))
+        } else {
+            Err(format!(
+                "invalid locale {:?}; expected a language tag such as en or de-ch",
+                s
+            ))
+        }
+    }
+}
+
+impl TryFrom<String> for Locale {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Locale> for String {
+    fn from(locale: Locale) -> Self {
+        locale.0
+    }
+}
+
+/// The locales a request's content is looked up in, most preferred first
+///
+/// The chain always ends with the default locale, whose text is stored on the products and
+/// categories themselves; the locales before it are looked up in their translations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocaleChain {
+    locales: Vec<Locale>,
+}
+
+impl LocaleChain {
+    /// The chain for `locale`: the locale, its language, then the default locale
+    ///
+    /// `locale` must be supported; its language is only included when it is too.
+    pub fn new(locale: Locale, config: &LocalizationConfig) -> Self {
+        let mut locales = vec![locale];
+        let language = locales[0].language().to_string();
+        for fallback in [
+            config
+                .supported_locales
+                .iter()
+                .find(|supported| supported.as_str() == language),
+            Some(&config.default_locale),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if !locales.contains(fallback) {
+                locales.push(fallback.clone());
+            }
+        }
+
+        Self { locales }
+    }
+
+    /// The most preferred locale, reported in `Content-Language`
+    pub fn locale(&self) -> &Locale {
+        &self.locales[0]
+    }
+
+    /// The default locale, whose text needs no translation
+    pub fn default_locale(&self) -> &Locale {
+        &self.locales[self.locales.len() - 1]
+    }
+
+    /// The locales looked up in translations, most preferred first
+    pub fn translated(&self) -> &[Locale] {
+        &self.locales[..self.locales.len() - 1]
+    }
+}
+
+impl Default for LocaleChain {
+    fn default() -> Self {
+        Self {
+            locales: vec![Locale::default()],
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for LocaleChain {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<LocaleChain>()
+            .cloned()
+            .ok_or_else(|| ApiError::internal_server_error("Locale was not resolved for this request"))
+    }
+}
+
+#[derive(Deserialize)]
+struct LocaleParams {
+    locale: Option<String>,
+}
+
+/// Middleware that resolves the [`LocaleChain`] of a request
+///
+/// A `locale` query parameter must name a supported locale. Otherwise the most preferred supported
+/// locale in `Accept-Language` is used, falling back to the default locale. Responses carry the
+/// chosen locale in `Content-Language`.
+pub async fn resolve_locale<B>(
+    State(config): State<LocalizationConfig>,
+    mut request: Request<B>,
+    next: Next<B>,
+) -> Result<Response, ApiError> {
+    let params = Query::<LocaleParams>::try_from_uri(request.uri()).map(|Query(params)| params);
+    let locale = match params.ok().and_then(|params| params.locale) {
+        Some(requested) => {
+            let requested: Locale = requested
+                .parse()
+                .map_err(|e| ApiError::bad_request(format!("locale: {}", e)))?;
+            negotiate(&config, &requested).ok_or_else(|| {
+                ApiError::bad_request(format!(
+                    "locale: {} is not supported; expected one of {}",
+                    requested,
+                    supported_list(&config)
+                ))
+            })?
+        }
+        None => accepted_locales(request.headers())
+            .iter()
+            .find_map(|accepted| negotiate(&config, accepted))
+            .unwrap_or_else(|| config.default_locale.clone()),
+    };
+
+    let chain = LocaleChain::new(locale, &config);
+    let content_language = HeaderValue::from_str(chain.locale().as_str()).ok();
+    request.extensions_mut().insert(chain);
+
+    let mut response = next.run(request).await;
+    if let Some(content_language) = content_language {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_LANGUAGE, content_language);
+    }
+    response
+        .headers_mut()
+        .append(header::VARY, HeaderValue::from_static("accept-language"));
+
+    Ok(response)
+}
+
+/// The supported locale serving a request for `requested`: the same locale, or its language
+fn negotiate(config: &LocalizationConfig, requested: &Locale) -> Option<Locale> {
+    let supported = &config.supported_locales;
+    supported
+        .iter()
+        .find(|locale| *locale == requested)
+        .or_else(|| supported.iter().find(|locale| locale.as_str() == requested.language()))
+        .cloned()
+}
+
+/// The locales listed in `Accept-Language`, most preferred first
+///
+/// Invalid entries, wildcards and entries with a quality of zero are skipped.
+fn accepted_locales(headers: &HeaderMap) -> Vec<Locale> {
+    let mut accepted: Vec<(f32, Locale)> = headers
+        .get_all(header::ACCEPT_LANGUAGE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let locale = parts.next()?.parse().ok()?;
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+            (quality > 0.0).then_some((quality, locale))
+        })
+        .collect();
+    // Stable, so entries of equal quality keep their order
+    accepted.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    accepted.into_iter().map(|(_, locale)| locale).collect()
+}
+
+fn supported_list(config: &LocalizationConfig) -> String {
+    config
+        .supported_locales
+        .iter()
+        .map(Locale::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
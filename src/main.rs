@@ -1,63 +1,132 @@
 #![allow(unused)]
 
-mod api;
-mod config;
-mod database;
-mod entity;
-mod error;
-mod models;
-mod repository;
-mod validation;
-
-#[cfg(test)]
-mod tests;
-
-use std::net::SocketAddr;
-
-use axum::Router;
-use axum::routing::get;
-use config::Config;
+use std::net::TcpListener;
+use std::time::Duration;
+
+use clap::Parser;
 use dotenvy::dotenv;
-use tracing_subscriber::layer::SubscriberExt;
-use tracing_subscriber::util::SubscriberInitExt;
+use product_catalog_api::cli::{self, Cli};
+use product_catalog_api::config::Config;
+use product_catalog_api::health::Health;
+use product_catalog_api::migration::Migrator;
+use product_catalog_api::shutdown::{self, Shutdown};
+use product_catalog_api::{
+    app, cache, database, idempotency, metrics, rate_limit, repository, scheduler, serve, storage, telemetry,
+};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Load environment variables
     dotenv().ok();
 
+    // Load configuration: defaults, then the config file, then env, then CLI flags
+    let cli = Cli::parse();
+    let config = Config::load(&cli.config)?;
+
+    if cli.print_config {
+        print!("{}", toml::to_string_pretty(&config.redacted())?);
+        return Ok(());
+    }
+
     // Initialize tracing
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::new(
-            std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
-        ))
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    let telemetry = telemetry::init(&config.telemetry)?;
 
-    // Load configuration
-    let config = Config::from_env()?;
+    // Run a maintenance command instead of serving
+    if let Some(command) = cli.command {
+        let result = cli::run(command, &config, &cli.tenant, cli.output).await;
+        telemetry.shutdown();
+        return result;
+    }
 
     // Set up database connection
-    let db = database::connect(&config.database_url).await?;
+    let db = database::connect(&config.database).await?;
+
+    // Set up response cache
+    let cache = cache::connect(&config.cache).await?;
+    tracing::info!("Using {} response cache", config.cache.backend);
+
+    // Set up media file storage
+    let storage = storage::connect(&config.storage).await?;
+    tracing::info!("Storing media in {} storage", storage.backend());
+
+    // Set up per-client rate limits
+    let rate_limiter = rate_limit::connect(&config.rate_limit).await?;
+    if config.rate_limit.enabled {
+        tracing::info!("Rate limiting clients with the {} backend", config.rate_limit.backend);
+    }
+
+    // Coordinate graceful shutdown of the server and background workers
+    let shutdown = Shutdown::new();
+
+    // Readiness fails until migrations have run, and again once shutdown begins
+    let health = Health::new(
+        db.clone(),
+        Duration::from_millis(config.health.db_timeout_ms),
+        shutdown.clone(),
+    );
 
-    // Run database migrations
-    tracing::info!("Running database migrations");
-    db.get_schema_registry("rust_app_test::entity::*").sync(&db).await?;
-    tracing::info!("Database migrations completed successfully");
+    // Bring the schema up to date, or refuse to serve while it is behind
+    let migrator = Migrator::new(db.clone());
+    if config.database.migration_mode == "apply" {
+        tracing::info!("Running database migrations");
+        let applied = migrator.up().await?;
+        tracing::info!("Applied {} database migration(s)", applied.len());
+    } else {
+        migrator.verify().await?;
+    }
+    health.mark_schema_ready();
+
+    // Publish and archive products as their schedules come due
+    shutdown.spawn_worker("product scheduler", {
+        let products = repository::ProductRepository::new(db.clone(), cache.clone(), storage.clone(), &config.facets);
+        let interval = Duration::from_secs(config.lifecycle.schedule_interval_secs);
+        move |shutdown| scheduler::run(products, interval, shutdown)
+    });
+
+    // Forget idempotency keys once their responses are no longer replayed
+    shutdown.spawn_worker("idempotency key purge", {
+        let keys = repository::IdempotencyRepository::new(
+            db.clone(),
+            &config.idempotency,
+            Duration::from_secs(config.limits.request_timeout_secs),
+        );
+        let interval = Duration::from_secs(config.idempotency.purge_interval_secs);
+        move |shutdown| idempotency::purge(keys, interval, shutdown)
+    });
+
+    // Keep the catalog size gauges current without counting on every scrape
+    if config.features.metrics {
+        shutdown.spawn_worker("catalog metrics sampler", {
+            let conn = db.clone();
+            let interval = Duration::from_secs(config.metrics.sample_interval_secs);
+            move |shutdown| metrics::sample_catalog_size(conn, interval, shutdown)
+        });
+    }
 
     // Build our application with routes
-    let app = Router::new()
-        .nest("/api", api::routes(db))
-        .route("/health", get(health_check));
+    let app = app(&config, db.clone(), cache, storage, rate_limiter, health);
+
+    // Begin shutting down on SIGINT or SIGTERM
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            shutdown::signal().await;
+            shutdown.trigger();
+        }
+    });
 
     // Run our application
-    let addr = SocketAddr::from(([0, 0, 0, 0], config.server_port));
-    tracing::info!("Listening on {}", addr);
-    axum::Server::bind(&addr).serve(app.into_make_service()).await?;
+    let listener = TcpListener::bind((config.server.host.as_str(), config.server.port))?;
+    tracing::info!("Listening on {}", listener.local_addr()?);
+    let drain_timeout = Duration::from_secs(config.server.shutdown_timeout_secs);
+    serve(listener, app, shutdown.clone(), drain_timeout).await?;
 
-    Ok(())
-}
+    // Stop background workers, then release database connections
+    shutdown.join_workers(drain_timeout).await;
+    db.close().await?;
+    tracing::info!("Shutdown complete");
 
-async fn health_check() -> &'static str {
-    "OK"
+    telemetry.shutdown();
+
+    Ok(())
 }
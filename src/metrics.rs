@@ -0,0 +1,187 @@
+use std::future::Future;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use axum::Router;
+use axum::extract::{MatchedPath, State};
+use axum::http::{Request, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use prometheus::{Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder};
+use sea_orm::{DbErr, EntityTrait, PaginatorTrait};
+use tokio::time::MissedTickBehavior;
+
+use crate::database::{self, DatabaseConnection};
+use crate::entity::{Category, Product};
+use crate::error::ApiError;
+use crate::shutdown::Shutdown;
+
+/// Process-wide Prometheus metrics
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+pub struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_request_duration: HistogramVec,
+    api_errors: IntCounterVec,
+    db_transaction_duration: HistogramVec,
+    db_pool_connections: IntGaugeVec,
+    catalog_items: IntGaugeVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "Total HTTP requests by route and status"),
+            &["method", "route", "status"],
+        )
+        .expect("valid metric");
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency by route"),
+            &["method", "route"],
+        )
+        .expect("valid metric");
+        let api_errors = IntCounterVec::new(
+            Opts::new("api_errors_total", "API errors returned, by error kind"),
+            &["kind"],
+        )
+        .expect("valid metric");
+        let db_transaction_duration = HistogramVec::new(
+            HistogramOpts::new("db_transaction_duration_seconds", "Repository transaction duration"),
+            &["operation"],
+        )
+        .expect("valid metric");
+        let db_pool_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Database pool connections by state"),
+            &["state"],
+        )
+        .expect("valid metric");
+        let catalog_items = IntGaugeVec::new(
+            Opts::new("catalog_items", "Number of items in the catalog by kind"),
+            &["kind"],
+        )
+        .expect("valid metric");
+
+        registry.register(Box::new(http_requests.clone())).expect("unique metric");
+        registry
+            .register(Box::new(http_request_duration.clone()))
+            .expect("unique metric");
+        registry.register(Box::new(api_errors.clone())).expect("unique metric");
+        registry
+            .register(Box::new(db_transaction_duration.clone()))
+            .expect("unique metric");
+        registry
+            .register(Box::new(db_pool_connections.clone()))
+            .expect("unique metric");
+        registry.register(Box::new(catalog_items.clone())).expect("unique metric");
+
+        Self {
+            registry,
+            http_requests,
+            http_request_duration,
+            api_errors,
+            db_transaction_duration,
+            db_pool_connections,
+            catalog_items,
+        }
+    }
+}
+
+/// Count an error returned to a client
+pub fn record_api_error(kind: &str) {
+    METRICS.api_errors.with_label_values(&[kind]).inc();
+}
+
+/// Run a repository transaction, recording its duration under `operation`
+///
+/// Only the transaction is timed, not the cache invalidation or file storage around it.
+pub async fn time_transaction<F: Future>(operation: &str, transaction: F) -> F::Output {
+    let _timer = METRICS
+        .db_transaction_duration
+        .with_label_values(&[operation])
+        .start_timer();
+    transaction.await
+}
+
+/// Count the products and categories of every tenant into the `catalog_items` gauges
+pub async fn record_catalog_size(conn: &DatabaseConnection) -> Result<(), DbErr> {
+    let products = Product::find().count(conn).await?;
+    let categories = Category::find().count(conn).await?;
+    METRICS.catalog_items.with_label_values(&["products"]).set(products as i64);
+    METRICS.catalog_items.with_label_values(&["categories"]).set(categories as i64);
+    Ok(())
+}
+
+/// Refresh the catalog size gauges every `interval` until shutdown begins
+///
+/// Counting happens here rather than on each scrape, so scrapes never query the catalog. A failed
+/// count is logged and leaves the previous values in place.
+pub async fn sample_catalog_size(conn: DatabaseConnection, interval: Duration, shutdown: Shutdown) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = shutdown.wait() => return,
+        }
+
+        if let Err(e) = record_catalog_size(&conn).await {
+            tracing::warn!("Failed to count catalog items for metrics: {}", e);
+        }
+    }
+}
+
+/// Middleware recording request counts and latency per matched route
+pub async fn track_requests<B>(request: Request<B>, next: Next<B>) -> Response {
+    let started = Instant::now();
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let response = next.run(request).await;
+
+    let status = response.status().as_u16().to_string();
+    METRICS
+        .http_requests
+        .with_label_values(&[&method, &route, &status])
+        .inc();
+    METRICS
+        .http_request_duration
+        .with_label_values(&[&method, &route])
+        .observe(started.elapsed().as_secs_f64());
+
+    response
+}
+
+/// Create metrics routes
+pub fn routes(conn: DatabaseConnection) -> Router {
+    Router::new().route("/metrics", get(metrics)).with_state(conn)
+}
+
+/// Export metrics in the Prometheus text format
+///
+/// GET /metrics
+async fn metrics(State(conn): State<DatabaseConnection>) -> Result<Response, ApiError> {
+    // Pool gauges are sampled at scrape time; catalog gauges by `sample_catalog_size`
+    let pool = database::pool_stats(&conn);
+    let gauges = &METRICS.db_pool_connections;
+    gauges.with_label_values(&["idle"]).set(pool.idle as i64);
+    gauges
+        .with_label_values(&["active"])
+        .set(pool.size as i64 - pool.idle as i64);
+    gauges.with_label_values(&["max"]).set(pool.max as i64);
+
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&METRICS.registry.gather(), &mut buffer)
+        .map_err(|e| ApiError::internal_server_error(format!("Failed to encode metrics: {}", e)))?;
+
+    Ok(([(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)], buffer).into_response())
+}
@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{Result, anyhow, bail};
+use chrono::{DateTime, Utc};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbBackend, Statement, TransactionTrait};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// Migrations embedded from `migrations/` by the build script
+static MIGRATIONS: &[Migration] = include!(concat!(env!("OUT_DIR"), "/migrations.rs"));
+
+/// SQLite ports of the migrations, embedded from `migrations/sqlite/`
+static SQLITE_MIGRATIONS: &[Migration] = include!(concat!(env!("OUT_DIR"), "/sqlite_migrations.rs"));
+
+/// Key of the Postgres advisory lock serializing concurrent migration runs
+const LOCK_KEY: i64 = 0x0063_6174_616c_6f67;
+
+const CREATE_MIGRATIONS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS schema_migrations (
+    version BIGINT PRIMARY KEY,
+    name VARCHAR NOT NULL,
+    checksum VARCHAR NOT NULL,
+    applied_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    execution_ms BIGINT NOT NULL
+)
+"#;
+
+/// A versioned schema change with its up script and optional down script
+#[derive(Clone, Debug)]
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: Option<&'static str>,
+}
+
+impl Migration {
+    /// SHA-256 of the up script, used to detect edits to applied migrations
+    pub fn checksum(&self) -> String {
+        hex::encode(Sha256::digest(self.up.as_bytes()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    /// Applied, but the script has changed since
+    Modified,
+    /// Applied, but no longer known to this build
+    Missing,
+}
+
+impl fmt::Display for MigrationState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            MigrationState::Applied => "applied",
+            MigrationState::Pending => "pending",
+            MigrationState::Modified => "modified",
+            MigrationState::Missing => "missing",
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub name: String,
+    pub state: MigrationState,
+    pub applied_at: Option<DateTime<Utc>>,
+}
+
+/// A row of the `schema_migrations` table
+struct AppliedMigration {
+    name: String,
+    checksum: String,
+    applied_at: DateTime<Utc>,
+}
+
+/// Applies and reverts migrations, recording them in `schema_migrations`
+///
+/// Every migration runs in its own transaction holding a transaction-scoped advisory lock, so
+/// concurrent runs (e.g. several replicas starting at once) apply each migration exactly once. On
+/// SQLite the transaction takes the database's write lock instead.
+pub struct Migrator {
+    conn: DatabaseConnection,
+    migrations: Vec<Migration>,
+}
+
+impl Migrator {
+    /// Create a migrator for the migrations embedded in this build for the connection's backend
+    pub fn new(conn: DatabaseConnection) -> Self {
+        let migrations = match conn.get_database_backend() {
+            DbBackend::Sqlite => SQLITE_MIGRATIONS,
+            _ => MIGRATIONS,
+        };
+        Self::with_migrations(conn, migrations.to_vec())
+    }
+
+    /// Create a migrator for an explicit set of migrations
+    pub fn with_migrations(conn: DatabaseConnection, mut migrations: Vec<Migration>) -> Self {
+        migrations.sort_by_key(|migration| migration.version);
+        Self { conn, migrations }
+    }
+
+    /// State of every known or applied migration, ordered by version
+    pub async fn status(&self) -> Result<Vec<MigrationStatus>> {
+        let txn = self.begin_locked().await?;
+        let applied = applied_migrations(&txn).await?;
+        txn.commit().await?;
+
+        let mut statuses: Vec<MigrationStatus> = self
+            .migrations
+            .iter()
+            .map(|migration| {
+                let (state, applied_at) = match applied.get(&migration.version) {
+                    Some(row) if row.checksum == migration.checksum() => {
+                        (MigrationState::Applied, Some(row.applied_at))
+                    }
+                    Some(row) => (MigrationState::Modified, Some(row.applied_at)),
+                    None => (MigrationState::Pending, None),
+                };
+                MigrationStatus {
+                    version: migration.version,
+                    name: migration.name.to_string(),
+                    state,
+                    applied_at,
+                }
+            })
+            .collect();
+
+        for (version, row) in applied {
+            if !self.migrations.iter().any(|migration| migration.version == version) {
+                statuses.push(MigrationStatus {
+                    version,
+                    name: row.name,
+                    state: MigrationState::Missing,
+                    applied_at: Some(row.applied_at),
+                });
+            }
+        }
+        statuses.sort_by_key(|status| status.version);
+
+        Ok(statuses)
+    }
+
+    /// Fail unless every migration is applied and unchanged
+    pub async fn verify(&self) -> Result<()> {
+        let problems: Vec<String> = self
+            .status()
+            .await?
+            .into_iter()
+            .filter(|status| status.state != MigrationState::Applied)
+            .map(|status| format!("{}_{} is {}", status.version, status.name, status.state))
+            .collect();
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("Database schema is not up to date: {}", problems.join(", ")))
+        }
+    }
+
+    /// Apply every pending migration in version order, returning the versions applied
+    pub async fn up(&self) -> Result<Vec<i64>> {
+        self.check_modified().await?;
+
+        let mut applied_versions = Vec::new();
+        for migration in &self.migrations {
+            let txn = self.begin_locked().await?;
+
+            // Another runner may have applied it while we waited for the lock
+            if applied_migrations(&txn).await?.contains_key(&migration.version) {
+                txn.rollback().await?;
+                continue;
+            }
+
+            let started = Instant::now();
+            txn.execute_unprepared(migration.up)
+                .await
+                .map_err(|e| anyhow!("Migration {}_{} failed: {}", migration.version, migration.name, e))?;
+            txn.execute_raw(Statement::from_sql_and_values(
+                txn.get_database_backend(),
+                "INSERT INTO schema_migrations (version, name, checksum, execution_ms) VALUES ($1, $2, $3, $4)",
+                [
+                    migration.version.into(),
+                    migration.name.into(),
+                    migration.checksum().into(),
+                    (started.elapsed().as_millis() as i64).into(),
+                ],
+            ))
+            .await?;
+            txn.commit().await?;
+
+            tracing::info!("Applied migration {}_{}", migration.version, migration.name);
+            applied_versions.push(migration.version);
+        }
+
+        Ok(applied_versions)
+    }
+
+    /// Revert the `steps` most recently applied migrations, returning the versions reverted
+    pub async fn down(&self, steps: usize) -> Result<Vec<i64>> {
+        let mut reverted = Vec::new();
+
+        for _ in 0..steps {
+            let txn = self.begin_locked().await?;
+            let Some(version) = applied_migrations(&txn).await?.into_keys().max() else {
+                txn.rollback().await?;
+                break;
+            };
+
+            let Some(migration) = self.migrations.iter().find(|migration| migration.version == version) else {
+                bail!("Migration {} is applied but unknown to this build", version);
+            };
+            let Some(down) = migration.down else {
+                bail!("Migration {}_{} has no down script", migration.version, migration.name);
+            };
+
+            txn.execute_unprepared(down)
+                .await
+                .map_err(|e| anyhow!("Reverting {}_{} failed: {}", migration.version, migration.name, e))?;
+            txn.execute_raw(Statement::from_sql_and_values(
+                txn.get_database_backend(),
+                "DELETE FROM schema_migrations WHERE version = $1",
+                [version.into()],
+            ))
+            .await?;
+            txn.commit().await?;
+
+            tracing::info!("Reverted migration {}_{}", migration.version, migration.name);
+            reverted.push(version);
+        }
+
+        Ok(reverted)
+    }
+
+    /// Refuse to run when an applied migration's script has been edited
+    async fn check_modified(&self) -> Result<()> {
+        let modified: Vec<String> = self
+            .status()
+            .await?
+            .into_iter()
+            .filter(|status| status.state == MigrationState::Modified)
+            .map(|status| format!("{}_{}", status.version, status.name))
+            .collect();
+
+        if modified.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Applied migrations have been modified: {}",
+                modified.join(", ")
+            ))
+        }
+    }
+
+    /// Begin a transaction holding the migration lock, creating the migrations table if needed
+    async fn begin_locked(&self) -> Result<DatabaseTransaction> {
+        match self.conn.get_database_backend() {
+            DbBackend::Sqlite => {
+                // A write, even one changing nothing, makes the transaction the only writer. It has
+                // to come first: a transaction that has already read can't wait for another writer
+                self.conn.execute_unprepared(CREATE_MIGRATIONS_TABLE).await?;
+                let txn = self.conn.begin().await?;
+                txn.execute_unprepared("DELETE FROM schema_migrations WHERE false")
+                    .await?;
+                Ok(txn)
+            }
+            backend => {
+                let txn = self.conn.begin().await?;
+                txn.execute_raw(Statement::from_sql_and_values(
+                    backend,
+                    "SELECT pg_advisory_xact_lock($1)",
+                    [LOCK_KEY.into()],
+                ))
+                .await?;
+                txn.execute_unprepared(CREATE_MIGRATIONS_TABLE).await?;
+                Ok(txn)
+            }
+        }
+    }
+}
+
+/// Read the `schema_migrations` table, keyed by version
+async fn applied_migrations(txn: &DatabaseTransaction) -> Result<HashMap<i64, AppliedMigration>> {
+    let rows = txn
+        .query_all_raw(Statement::from_string(
+            txn.get_database_backend(),
+            "SELECT version, name, checksum, applied_at FROM schema_migrations",
+        ))
+        .await?;
+
+    rows.into_iter()
+        .map(|row| {
+            Ok((
+                row.try_get("", "version")?,
+                AppliedMigration {
+                    name: row.try_get("", "name")?,
+                    checksum: row.try_get("", "checksum")?,
+                    applied_at: row.try_get("", "applied_at")?,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Create empty up and down scripts for a new migration in `dir`
+pub fn create_migration_files(dir: &Path, name: &str) -> Result<(PathBuf, PathBuf)> {
+    let slug: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if slug.trim_matches('_').is_empty() {
+        bail!("Migration name must contain letters or digits");
+    }
+
+    let stem = format!("{}_{}", Utc::now().format("%Y%m%d%H%M%S"), slug.trim_matches('_'));
+    let up = dir.join(format!("{}.up.sql", stem));
+    let down = dir.join(format!("{}.down.sql", stem));
+
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(&up, format!("-- {}\n", name.trim()))?;
+    std::fs::write(&down, format!("-- Revert {}\n", name.trim()))?;
+
+    Ok((up, down))
+}
@@ -0,0 +1,36 @@
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::tenant::Tenant;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateApiKeyRequest {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "API key name cannot be empty and must be less than 101 characters"
+    ))]
+    pub name: String,
+    /// Tenant the key grants access to
+    #[serde(default)]
+    pub tenant: Tenant,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiKeyResponse {
+    pub id: i32,
+    pub name: String,
+    pub tenant: Tenant,
+    pub prefix: String,
+    pub created_at: DateTimeWithTimeZone,
+    pub revoked_at: Option<DateTimeWithTimeZone>,
+}
+
+/// A newly created key; the secret is only ever returned here
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatedApiKeyResponse {
+    #[serde(flatten)]
+    pub api_key: ApiKeyResponse,
+    pub secret: String,
+}
@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::validation::validate_attribute_name;
+
+/// Attribute values of a product, keyed by attribute name
+pub type AttributeValues = BTreeMap<String, serde_json::Value>;
+
+/// Kind of value an attribute holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeType {
+    /// Free text
+    String,
+    /// Any JSON number
+    Number,
+    /// `true` or `false`
+    Bool,
+    /// One of the definition's `values`
+    Enum,
+    /// A number measured in the definition's `unit`
+    Unit,
+}
+
+impl AttributeType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttributeType::String => "string",
+            AttributeType::Number => "number",
+            AttributeType::Bool => "bool",
+            AttributeType::Enum => "enum",
+            AttributeType::Unit => "unit",
+        }
+    }
+}
+
+impl fmt::Display for AttributeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl FromStr for AttributeType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "string" => Ok(AttributeType::String),
+            "number" => Ok(AttributeType::Number),
+            "bool" => Ok(AttributeType::Bool),
+            "enum" => Ok(AttributeType::Enum),
+            "unit" => Ok(AttributeType::Unit),
+            other => Err(format!("unknown attribute type {:?}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct CreateAttributeRequest {
+    #[validate(custom(function = "validate_attribute_name"))]
+    pub name: String,
+    #[serde(rename = "type")]
+    pub value_type: AttributeType,
+    #[serde(default)]
+    pub required: bool,
+    /// Allowed values; required for `enum` attributes and rejected for others
+    pub values: Option<Vec<String>>,
+    /// Unit of measure; required for `unit` attributes and rejected for others
+    #[validate(length(min = 1, max = 20, message = "Unit must be between 1 and 20 characters"))]
+    pub unit: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateAttributeRequest {
+    pub required: Option<bool>,
+    pub values: Option<Vec<String>>,
+    #[validate(length(min = 1, max = 20, message = "Unit must be between 1 and 20 characters"))]
+    pub unit: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttributeResponse {
+    pub id: i32,
+    pub category_id: i32,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub value_type: AttributeType,
+    pub required: bool,
+    pub values: Option<Vec<String>>,
+    pub unit: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ApiError;
+use crate::models::money::Currency;
+
+/// A count requested through the `facets` query parameter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Facet {
+    /// Matching products per category
+    Category,
+    /// Matching products per currency and configured price bucket
+    PriceRange,
+    /// Matching products per value of the named attribute
+    Attribute(String),
+}
+
+impl Facet {
+    /// Parse a comma-separated list such as `category,price_range,attr.color`
+    pub fn parse_list(list: &str) -> Result<Vec<Facet>, ApiError> {
+        let mut facets = Vec::new();
+        for name in list.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            let facet = match name {
+                "category" => Facet::Category,
+                "price_range" => Facet::PriceRange,
+                _ => match name.strip_prefix("attr.") {
+                    Some(attribute) if !attribute.is_empty() => Facet::Attribute(attribute.to_string()),
+                    _ => {
+                        return Err(ApiError::bad_request(format!(
+                            "facets: unknown facet {:?}; expected category, price_range or attr.<name>",
+                            name
+                        )));
+                    }
+                },
+            };
+            if !facets.contains(&facet) {
+                facets.push(facet);
+            }
+        }
+        Ok(facets)
+    }
+}
+
+/// Counts over every product matching the list filters, not just the returned page
+///
+/// Only the requested facets are present.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProductFacets {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<Vec<CategoryFacet>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_range: Option<Vec<PriceRangeFacet>>,
+    /// Value counts keyed by attribute name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, Vec<AttributeFacet>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CategoryFacet {
+    pub id: i32,
+    pub name: String,
+    pub count: i64,
+}
+
+/// Products priced in `currency` from `min` (inclusive) up to `max` (exclusive); the first bucket
+/// has no minimum and the last no maximum
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PriceRangeFacet {
+    pub currency: Currency,
+    pub min: Option<BigDecimal>,
+    pub max: Option<BigDecimal>,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttributeFacet {
+    pub value: serde_json::Value,
+    pub count: i64,
+}
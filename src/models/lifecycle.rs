@@ -0,0 +1,116 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+/// Where a product is in the publishing workflow
+///
+/// Only published products are visible to anonymous clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProductStatus {
+    Draft,
+    InReview,
+    Published,
+    Archived,
+}
+
+impl ProductStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProductStatus::Draft => "draft",
+            ProductStatus::InReview => "in_review",
+            ProductStatus::Published => "published",
+            ProductStatus::Archived => "archived",
+        }
+    }
+}
+
+impl fmt::Display for ProductStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl FromStr for ProductStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "draft" => Ok(ProductStatus::Draft),
+            "in_review" => Ok(ProductStatus::InReview),
+            "published" => Ok(ProductStatus::Published),
+            "archived" => Ok(ProductStatus::Archived),
+            other => Err(format!("unknown product status {:?}", other)),
+        }
+    }
+}
+
+/// A step of the publishing workflow, each served by its own endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    /// Send a draft for review
+    Submit,
+    /// Return a product under review to draft
+    Reject,
+    /// Make a reviewed product live
+    Publish,
+    /// Retire a product from any state but archived
+    Archive,
+    /// Bring an archived product back as a draft
+    Restore,
+}
+
+impl Transition {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Transition::Submit => "submit",
+            Transition::Reject => "reject",
+            Transition::Publish => "publish",
+            Transition::Archive => "archive",
+            Transition::Restore => "restore",
+        }
+    }
+
+    /// Statuses the transition may start from
+    pub fn sources(self) -> &'static [ProductStatus] {
+        match self {
+            Transition::Submit => &[ProductStatus::Draft],
+            Transition::Reject | Transition::Publish => &[ProductStatus::InReview],
+            Transition::Archive => &[ProductStatus::Draft, ProductStatus::InReview, ProductStatus::Published],
+            Transition::Restore => &[ProductStatus::Archived],
+        }
+    }
+
+    /// Status the product ends up in
+    pub fn target(self) -> ProductStatus {
+        match self {
+            Transition::Submit => ProductStatus::InReview,
+            Transition::Reject | Transition::Restore => ProductStatus::Draft,
+            Transition::Publish => ProductStatus::Published,
+            Transition::Archive => ProductStatus::Archived,
+        }
+    }
+}
+
+/// Workflow state embedded in product responses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductLifecycle {
+    pub status: ProductStatus,
+    /// When the product was last published
+    pub published_at: Option<DateTime<FixedOffset>>,
+    /// When the product was archived, while it is
+    pub archived_at: Option<DateTime<FixedOffset>>,
+    /// Scheduled publication, applied while the product is in review
+    pub publish_at: Option<DateTime<FixedOffset>>,
+    /// Scheduled archiving, applied once the product is published
+    pub unpublish_at: Option<DateTime<FixedOffset>>,
+}
+
+/// Replaces a product's publishing schedule; omitted or null times clear it
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ScheduleRequest {
+    pub publish_at: Option<DateTime<FixedOffset>>,
+    pub unpublish_at: Option<DateTime<FixedOffset>>,
+}
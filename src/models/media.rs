@@ -0,0 +1,57 @@
+use bytes::Bytes;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// A file uploaded for a product, as read from a multipart request
+#[derive(Debug, Validate)]
+pub struct UploadMediaRequest {
+    #[validate(length(max = 255, message = "File name must be less than 256 characters"))]
+    pub file_name: Option<String>,
+    /// Content type declared by the client; the stored type is sniffed from the data
+    pub declared_type: Option<String>,
+    #[validate(length(max = 500, message = "Alt text must be less than 501 characters"))]
+    pub alt_text: Option<String>,
+    /// Position among the product's media; appended after the last item when omitted
+    #[validate(range(min = 0, message = "Sort order cannot be negative"))]
+    pub sort_order: Option<i32>,
+    pub data: Bytes,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaResponse {
+    pub id: i32,
+    /// Path the file is served from
+    pub url: String,
+    pub file_name: Option<String>,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    /// SHA-256 of the file contents, hex encoded
+    pub checksum: String,
+    pub alt_text: Option<String>,
+    pub sort_order: i32,
+    /// Resized copies generated so far, in configuration order
+    #[serde(default)]
+    pub derivatives: Vec<DerivativeResponse>,
+    pub created_at: DateTime<FixedOffset>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DerivativeResponse {
+    pub name: String,
+    /// Path the derivative is served from
+    pub url: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// A stored file ready to be served
+pub struct MediaFile {
+    pub mime_type: String,
+    pub checksum: String,
+    pub data: Bytes,
+}
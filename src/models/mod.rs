@@ -1,5 +1,14 @@
+pub mod api_key;
+pub mod attribute;
 pub mod category;
+pub mod facet;
+pub mod lifecycle;
+pub mod media;
+pub mod money;
 pub mod product;
+pub mod revision;
+pub mod tag;
+pub mod translation;
 
 pub use category::{Category, CategoryResponse, CreateCategoryRequest, UpdateCategoryRequest};
 pub use product::{CreateProductRequest, Product, ProductResponse, UpdateProductRequest};
@@ -0,0 +1,306 @@
+use std::fmt;
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal, ToPrimitive};
+use sea_orm::prelude::Decimal;
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+
+/// ISO 4217 currency code, such as `USD`
+///
+/// Parsing accepts any three ASCII letters and uppercases them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    pub const USD: Currency = Currency(*b"USD");
+
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.0).expect("currency codes are ASCII")
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Currency::USD
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.as_bytes() {
+            &[a, b, c] if s.bytes().all(|byte| byte.is_ascii_alphabetic()) => Ok(Currency([
+                a.to_ascii_uppercase(),
+                b.to_ascii_uppercase(),
+                c.to_ascii_uppercase(),
+            ])),
+            _ => Err(format!("currency must be a three-letter ISO 4217 code, not {:?}", s)),
+        }
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        code.parse().map_err(de::Error::custom)
+    }
+}
+
+/// An amount of money in one currency, as stored in a `price` column
+///
+/// Amounts have exactly two decimal places and stay below 10^8 either way, the range of the
+/// `NUMERIC(10, 2)` columns. Values that don't fit are rejected rather than rounded. In JSON, money
+/// is the pair of fields `"price": "19.99"` and `"currency": "USD"`; the amount is a string so it
+/// never passes through a float, and the currency defaults to USD when omitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Money {
+    amount: Decimal,
+    currency: Currency,
+}
+
+impl Money {
+    /// Decimal places of every amount
+    pub const SCALE: u32 = 2;
+
+    /// Amounts must stay strictly below this in magnitude
+    pub const LIMIT: Decimal = Decimal::from_parts(100_000_000, 0, 0, false, 0);
+
+    /// Money of `amount`, which must fit the price columns exactly
+    pub fn new(amount: Decimal, currency: Currency) -> Result<Self, String> {
+        if amount.abs() >= Self::LIMIT {
+            return Err(format!("price must be below {} in magnitude", Self::LIMIT));
+        }
+        if amount.normalize().scale() > Self::SCALE {
+            return Err(format!("price must have at most {} decimal places", Self::SCALE));
+        }
+
+        let mut amount = amount;
+        amount.rescale(Self::SCALE);
+        Ok(Self { amount, currency })
+    }
+
+    /// Money of `amount` as a product price, which must also be positive
+    pub fn price(amount: Decimal, currency: Currency) -> Result<Self, String> {
+        Money::new(positive(amount)?, currency)
+    }
+
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn with_currency(self, currency: Currency) -> Self {
+        Self { currency, ..self }
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount, self.currency)
+    }
+}
+
+/// Parses the display form, such as `19.99 USD`
+impl FromStr for Money {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (amount, currency) = s
+            .split_once(' ')
+            .ok_or_else(|| format!("money must be an amount and a currency, not {:?}", s))?;
+        Money::new(parse_amount(amount)?, currency.parse()?)
+    }
+}
+
+/// Parse a decimal amount such as `19.99`, checking it fits the price columns
+///
+/// Exponent forms such as `1.5e2` are accepted; any number of trailing zeros is allowed, but no
+/// more than two significant decimal places.
+pub fn parse_amount(s: &str) -> Result<Decimal, String> {
+    let amount = BigDecimal::from_str(s).map_err(|_| format!("price must be a decimal number, not {:?}", s))?;
+    amount_from_big_decimal(&amount)
+}
+
+/// Parse a product price, an amount as [`parse_amount`] reads it that must also be positive
+pub fn parse_price(s: &str) -> Result<Decimal, String> {
+    positive(parse_amount(s)?)
+}
+
+fn positive(amount: Decimal) -> Result<Decimal, String> {
+    if amount <= Decimal::ZERO {
+        return Err("price must be a positive number".to_string());
+    }
+    Ok(amount)
+}
+
+fn amount_from_big_decimal(amount: &BigDecimal) -> Result<Decimal, String> {
+    if amount.abs() >= 100_000_000 {
+        return Err(format!("price must be below {} in magnitude", Money::LIMIT));
+    }
+    let rounded = amount.with_scale(Money::SCALE as i64);
+    if &rounded != amount {
+        return Err(format!("price must have at most {} decimal places", Money::SCALE));
+    }
+
+    // Within the limit, the amount in cents always fits an i64
+    let (cents, _) = rounded.as_bigint_and_exponent();
+    Ok(Decimal::new(cents.to_i64().expect("amount is in range"), Money::SCALE))
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Fields<'a> {
+            #[serde(with = "amount")]
+            price: &'a Decimal,
+            currency: Currency,
+        }
+
+        Fields {
+            price: &self.amount,
+            currency: self.currency,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Fields {
+            #[serde(deserialize_with = "amount::deserialize")]
+            price: Decimal,
+            #[serde(default)]
+            currency: Currency,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        Money::new(fields.price, fields.currency).map_err(de::Error::custom)
+    }
+}
+
+/// Serde helpers for product prices, money whose amount must be positive
+pub mod price {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(price: &Money, serializer: S) -> Result<S::Ok, S::Error> {
+        price.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Money, D::Error> {
+        let price = Money::deserialize(deserializer)?;
+        positive(price.amount()).map_err(de::Error::custom)?;
+        Ok(price)
+    }
+
+    /// The same, for an optional bare amount; use with `#[serde(default)]`
+    pub mod option {
+        use super::*;
+
+        pub use crate::models::money::amount::option::serialize;
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Decimal>, D::Error> {
+            let amount = amount::option::deserialize(deserializer)?;
+            amount.map(positive).transpose().map_err(de::Error::custom)
+        }
+    }
+}
+
+/// Serde helpers for a bare amount, written as a string and read from a string or a number
+///
+/// JSON numbers are read by their shortest decimal form, so `19.99` stays `19.99`.
+pub mod amount {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(amount: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(amount)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+        deserializer.deserialize_any(AmountVisitor)
+    }
+
+    /// The same, for optional amounts; use with `#[serde(default)]`
+    pub mod option {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(amount: &Option<Decimal>, serializer: S) -> Result<S::Ok, S::Error> {
+            match amount {
+                Some(amount) => serializer.collect_str(amount),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Decimal>, D::Error> {
+            deserializer.deserialize_option(OptionVisitor)
+        }
+
+        struct OptionVisitor;
+
+        impl<'de> Visitor<'de> for OptionVisitor {
+            type Value = Option<Decimal>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a decimal price or null")
+            }
+
+            fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(None)
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(None)
+            }
+
+            fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+                super::deserialize(deserializer).map(Some)
+            }
+        }
+    }
+
+    struct AmountVisitor;
+
+    impl Visitor<'_> for AmountVisitor {
+        type Value = Decimal;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a decimal price such as \"19.99\"")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Decimal, E> {
+            parse_amount(value).map_err(E::custom)
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<Decimal, E> {
+            amount_from_big_decimal(&BigDecimal::from(value)).map_err(E::custom)
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<Decimal, E> {
+            amount_from_big_decimal(&BigDecimal::from(value)).map_err(E::custom)
+        }
+
+        fn visit_f64<E: de::Error>(self, value: f64) -> Result<Decimal, E> {
+            if !value.is_finite() {
+                return Err(E::custom("price must be a finite number"));
+            }
+            parse_amount(&value.to_string()).map_err(E::custom)
+        }
+    }
+}
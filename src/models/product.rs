@@ -1,16 +1,24 @@
-use bigdecimal::BigDecimal;
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, FixedOffset};
+use sea_orm::prelude::Decimal;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-use crate::validation::validate_decimal_positive;
+use crate::models::attribute::AttributeValues;
+use crate::models::facet::ProductFacets;
+use crate::models::lifecycle::{ProductLifecycle, ProductStatus};
+use crate::models::media::MediaResponse;
+use crate::models::money::{self, Currency, Money};
+use crate::validation::validate_tag_names;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Product {
     pub id: i32,
     pub name: String,
     pub description: Option<String>,
-    pub price: BigDecimal,
+    #[serde(flatten)]
+    pub price: Money,
     pub sku: Option<String>,
     pub created_at: DateTime<FixedOffset>,
     pub updated_at: DateTime<FixedOffset>,
@@ -31,12 +39,20 @@ pub struct CreateProductRequest {
     ))]
     pub name: String,
     pub description: Option<String>,
-    #[validate(custom(function = "validate_decimal_positive"))]
-    pub price: BigDecimal,
+    /// The `price` and `currency` fields
+    #[serde(flatten, with = "money::price")]
+    pub price: Money,
     #[validate(length(max = 50, message = "SKU must be less than 51 characters"))]
     pub sku: Option<String>,
     #[validate(length(min = 1, message = "At least one category ID must be provided"))]
     pub category_ids: Vec<i32>,
+    /// Values for the attributes defined by the product's categories
+    #[serde(default)]
+    pub attributes: AttributeValues,
+    /// Free-form labels; unknown tags are created on first use
+    #[serde(default)]
+    #[validate(custom(function = "validate_tag_names"))]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
@@ -48,8 +64,10 @@ pub struct UpdateProductRequest {
     ))]
     pub name: Option<String>,
     pub description: Option<String>,
-    #[validate(custom(function = "validate_decimal_positive"))]
-    pub price: Option<BigDecimal>,
+    #[serde(default, with = "money::price::option")]
+    pub price: Option<Decimal>,
+    /// Changes the currency of the current or new price
+    pub currency: Option<Currency>,
     #[validate(length(max = 50, message = "SKU must be less than 51 characters"))]
     pub sku: Option<String>,
     #[validate(length(
@@ -57,6 +75,11 @@ pub struct UpdateProductRequest {
         message = "At least one category ID must be provided (use null to leave unchanged)"
     ))]
     pub category_ids: Option<Vec<i32>>,
+    /// Replaces every attribute value when provided
+    pub attributes: Option<AttributeValues>,
+    /// Replaces every tag when provided
+    #[validate(custom(function = "validate_tag_names"))]
+    pub tags: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -64,9 +87,21 @@ pub struct ProductResponse {
     pub id: i32,
     pub name: String,
     pub description: Option<String>,
-    pub price: BigDecimal,
+    /// The `price` and `currency` fields
+    #[serde(flatten)]
+    pub price: Money,
     pub sku: Option<String>,
+    #[serde(flatten)]
+    pub lifecycle: ProductLifecycle,
     pub categories: Vec<CategoryBrief>,
+    #[serde(default)]
+    pub attributes: AttributeValues,
+    /// Tag names, in alphabetical order
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Images and other attachments, in display order
+    #[serde(default)]
+    pub media: Vec<MediaResponse>,
     pub created_at: DateTime<FixedOffset>,
     pub updated_at: DateTime<FixedOffset>,
 }
@@ -83,6 +118,9 @@ pub struct ProductListResponse {
     pub total: i64,
     pub page: i64,
     pub page_size: i64,
+    /// Present when the request asked for `facets`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub facets: Option<ProductFacets>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -90,18 +128,48 @@ pub struct ProductQueryParams {
     pub page: Option<i64>,
     pub page_size: Option<i64>,
     pub category_id: Option<i32>,
+    /// Full-text search over names and descriptions, in web search syntax
+    pub q: Option<String>,
+    /// Only honored for editors; anonymous requests only ever see published products
+    pub status: Option<ProductStatus>,
+    /// Comma-separated facets to count, e.g. `category,price_range,attr.color`
+    pub facets: Option<String>,
+    /// Attribute filters from `attr.<name>=<value>` parameters
+    ///
+    /// A product matches when, for every attribute, its value equals one of the listed values.
+    #[serde(skip)]
+    pub attributes: BTreeMap<String, Vec<String>>,
+    /// Tag filters from `tag=<name>` parameters; a product matches when it has every tag
+    #[serde(skip)]
+    pub tags: Vec<String>,
+    /// Whether products that aren't published are listed; set for editors
+    #[serde(skip)]
+    pub include_unpublished: bool,
 }
 
 impl ProductQueryParams {
+    /// Collect the repeatable `attr.<name>=<value>` and `tag=<name>` filters from raw query pairs
+    pub fn with_filters(mut self, pairs: Vec<(String, String)>) -> Self {
+        for (key, value) in pairs {
+            if let Some(name) = key.strip_prefix("attr.") {
+                self.attributes.entry(name.to_string()).or_default().push(value);
+            } else if key == "tag" {
+                self.tags.push(value);
+            }
+        }
+        self
+    }
+
     pub fn page(&self) -> i64 {
         self.page.unwrap_or(1).max(1)
     }
 
     pub fn page_size(&self) -> i64 {
-        self.page_size.unwrap_or(10).min(100).max(1)
+        self.page_size.unwrap_or(10).clamp(1, 100)
     }
 
+    /// Rows skipped before the requested page; saturates rather than overflowing for huge pages
     pub fn offset(&self) -> i64 {
-        (self.page() - 1) * self.page_size()
+        (self.page() - 1).saturating_mul(self.page_size())
     }
 }
@@ -0,0 +1,165 @@
+use std::fmt;
+use std::str::FromStr;
+
+use sea_orm::prelude::{DateTimeWithTimeZone, Decimal};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use validator::Validate;
+
+use crate::models::money::{self, Currency, Money};
+
+/// Whether a revision proposes a change or holds content that has been live
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RevisionStatus {
+    /// Prepared by an editor and not applied to the product yet
+    Draft,
+    /// Applied to the product, whether by an update, a published draft or a revert
+    Published,
+}
+
+impl RevisionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RevisionStatus::Draft => "draft",
+            RevisionStatus::Published => "published",
+        }
+    }
+}
+
+impl fmt::Display for RevisionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl FromStr for RevisionStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "draft" => Ok(RevisionStatus::Draft),
+            "published" => Ok(RevisionStatus::Published),
+            other => Err(format!("unknown revision status {:?}", other)),
+        }
+    }
+}
+
+/// The product content a revision captures
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RevisionContent {
+    pub name: String,
+    pub description: Option<String>,
+    /// The `price` and `currency` fields
+    #[serde(flatten)]
+    pub price: Money,
+    /// Category IDs, in ascending order
+    pub category_ids: Vec<i32>,
+}
+
+impl RevisionContent {
+    /// Fields that differ between `self` and `other`, in field order
+    pub fn diff(&self, other: &RevisionContent) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+
+        if self.name != other.name {
+            changes.push(FieldChange::new("name", &self.name, &other.name));
+        }
+        if self.description != other.description {
+            changes.push(FieldChange::new("description", &self.description, &other.description));
+        }
+        if self.price.amount() != other.price.amount() {
+            changes.push(FieldChange::new(
+                "price",
+                self.price.amount().to_string(),
+                other.price.amount().to_string(),
+            ));
+        }
+        if self.price.currency() != other.price.currency() {
+            changes.push(FieldChange::new(
+                "currency",
+                self.price.currency().as_str(),
+                other.price.currency().as_str(),
+            ));
+        }
+        if self.category_ids != other.category_ids {
+            changes.push(FieldChange::new(
+                "category_ids",
+                &self.category_ids,
+                &other.category_ids,
+            ));
+        }
+
+        changes
+    }
+}
+
+/// A draft revision; omitted fields keep the live product's content
+#[derive(Debug, Default, Serialize, Deserialize, Validate)]
+pub struct CreateRevisionRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Product name cannot be empty and must be less than 256 characters"
+    ))]
+    pub name: Option<String>,
+    pub description: Option<String>,
+    #[serde(default, with = "money::price::option")]
+    pub price: Option<Decimal>,
+    pub currency: Option<Currency>,
+    #[validate(length(
+        min = 1,
+        message = "At least one category ID must be provided (use null to leave unchanged)"
+    ))]
+    pub category_ids: Option<Vec<i32>>,
+    /// Why the change is being made
+    #[validate(length(max = 255, message = "Note must be less than 256 characters"))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionResponse {
+    /// Revision number, counting from 1 for each product
+    pub number: i32,
+    pub status: RevisionStatus,
+    /// For drafts, the revision that was live when the draft was created
+    pub base_number: Option<i32>,
+    /// Whether this revision holds the product's live content
+    pub live: bool,
+    #[serde(flatten)]
+    pub content: RevisionContent,
+    pub note: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    pub published_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionDiffParams {
+    pub from: i32,
+    pub to: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionDiffResponse {
+    pub from: i32,
+    pub to: i32,
+    /// Changed fields; empty when the revisions hold the same content
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+impl FieldChange {
+    fn new(field: &str, from: impl Serialize, to: impl Serialize) -> Self {
+        Self {
+            field: field.to_string(),
+            from: serde_json::to_value(from).unwrap_or(Value::Null),
+            to: serde_json::to_value(to).unwrap_or(Value::Null),
+        }
+    }
+}
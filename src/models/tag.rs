@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::validation::validate_tag_name;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct RenameTagRequest {
+    #[validate(custom(function = "validate_tag_name"))]
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MergeTagsRequest {
+    /// Tag that receives the merged tag's products
+    pub into: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagResponse {
+    pub id: i32,
+    pub name: String,
+    /// Number of products carrying the tag
+    pub product_count: i64,
+}
@@ -0,0 +1,38 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::locale::Locale;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ProductTranslationRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Product name cannot be empty and must be less than 256 characters"
+    ))]
+    pub name: String,
+    /// Falls back along the locale chain when omitted
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CategoryTranslationRequest {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Category name cannot be empty and must be less than 101 characters"
+    ))]
+    pub name: String,
+    /// Falls back along the locale chain when omitted
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TranslationResponse {
+    pub locale: Locale,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<FixedOffset>,
+    pub updated_at: DateTime<FixedOffset>,
+}
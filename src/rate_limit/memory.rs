@@ -0,0 +1,48 @@
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use lru::LruCache;
+
+use super::{Decision, Quota, RateLimitStore};
+
+/// In-process token buckets, evicting the least recently seen client when full
+///
+/// An evicted client starts over with a full bucket, so `capacity` should comfortably exceed
+/// the number of clients active within one window.
+pub struct MemoryRateLimitStore {
+    buckets: Mutex<LruCache<String, (f64, Instant)>>,
+}
+
+impl MemoryRateLimitStore {
+    /// Create a new in-memory store tracking at most `capacity` buckets
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            buckets: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryRateLimitStore {
+    async fn acquire(&self, key: &str, quota: Quota) -> Result<Decision> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .map_err(|_| anyhow!("Rate limit store lock poisoned"))?;
+
+        let now = Instant::now();
+        let (tokens, updated) = buckets.get(key).copied().unwrap_or((f64::from(quota.limit), now));
+        let (tokens, decision) = quota.take(tokens, now.duration_since(updated));
+        buckets.put(key.to_string(), (tokens, now));
+
+        Ok(decision)
+    }
+
+    fn backend(&self) -> &'static str {
+        "memory"
+    }
+}
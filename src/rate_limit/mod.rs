@@ -0,0 +1,298 @@
+pub mod memory;
+pub mod redis;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use axum::extract::{ConnectInfo, State};
+use axum::http::{HeaderMap, HeaderValue, Method, Request, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+pub use self::redis::RedisRateLimitStore;
+pub use memory::MemoryRateLimitStore;
+
+use crate::auth::Editor;
+use crate::config::RateLimitConfig;
+use crate::error::ApiError;
+
+/// Build the rate limiter selected by the configuration
+pub async fn connect(config: &RateLimitConfig) -> Result<RateLimiter> {
+    if !config.enabled {
+        return Ok(RateLimiter::disabled());
+    }
+
+    let store: Arc<dyn RateLimitStore> = match config.backend.as_str() {
+        "memory" => Arc::new(MemoryRateLimitStore::new(config.capacity)),
+        "redis" => {
+            let Some(url) = &config.redis_url else {
+                bail!("rate_limit.redis_url must be set for the redis backend");
+            };
+            Arc::new(RedisRateLimitStore::connect(url, "ratelimit:").await?)
+        }
+        other => bail!("Unknown rate limit backend: {}", other),
+    };
+
+    Ok(RateLimiter::new(store, config))
+}
+
+/// Headers describing a client's budget, set on every rate-limited response
+pub const HEADERS: [&str; 4] = [
+    "ratelimit-limit",
+    "ratelimit-remaining",
+    "ratelimit-reset",
+    "ratelimit-policy",
+];
+
+/// Budget of a token bucket: `limit` requests, refilled evenly over `window`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quota {
+    pub limit: u32,
+    pub window: Duration,
+}
+
+impl Quota {
+    /// Tokens added back per second
+    fn rate(&self) -> f64 {
+        f64::from(self.limit) / self.window.as_secs_f64()
+    }
+
+    /// The outcome of a request that left `tokens` in the bucket
+    pub fn decision(&self, allowed: bool, tokens: f64) -> Decision {
+        let tokens = tokens.clamp(0.0, f64::from(self.limit));
+        let retry_after = if allowed {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - tokens) / self.rate())
+        };
+
+        Decision {
+            allowed,
+            remaining: tokens as u32,
+            reset: Duration::from_secs_f64((f64::from(self.limit) - tokens) / self.rate()),
+            retry_after,
+        }
+    }
+
+    /// Refill a bucket holding `tokens` for `elapsed`, then take one token if there is one
+    ///
+    /// Returns the tokens left and the outcome; shared by stores that keep buckets themselves.
+    pub fn take(&self, tokens: f64, elapsed: Duration) -> (f64, Decision) {
+        let tokens = (tokens + elapsed.as_secs_f64() * self.rate()).min(f64::from(self.limit));
+        let allowed = tokens >= 1.0;
+        let tokens = if allowed { tokens - 1.0 } else { tokens };
+
+        (tokens, self.decision(allowed, tokens))
+    }
+}
+
+/// Outcome of taking a token from a bucket
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Decision {
+    pub allowed: bool,
+    /// Whole tokens left in the bucket
+    pub remaining: u32,
+    /// Time until the bucket is full again
+    pub reset: Duration,
+    /// Time until a token is available; zero when the request was allowed
+    pub retry_after: Duration,
+}
+
+/// A store of token buckets, shared by every request of a client
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Take a token from the bucket under `key`, creating it full if it doesn't exist
+    ///
+    /// Buckets hold up to `quota.limit` tokens and refill at `quota.limit` per `quota.window`.
+    async fn acquire(&self, key: &str, quota: Quota) -> Result<Decision>;
+
+    /// Short name of the backend, used in logs
+    fn backend(&self) -> &'static str;
+}
+
+/// Token-bucket rate limiter over a [`RateLimitStore`]
+///
+/// Clients are identified by API key, or by IP address when anonymous, and have separate
+/// budgets for reads (`GET`, `HEAD` and `OPTIONS`) and writes. Requests presenting an API key
+/// also take a token from their IP address's authentication budget before the key is checked.
+/// Store failures are logged and let the request through, so a broken store never takes the
+/// API down.
+#[derive(Clone)]
+pub struct RateLimiter {
+    store: Option<Arc<dyn RateLimitStore>>,
+    read: Quota,
+    write: Quota,
+    auth: Quota,
+    trust_forwarded_for: bool,
+}
+
+impl RateLimiter {
+    /// Create a rate limiter over the given store with the configured budgets
+    pub fn new(store: Arc<dyn RateLimitStore>, config: &RateLimitConfig) -> Self {
+        let window = Duration::from_secs(config.window_secs);
+        Self {
+            store: Some(store),
+            read: Quota {
+                limit: config.read_limit,
+                window,
+            },
+            write: Quota {
+                limit: config.write_limit,
+                window,
+            },
+            auth: Quota {
+                limit: config.auth_limit,
+                window,
+            },
+            trust_forwarded_for: config.trust_forwarded_for,
+        }
+    }
+
+    /// Create a rate limiter that lets every request through
+    pub fn disabled() -> Self {
+        let quota = Quota {
+            limit: 1,
+            window: Duration::from_secs(1),
+        };
+        Self {
+            store: None,
+            read: quota,
+            write: quota,
+            auth: quota,
+            trust_forwarded_for: false,
+        }
+    }
+
+    /// Bucket key of a request: its budget and the client making it
+    fn key<B>(&self, request: &Request<B>) -> (Quota, String) {
+        let (budget, quota) = if matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+            ("read", self.read)
+        } else {
+            ("write", self.write)
+        };
+
+        let client = match request.extensions().get::<Editor>() {
+            Some(editor) => format!("key:{}", editor.api_key_id),
+            None => format!("ip:{}", self.client_ip(request)),
+        };
+
+        (quota, format!("{}:{}", budget, client))
+    }
+
+    /// Address of the client sending a request, as far as it can be told
+    fn client_ip<B>(&self, request: &Request<B>) -> String {
+        let forwarded = self
+            .trust_forwarded_for
+            .then(|| forwarded_for(request.headers()))
+            .flatten();
+        let peer = request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string());
+        forwarded.or(peer).unwrap_or_else(|| "unknown".to_string())
+    }
+}
+
+/// Middleware enforcing the [`RateLimiter`]'s budgets
+///
+/// Responses carry `RateLimit-Limit`, `RateLimit-Remaining`, `RateLimit-Reset` and
+/// `RateLimit-Policy` headers; requests over budget get `429` with a `Retry-After` header. Must
+/// run after [`crate::auth::authenticate`] so editors are limited by API key.
+pub async fn limit_requests<B>(State(limiter): State<RateLimiter>, request: Request<B>, next: Next<B>) -> Response {
+    let Some(store) = &limiter.store else {
+        return next.run(request).await;
+    };
+
+    let (quota, key) = limiter.key(&request);
+    let decision = match store.acquire(&key, quota).await {
+        Ok(decision) => decision,
+        Err(e) => {
+            tracing::warn!("Rate limit check failed for {}: {:?}", key, e);
+            return next.run(request).await;
+        }
+    };
+
+    let mut response = if decision.allowed {
+        next.run(request).await
+    } else {
+        too_many_requests(quota, &decision)
+    };
+    set_headers(&mut response, quota, &decision);
+
+    response
+}
+
+/// Middleware limiting requests that present an API key by client address, before the key is
+/// looked up
+///
+/// Without it, an unknown key is rejected by [`crate::auth::authenticate`] before
+/// [`limit_requests`] runs, so guessing keys would never be limited. Must run before
+/// authentication. Allowed requests are passed on untouched, leaving the rate limit headers to
+/// [`limit_requests`].
+pub async fn limit_authentication<B>(
+    State(limiter): State<RateLimiter>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let Some(store) = &limiter.store else {
+        return next.run(request).await;
+    };
+    if !request.headers().contains_key(header::AUTHORIZATION) {
+        return next.run(request).await;
+    }
+
+    let key = format!("auth:ip:{}", limiter.client_ip(&request));
+    match store.acquire(&key, limiter.auth).await {
+        Ok(decision) if !decision.allowed => {
+            let mut response = too_many_requests(limiter.auth, &decision);
+            set_headers(&mut response, limiter.auth, &decision);
+            response
+        }
+        Ok(_) => next.run(request).await,
+        Err(e) => {
+            tracing::warn!("Rate limit check failed for {}: {:?}", key, e);
+            next.run(request).await
+        }
+    }
+}
+
+/// A `429` response saying when to retry
+fn too_many_requests(quota: Quota, decision: &Decision) -> Response {
+    let retry_after = decision.retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    let mut response = ApiError::TooManyRequests(format!(
+        "Rate limit of {} requests per {}s exceeded; retry in {}s",
+        quota.limit,
+        quota.window.as_secs(),
+        retry_after
+    ))
+    .into_response();
+    response.headers_mut().insert(header::RETRY_AFTER, retry_after.into());
+    response
+}
+
+/// Describe the client's budget in the rate limit headers
+fn set_headers(response: &mut Response, quota: Quota, decision: &Decision) {
+    let headers = response.headers_mut();
+    headers.insert("ratelimit-limit", quota.limit.into());
+    headers.insert("ratelimit-remaining", decision.remaining.into());
+    headers.insert("ratelimit-reset", (decision.reset.as_secs_f64().ceil() as u64).into());
+    if let Ok(policy) = HeaderValue::from_str(&format!("{};w={}", quota.limit, quota.window.as_secs())) {
+        headers.insert("ratelimit-policy", policy);
+    }
+}
+
+/// The client address a trusted proxy reports: the first entry of `X-Forwarded-For`
+fn forwarded_for(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("x-forwarded-for")?
+        .to_str()
+        .ok()?
+        .split(',')
+        .next()
+        .map(str::trim)
+        .filter(|addr| !addr.is_empty())
+        .map(str::to_string)
+}
@@ -0,0 +1,75 @@
+use ::redis::Script;
+use ::redis::aio::MultiplexedConnection;
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+
+use super::{Decision, Quota, RateLimitStore};
+
+/// Refills and takes from a bucket atomically, using the server's clock so every replica agrees
+///
+/// Buckets expire once they would be full again, which is the same as not existing.
+const ACQUIRE_SCRIPT: &str = r"
+local limit = tonumber(ARGV[1])
+local window_ms = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local state = redis.call('HMGET', KEYS[1], 'tokens', 'updated')
+local tokens = tonumber(state[1]) or limit
+local updated = tonumber(state[2]) or now
+tokens = math.min(limit, tokens + math.max(0, now - updated) * limit / window_ms)
+local allowed = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+    allowed = 1
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'updated', now)
+redis.call('PEXPIRE', KEYS[1], window_ms)
+return {allowed, tostring(tokens)}
+";
+
+/// Token buckets kept in Redis, shared by every replica of the API
+pub struct RedisRateLimitStore {
+    conn: MultiplexedConnection,
+    prefix: String,
+    script: Script,
+}
+
+impl RedisRateLimitStore {
+    /// Connect to the server at `url`, namespacing every key with `prefix`
+    pub async fn connect(url: &str, prefix: impl Into<String>) -> Result<Self> {
+        let client = ::redis::Client::open(url).map_err(|e| anyhow!("Invalid Redis URL: {:?}", e))?;
+        let conn = client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| anyhow!("Redis connection error: {:?}", e))?;
+
+        Ok(Self {
+            conn,
+            prefix: prefix.into(),
+            script: Script::new(ACQUIRE_SCRIPT),
+        })
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for RedisRateLimitStore {
+    async fn acquire(&self, key: &str, quota: Quota) -> Result<Decision> {
+        let mut conn = self.conn.clone();
+        let (allowed, tokens): (i32, String) = self
+            .script
+            .key(format!("{}{}", self.prefix, key))
+            .arg(quota.limit)
+            .arg(quota.window.as_millis() as u64)
+            .invoke_async(&mut conn)
+            .await?;
+        let tokens: f64 = tokens
+            .parse()
+            .map_err(|_| anyhow!("Invalid token count from Redis: {}", tokens))?;
+
+        Ok(quota.decision(allowed == 1, tokens))
+    }
+
+    fn backend(&self) -> &'static str {
+        "redis"
+    }
+}
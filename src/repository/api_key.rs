@@ -0,0 +1,115 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::database::DatabaseConnection;
+use crate::entity::{ApiKey, ApiKeyActiveModel, ApiKeyColumn, ApiKeyModel};
+use crate::error::ApiError;
+use crate::models::api_key::{ApiKeyResponse, CreateApiKeyRequest, CreatedApiKeyResponse};
+
+/// Prefix identifying secrets issued by this service
+const SECRET_PREFIX: &str = "pcat_";
+
+/// Number of leading secret characters stored in clear text to identify a key
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// Repository for API key operations
+///
+/// Only a SHA-256 hash of each secret is stored; the secret itself is returned once, on creation.
+#[derive(Clone)]
+pub struct ApiKeyRepository {
+    conn: DatabaseConnection,
+}
+
+impl ApiKeyRepository {
+    /// Create a new API key repository
+    pub fn new(conn: DatabaseConnection) -> Self {
+        Self { conn }
+    }
+
+    /// Issue a new API key
+    pub async fn create_api_key(&self, req: CreateApiKeyRequest) -> Result<CreatedApiKeyResponse, ApiError> {
+        let secret = format!(
+            "{}{}{}",
+            SECRET_PREFIX,
+            Uuid::new_v4().simple(),
+            Uuid::new_v4().simple()
+        );
+
+        let api_key = ApiKeyActiveModel {
+            name: Set(req.name),
+            tenant_id: Set(req.tenant.to_string()),
+            prefix: Set(secret[..DISPLAY_PREFIX_LEN].to_string()),
+            key_hash: Set(hash_secret(&secret)),
+            ..Default::default()
+        };
+        let api_key = api_key.insert(&self.conn).await.map_err(ApiError::Database)?;
+
+        Ok(CreatedApiKeyResponse {
+            api_key: api_key.try_into()?,
+            secret,
+        })
+    }
+
+    /// List all API keys, including revoked ones
+    pub async fn list_api_keys(&self) -> Result<Vec<ApiKeyResponse>, ApiError> {
+        let api_keys = ApiKey::find()
+            .order_by_asc(ApiKeyColumn::Id)
+            .all(&self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+
+        api_keys.into_iter().map(ApiKeyResponse::try_from).collect()
+    }
+
+    /// Revoke an API key so it can no longer be used
+    pub async fn revoke_api_key(&self, id: i32) -> Result<ApiKeyResponse, ApiError> {
+        let api_key = ApiKey::find_by_id(id)
+            .one(&self.conn)
+            .await
+            .map_err(ApiError::Database)?
+            .ok_or_else(|| ApiError::not_found("API key", id))?;
+
+        if api_key.revoked_at.is_some() {
+            return Err(ApiError::Conflict(format!("API key with ID {} is already revoked", id)));
+        }
+
+        let mut api_key: ApiKeyActiveModel = api_key.into();
+        api_key.revoked_at = Set(Some(Utc::now().into()));
+        let api_key = api_key.update(&self.conn).await.map_err(ApiError::Database)?;
+
+        api_key.try_into()
+    }
+
+    /// Look up the active (non-revoked) key matching a presented secret
+    pub async fn find_active_by_secret(&self, secret: &str) -> Result<Option<ApiKeyResponse>, ApiError> {
+        let api_key = ApiKey::find()
+            .filter(ApiKeyColumn::KeyHash.eq(hash_secret(secret)))
+            .filter(ApiKeyColumn::RevokedAt.is_null())
+            .one(&self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+
+        api_key.map(ApiKeyResponse::try_from).transpose()
+    }
+}
+
+impl TryFrom<ApiKeyModel> for ApiKeyResponse {
+    type Error = ApiError;
+
+    fn try_from(model: ApiKeyModel) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: model.id,
+            name: model.name,
+            tenant: model.tenant_id.parse().map_err(ApiError::internal_server_error)?,
+            prefix: model.prefix,
+            created_at: model.created_at,
+            revoked_at: model.revoked_at,
+        })
+    }
+}
+
+fn hash_secret(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
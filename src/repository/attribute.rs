@@ -0,0 +1,504 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use sea_orm::sea_query::{Expr, Query};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DbBackend, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    Set, TransactionTrait,
+};
+
+use crate::cache::{CatalogCache, keys};
+use crate::database::DatabaseConnection;
+use crate::entity::{
+    AttributeDefinition, AttributeDefinitionActiveModel, AttributeDefinitionColumn, AttributeDefinitionModel, Category,
+    CategoryColumn, ProductAttribute, ProductAttributeActiveModel, ProductAttributeColumn, ProductCategory,
+    ProductCategoryColumn, ProductColumn,
+};
+use crate::error::ApiError;
+use crate::metrics;
+use crate::models::attribute::{
+    AttributeResponse, AttributeType, AttributeValues, CreateAttributeRequest, UpdateAttributeRequest,
+};
+use crate::tenant::Tenant;
+
+/// Longest accepted `string` attribute value, in characters
+const MAX_STRING_VALUE_CHARS: usize = 500;
+
+/// Repository for the attribute definitions attached to one tenant's categories
+#[derive(Clone)]
+pub struct AttributeRepository {
+    conn: DatabaseConnection,
+    cache: CatalogCache,
+    tenant: Tenant,
+}
+
+impl AttributeRepository {
+    /// Create a new attribute repository working on the default tenant
+    pub fn new(conn: DatabaseConnection, cache: CatalogCache) -> Self {
+        Self {
+            conn,
+            cache,
+            tenant: Tenant::default(),
+        }
+    }
+
+    /// A copy of this repository working on `tenant`'s catalog
+    pub fn for_tenant(&self, tenant: &Tenant) -> Self {
+        Self {
+            tenant: tenant.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// List a category's attribute definitions, ordered by name
+    pub async fn list_attributes(&self, category_id: i32) -> Result<Vec<AttributeResponse>, ApiError> {
+        Self::check_category(category_id, &self.tenant, &self.conn).await?;
+
+        let definitions = get_category_definitions(&[category_id], &self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+
+        definitions.into_iter().map(AttributeResponse::try_from).collect()
+    }
+
+    /// Add an attribute definition to a category
+    pub async fn create_attribute(
+        &self,
+        category_id: i32,
+        req: CreateAttributeRequest,
+    ) -> Result<AttributeResponse, ApiError> {
+        check_definition(req.value_type, req.values.as_deref(), req.unit.as_deref())?;
+        let tenant = self.tenant.clone();
+
+        metrics::time_transaction(
+            "create_attribute",
+            self.conn.transaction(|txn| {
+                Box::pin(async move {
+                    Self::check_category(category_id, &tenant, txn).await?;
+
+                    let definition = AttributeDefinitionActiveModel {
+                        category_id: Set(category_id),
+                        name: Set(req.name),
+                        value_type: Set(req.value_type.to_string()),
+                        required: Set(req.required),
+                        allowed_values: Set(req.values.map(|values| serde_json::json!(values))),
+                        unit: Set(req.unit),
+                        ..Default::default()
+                    };
+
+                    // A duplicate name within the category violates a unique constraint (409)
+                    let definition = definition.insert(txn).await.map_err(ApiError::Database)?;
+
+                    AttributeResponse::try_from(definition)
+                })
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })
+    }
+
+    /// Update an attribute definition
+    ///
+    /// Existing product values are not re-checked; they must satisfy the new definition the next
+    /// time the product's attributes or categories change.
+    pub async fn update_attribute(
+        &self,
+        category_id: i32,
+        id: i32,
+        req: UpdateAttributeRequest,
+    ) -> Result<AttributeResponse, ApiError> {
+        let tenant = self.tenant.clone();
+
+        metrics::time_transaction(
+            "update_attribute",
+            self.conn.transaction(|txn| {
+                Box::pin(async move {
+                    let definition = Self::find_definition(category_id, id, &tenant, txn).await?;
+                    let value_type = parse_type(&definition)?;
+                    let mut values = allowed_values(&definition);
+                    let mut unit = definition.unit.clone();
+
+                    let mut active: AttributeDefinitionActiveModel = definition.into();
+
+                    if let Some(required) = req.required {
+                        active.required = Set(required);
+                    }
+
+                    if let Some(new_values) = req.values {
+                        active.allowed_values = Set(Some(serde_json::json!(new_values)));
+                        values = Some(new_values);
+                    }
+
+                    if let Some(new_unit) = req.unit {
+                        active.unit = Set(Some(new_unit.clone()));
+                        unit = Some(new_unit);
+                    }
+
+                    check_definition(value_type, values.as_deref(), unit.as_deref())?;
+
+                    active.updated_at = Set(Utc::now().into());
+                    let definition = active.update(txn).await.map_err(ApiError::Database)?;
+
+                    AttributeResponse::try_from(definition)
+                })
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })
+    }
+
+    /// Delete an attribute definition
+    ///
+    /// Products in the category lose their value for the attribute, unless another of their
+    /// categories defines an attribute with the same name.
+    pub async fn delete_attribute(&self, category_id: i32, id: i32) -> Result<(), ApiError> {
+        let tenant = self.tenant.clone();
+
+        let affected_product_ids = metrics::time_transaction(
+            "delete_attribute",
+            self.conn.transaction(|txn| {
+                Box::pin(async move {
+                    Self::find_definition(category_id, id, &tenant, txn).await?;
+
+                    AttributeDefinition::delete_by_id(id)
+                        .exec(txn)
+                        .await
+                        .map_err(ApiError::Database)?;
+
+                    let affected_product_ids: Vec<i32> = ProductCategory::find()
+                        .filter(ProductCategoryColumn::CategoryId.eq(category_id))
+                        .all(txn)
+                        .await
+                        .map_err(ApiError::Database)?
+                        .into_iter()
+                        .map(|link| link.product_id)
+                        .collect();
+                    prune_product_attributes(&affected_product_ids, txn)
+                        .await
+                        .map_err(ApiError::Database)?;
+
+                    Ok(affected_product_ids)
+                })
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })?;
+
+        let stale = affected_product_ids
+            .iter()
+            .map(|product_id| keys::product(&self.tenant, *product_id))
+            .collect();
+        self.cache.invalidate(stale).await;
+
+        Ok(())
+    }
+
+    /// Helper method to check that a category exists and belongs to `tenant`
+    async fn check_category(
+        category_id: i32,
+        tenant: &Tenant,
+        executor: &impl ConnectionTrait,
+    ) -> Result<(), ApiError> {
+        Category::find_by_id(category_id)
+            .filter(CategoryColumn::TenantId.eq(tenant.as_str()))
+            .one(executor)
+            .await
+            .map_err(ApiError::Database)?
+            .ok_or_else(|| ApiError::not_found_simple("Category not found"))?;
+
+        Ok(())
+    }
+
+    /// Helper method to find a definition belonging to one of `tenant`'s categories
+    async fn find_definition(
+        category_id: i32,
+        id: i32,
+        tenant: &Tenant,
+        executor: &impl ConnectionTrait,
+    ) -> Result<AttributeDefinitionModel, ApiError> {
+        Self::check_category(category_id, tenant, executor).await?;
+
+        AttributeDefinition::find_by_id(id)
+            .filter(AttributeDefinitionColumn::CategoryId.eq(category_id))
+            .one(executor)
+            .await
+            .map_err(ApiError::Database)?
+            .ok_or_else(|| ApiError::not_found_simple("Attribute not found"))
+    }
+}
+
+impl TryFrom<AttributeDefinitionModel> for AttributeResponse {
+    type Error = ApiError;
+
+    fn try_from(definition: AttributeDefinitionModel) -> Result<Self, Self::Error> {
+        Ok(AttributeResponse {
+            value_type: parse_type(&definition)?,
+            values: allowed_values(&definition),
+            id: definition.id,
+            category_id: definition.category_id,
+            name: definition.name,
+            required: definition.required,
+            unit: definition.unit,
+            created_at: definition.created_at,
+            updated_at: definition.updated_at,
+        })
+    }
+}
+
+/// Get the attribute definitions of the given categories, ordered by name then category
+pub async fn get_category_definitions(
+    category_ids: &[i32],
+    executor: &impl ConnectionTrait,
+) -> Result<Vec<AttributeDefinitionModel>, DbErr> {
+    AttributeDefinition::find()
+        .filter(AttributeDefinitionColumn::CategoryId.is_in(category_ids.iter().copied()))
+        .order_by_asc(AttributeDefinitionColumn::Name)
+        .order_by_asc(AttributeDefinitionColumn::CategoryId)
+        .all(executor)
+        .await
+}
+
+/// Get a product's attribute values
+pub async fn get_product_attributes(
+    product_id: i32,
+    executor: &impl ConnectionTrait,
+) -> Result<AttributeValues, DbErr> {
+    let attributes = ProductAttribute::find()
+        .filter(ProductAttributeColumn::ProductId.eq(product_id))
+        .all(executor)
+        .await?;
+
+    Ok(attributes
+        .into_iter()
+        .map(|attribute| (attribute.name, attribute.value))
+        .collect())
+}
+
+/// Get the attribute values of several products, keyed by product ID
+pub async fn get_products_attributes(
+    product_ids: &[i32],
+    executor: &impl ConnectionTrait,
+) -> Result<HashMap<i32, AttributeValues>, DbErr> {
+    let mut values: HashMap<i32, AttributeValues> = HashMap::new();
+    if product_ids.is_empty() {
+        return Ok(values);
+    }
+
+    let attributes = ProductAttribute::find()
+        .filter(ProductAttributeColumn::ProductId.is_in(product_ids.iter().copied()))
+        .all(executor)
+        .await?;
+    for attribute in attributes {
+        values
+            .entry(attribute.product_id)
+            .or_default()
+            .insert(attribute.name, attribute.value);
+    }
+
+    Ok(values)
+}
+
+/// Replace a product's attribute values
+pub async fn set_product_attributes(
+    product_id: i32,
+    values: &AttributeValues,
+    executor: &impl ConnectionTrait,
+) -> Result<(), DbErr> {
+    ProductAttribute::delete_many()
+        .filter(ProductAttributeColumn::ProductId.eq(product_id))
+        .exec(executor)
+        .await?;
+
+    if values.is_empty() {
+        return Ok(());
+    }
+
+    let rows = values.iter().map(|(name, value)| ProductAttributeActiveModel {
+        product_id: Set(product_id),
+        name: Set(name.clone()),
+        value: Set(value.clone()),
+    });
+    ProductAttribute::insert_many(rows).exec(executor).await?;
+
+    Ok(())
+}
+
+/// Remove product values for attributes none of the product's categories define any more
+pub async fn prune_product_attributes(product_ids: &[i32], executor: &impl ConnectionTrait) -> Result<(), DbErr> {
+    for product_id in product_ids {
+        let category_ids: Vec<i32> = ProductCategory::find()
+            .filter(ProductCategoryColumn::ProductId.eq(*product_id))
+            .all(executor)
+            .await?
+            .into_iter()
+            .map(|link| link.category_id)
+            .collect();
+        let defined: BTreeSet<String> = get_category_definitions(&category_ids, executor)
+            .await?
+            .into_iter()
+            .map(|definition| definition.name)
+            .collect();
+
+        ProductAttribute::delete_many()
+            .filter(ProductAttributeColumn::ProductId.eq(*product_id))
+            .filter(ProductAttributeColumn::Name.is_not_in(defined))
+            .exec(executor)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Check a product's attribute values against its categories' definitions
+///
+/// Every value must be defined by at least one category and satisfy each definition of that name,
+/// and every required attribute must have a value. All problems are reported together.
+pub fn check_attributes(definitions: &[AttributeDefinitionModel], values: &AttributeValues) -> Result<(), ApiError> {
+    let mut by_name: BTreeMap<&str, Vec<&AttributeDefinitionModel>> = BTreeMap::new();
+    for definition in definitions {
+        by_name.entry(definition.name.as_str()).or_default().push(definition);
+    }
+
+    let mut problems = Vec::new();
+
+    for (name, value) in values {
+        match by_name.get(name.as_str()) {
+            None => problems.push(format!(
+                "attributes.{}: not defined by any of the product's categories",
+                name
+            )),
+            Some(definitions) => {
+                for definition in definitions {
+                    if let Err(problem) = check_value(definition, value) {
+                        problems.push(format!("attributes.{}: {}", name, problem));
+                    }
+                }
+            }
+        }
+    }
+
+    for (name, definitions) in &by_name {
+        if definitions.iter().any(|definition| definition.required) && !values.contains_key(*name) {
+            problems.push(format!("attributes.{}: is required", name));
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        problems.sort();
+        problems.dedup();
+        Err(ApiError::bad_request(problems.join("; ")))
+    }
+}
+
+/// Condition matching products whose attributes pass every `attr.<name>=<value>` filter
+///
+/// Values are compared as text, and numerically when both sides are numbers, so `attr.voltage=220`
+/// matches a stored `220` or `220.0`.
+pub fn attribute_filter(filters: &BTreeMap<String, Vec<String>>, backend: DbBackend) -> Condition {
+    let (as_text, equals_number) = match backend {
+        DbBackend::Sqlite => (
+            r#"CASE json_type("value") WHEN 'text' THEN json_extract("value", '$') ELSE json("value") END = ?"#,
+            r#"json_type("value") IN ('integer', 'real') AND json_extract("value", '$') = CAST(? AS REAL)"#,
+        ),
+        _ => (
+            r#""value" #>> '{}' = $1"#,
+            r#"jsonb_typeof("value") = 'number' AND ("value" #>> '{}')::numeric = $1::numeric"#,
+        ),
+    };
+    let mut condition = Condition::all();
+
+    for (name, accepted) in filters {
+        let mut matches_value = Condition::any();
+        for value in accepted {
+            matches_value = matches_value.add(Expr::cust_with_values(as_text, [value.clone()]));
+            if BigDecimal::from_str(value).is_ok() {
+                matches_value = matches_value.add(Expr::cust_with_values(equals_number, [value.clone()]));
+            }
+        }
+
+        let matching_products = Query::select()
+            .column(ProductAttributeColumn::ProductId)
+            .from(ProductAttribute)
+            .and_where(ProductAttributeColumn::Name.eq(name.as_str()))
+            .cond_where(matches_value)
+            .to_owned();
+        condition = condition.add(ProductColumn::Id.in_subquery(matching_products));
+    }
+
+    condition
+}
+
+/// Check that a definition's allowed values and unit suit its type
+fn check_definition(value_type: AttributeType, values: Option<&[String]>, unit: Option<&str>) -> Result<(), ApiError> {
+    match (value_type, values) {
+        (AttributeType::Enum, None) => return Err(ApiError::bad_request("values: required for enum attributes")),
+        (AttributeType::Enum, Some([])) => return Err(ApiError::bad_request("values: must not be empty")),
+        (AttributeType::Enum, Some(_)) => {}
+        (_, Some(_)) => return Err(ApiError::bad_request("values: only allowed for enum attributes")),
+        (_, None) => {}
+    }
+
+    match (value_type, unit) {
+        (AttributeType::Unit, None) => Err(ApiError::bad_request("unit: required for unit attributes")),
+        (AttributeType::Unit, Some(_)) | (_, None) => Ok(()),
+        (_, Some(_)) => Err(ApiError::bad_request("unit: only allowed for unit attributes")),
+    }
+}
+
+/// Check a single value against a definition
+fn check_value(definition: &AttributeDefinitionModel, value: &serde_json::Value) -> Result<(), String> {
+    let value_type = definition
+        .value_type
+        .parse::<AttributeType>()
+        .map_err(|e| format!("definition is invalid: {}", e))?;
+
+    match (value_type, value) {
+        (AttributeType::String, serde_json::Value::String(text)) => {
+            if text.chars().count() > MAX_STRING_VALUE_CHARS {
+                Err(format!("must be at most {} characters", MAX_STRING_VALUE_CHARS))
+            } else {
+                Ok(())
+            }
+        }
+        (AttributeType::Number | AttributeType::Unit, serde_json::Value::Number(_)) => Ok(()),
+        (AttributeType::Bool, serde_json::Value::Bool(_)) => Ok(()),
+        (AttributeType::Enum, serde_json::Value::String(text)) => {
+            let values = allowed_values(definition).unwrap_or_default();
+            if values.contains(text) {
+                Ok(())
+            } else {
+                Err(format!("must be one of {}", values.join(", ")))
+            }
+        }
+        (AttributeType::String | AttributeType::Enum, _) => Err("must be a string".to_string()),
+        (AttributeType::Number | AttributeType::Unit, _) => Err("must be a number".to_string()),
+        (AttributeType::Bool, _) => Err("must be true or false".to_string()),
+    }
+}
+
+fn parse_type(definition: &AttributeDefinitionModel) -> Result<AttributeType, ApiError> {
+    definition
+        .value_type
+        .parse()
+        .map_err(|e| ApiError::internal_server_error(format!("Invalid attribute definition: {}", e)))
+}
+
+fn allowed_values(definition: &AttributeDefinitionModel) -> Option<Vec<String>> {
+    definition
+        .allowed_values
+        .clone()
+        .and_then(|values| serde_json::from_value(values).ok())
+}
@@ -1,7 +1,6 @@
 use std::str::FromStr;
 
 use anyhow::Result;
-use bigdecimal::BigDecimal;
 use chrono::{FixedOffset, Utc};
 use sea_orm::prelude::DateTimeWithTimeZone;
 use sea_orm::{
@@ -9,41 +8,77 @@ use sea_orm::{
     QuerySelect, RelationTrait, Set, TransactionTrait,
 };
 
+use crate::cache::{CatalogCache, keys};
 use crate::database::DatabaseConnection;
 use crate::entity::{
-    Category, CategoryActiveModel, CategoryColumn, CategoryModel, CategoryRelation, Product, ProductCategory,
-    ProductCategoryColumn, ProductCategoryModel, ProductColumn, ProductModel, ProductRelation,
+    AttributeDefinition, AttributeDefinitionColumn, Category, CategoryActiveModel, CategoryColumn, CategoryModel,
+    CategoryRelation, Product, ProductCategory, ProductCategoryActiveModel, ProductCategoryColumn,
+    ProductCategoryModel, ProductColumn, ProductModel, ProductRelation,
 };
 use crate::error::ApiError;
+use crate::locale::LocaleChain;
+use crate::metrics;
 use crate::models::category::{
     CategoryListResponse, CategoryQueryParams, CategoryResponse, CategoryWithProductsResponse, CreateCategoryRequest,
     UpdateCategoryRequest,
 };
+use crate::models::lifecycle::ProductStatus;
 use crate::models::product::ProductResponse;
+use crate::repository::attribute::prune_product_attributes;
+use crate::repository::product::product_responses;
+use crate::repository::translation::{get_category_texts, localize_products};
+use crate::tenant::Tenant;
 
-/// Repository for category operations
+/// Repository for category operations within one tenant's catalog
 #[derive(Clone)]
 pub struct CategoryRepository {
     conn: DatabaseConnection,
+    cache: CatalogCache,
+    tenant: Tenant,
+    locales: LocaleChain,
 }
 
 impl CategoryRepository {
-    /// Create a new category repository
-    pub fn new(conn: DatabaseConnection) -> Self {
-        Self { conn }
+    /// Create a new category repository working on the default tenant
+    pub fn new(conn: DatabaseConnection, cache: CatalogCache) -> Self {
+        Self {
+            conn,
+            cache,
+            tenant: Tenant::default(),
+            locales: LocaleChain::default(),
+        }
+    }
+
+    /// A copy of this repository working on `tenant`'s catalog
+    pub fn for_tenant(&self, tenant: &Tenant) -> Self {
+        Self {
+            tenant: tenant.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// A copy of this repository reading category and product text along `locales`
+    pub fn for_locale(&self, locales: &LocaleChain) -> Self {
+        Self {
+            locales: locales.clone(),
+            ..self.clone()
+        }
     }
 
     /// Create a new category
     pub async fn create_category(&self, req: CreateCategoryRequest) -> Result<CategoryResponse, ApiError> {
+        let tenant = self.tenant.clone();
+
         // Using Sea-ORM's transaction
-        let result = self
-            .conn
-            .transaction(|txn| {
+        let result = metrics::time_transaction(
+            "create_category",
+            self.conn.transaction(|txn| {
                 Box::pin(async move {
                     // Create category active model
                     let category = CategoryActiveModel {
                         name: Set(req.name.clone()),
                         description: Set(req.description.clone()),
+                        tenant_id: Set(tenant.to_string()),
                         ..Default::default()
                     };
 
@@ -58,38 +93,100 @@ impl CategoryRepository {
                         updated_at: category_model.updated_at,
                     })
                 })
-            })
-            .await
-            .map_err(|e| match e {
-                sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
-                sea_orm::TransactionError::Transaction(api_err) => api_err,
-            })?;
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })?;
+
+        self.cache.invalidate(keys::category_lists(&self.tenant)).await;
 
         Ok(result)
     }
 
-    /// Get a category by ID
+    /// Get a category by ID, translated along the repository's locales
     pub async fn get_category(&self, id: i32) -> Result<CategoryResponse, ApiError> {
+        let mut category = self.get_untranslated_category(id).await?;
+        let mut texts = get_category_texts(&[id], &self.locales, &self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+        if let Some(text) = texts.remove(&id) {
+            text.apply(&mut category.name, &mut category.description);
+        }
+
+        Ok(category)
+    }
+
+    /// Get a category by ID in the default locale, as cached
+    async fn get_untranslated_category(&self, id: i32) -> Result<CategoryResponse, ApiError> {
+        let cache_key = keys::category(&self.tenant, id);
+        if let Some(category) = self.cache.get(&cache_key).await {
+            return Ok(category);
+        }
+
         // Find category by ID
         let category = Category::find_by_id(id)
+            .filter(CategoryColumn::TenantId.eq(self.tenant.as_str()))
             .one(&self.conn)
             .await
             .map_err(ApiError::Database)?
             .ok_or_else(|| ApiError::not_found_simple("Category not found"))?;
 
         // Create the response directly without timezone conversion
-        Ok(CategoryResponse {
+        let response = CategoryResponse {
             id: category.id,
             name: category.name,
             description: category.description,
             created_at: category.created_at,
             updated_at: category.updated_at,
-        })
+        };
+
+        self.cache.put(&cache_key, &response).await;
+
+        Ok(response)
+    }
+
+    /// List all categories, translated along the repository's locales and ordered by name
+    ///
+    /// Product counts only include published products unless `include_unpublished` is set.
+    pub async fn list_categories(
+        &self,
+        params: CategoryQueryParams,
+        include_unpublished: bool,
+    ) -> Result<CategoryListResponse, ApiError> {
+        let mut response = self.list_untranslated_categories(params, include_unpublished).await?;
+
+        let ids: Vec<i32> = response.categories.iter().map(|category| category.id).collect();
+        let mut texts = get_category_texts(&ids, &self.locales, &self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+        if !texts.is_empty() {
+            for category in &mut response.categories {
+                if let Some(text) = texts.remove(&category.id) {
+                    text.apply(&mut category.name, &mut category.description);
+                }
+            }
+            response.categories.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+
+        Ok(response)
     }
 
-    /// List all categories
-    pub async fn list_categories(&self, params: CategoryQueryParams) -> Result<CategoryListResponse, ApiError> {
+    /// List all categories in the default locale, as cached
+    async fn list_untranslated_categories(
+        &self,
+        params: CategoryQueryParams,
+        include_unpublished: bool,
+    ) -> Result<CategoryListResponse, ApiError> {
+        let cache_key = keys::category_list(&self.tenant, params.include_product_count(), include_unpublished);
+        if let Some(response) = self.cache.get(&cache_key).await {
+            return Ok(response);
+        }
+
         let categories = Category::find()
+            .filter(CategoryColumn::TenantId.eq(self.tenant.as_str()))
             .order_by_asc(CategoryColumn::Name)
             .all(&self.conn)
             .await
@@ -100,7 +197,8 @@ impl CategoryRepository {
         for category in categories {
             // If requested, get product count for each category
             let product_count = if params.include_product_count() {
-                self.count_products_in_category(category.id).await?
+                self.count_products_in_category(category.id, include_unpublished)
+                    .await?
             } else {
                 0 // Default value if not requested
             };
@@ -115,20 +213,27 @@ impl CategoryRepository {
             });
         }
 
-        Ok(CategoryListResponse {
+        let response = CategoryListResponse {
             categories: category_responses,
-        })
+        };
+
+        self.cache.put(&cache_key, &response).await;
+
+        Ok(response)
     }
 
     /// Update a category
     pub async fn update_category(&self, id: i32, req: UpdateCategoryRequest) -> Result<CategoryResponse, ApiError> {
+        let tenant = self.tenant.clone();
+
         // Using Sea-ORM's transaction
-        let result = self
-            .conn
-            .transaction(|txn| {
+        let (result, affected_product_ids) = metrics::time_transaction(
+            "update_category",
+            self.conn.transaction(|txn| {
                 Box::pin(async move {
                     // Find category by ID
                     let category = Category::find_by_id(id)
+                        .filter(CategoryColumn::TenantId.eq(tenant.as_str()))
                         .one(txn)
                         .await
                         .map_err(ApiError::Database)?
@@ -137,6 +242,14 @@ impl CategoryRepository {
                     // Create active model for update
                     let mut category_active: CategoryActiveModel = category.clone().into();
 
+                    // Products embed the category name, so a rename makes their cached responses stale
+                    let renamed = req.name.as_ref().is_some_and(|name| *name != category.name);
+                    let affected_product_ids = if renamed {
+                        Self::get_category_product_ids(id, txn).await?
+                    } else {
+                        Vec::new()
+                    };
+
                     // Update fields if provided
                     if let Some(name) = req.name {
                         category_active.name = Set(name);
@@ -149,32 +262,41 @@ impl CategoryRepository {
                     // Update the category
                     let category_model = category_active.update(txn).await.map_err(ApiError::Database)?;
 
-                    Ok(CategoryResponse {
+                    let response = CategoryResponse {
                         id: category_model.id,
                         name: category_model.name,
                         description: category_model.description,
                         created_at: category_model.created_at,
                         updated_at: category_model.updated_at,
-                    })
+                    };
+
+                    Ok((response, affected_product_ids))
                 })
-            })
-            .await
-            .map_err(|e| match e {
-                sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
-                sea_orm::TransactionError::Transaction(api_err) => api_err,
-            })?;
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })?;
+
+        self.invalidate_category(id, &affected_product_ids).await;
 
         Ok(result)
     }
 
     /// Delete a category
     pub async fn delete_category(&self, id: i32) -> Result<(), ApiError> {
+        let tenant = self.tenant.clone();
+
         // Using Sea-ORM's transaction
-        self.conn
-            .transaction(|txn| {
+        let affected_product_ids = metrics::time_transaction(
+            "delete_category",
+            self.conn.transaction(|txn| {
                 Box::pin(async move {
                     // Check if category exists
                     let category_exists = Category::find_by_id(id)
+                        .filter(CategoryColumn::TenantId.eq(tenant.as_str()))
                         .one(txn)
                         .await
                         .map_err(ApiError::Database)?
@@ -184,6 +306,8 @@ impl CategoryRepository {
                         return Err(ApiError::not_found_simple("Category not found"));
                     }
 
+                    let affected_product_ids = Self::get_category_product_ids(id, txn).await?;
+
                     // Delete product categories
                     ProductCategory::delete_many()
                         .filter(ProductCategoryColumn::CategoryId.eq(id))
@@ -191,23 +315,140 @@ impl CategoryRepository {
                         .await
                         .map_err(ApiError::Database)?;
 
-                    // Delete category
+                    // Delete category; its attribute definitions cascade, so drop the values they covered
                     Category::delete_by_id(id).exec(txn).await.map_err(ApiError::Database)?;
+                    prune_product_attributes(&affected_product_ids, txn)
+                        .await
+                        .map_err(ApiError::Database)?;
 
-                    Ok(())
+                    Ok(affected_product_ids)
                 })
-            })
-            .await
-            .map_err(|e| match e {
-                sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
-                sea_orm::TransactionError::Transaction(api_err) => api_err,
-            })
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })?;
+
+        self.invalidate_category(id, &affected_product_ids).await;
+
+        Ok(())
+    }
+
+    /// Merge one category into another
+    ///
+    /// Products in `source_id` are moved to `target_id`, and the source category is deleted. The
+    /// source's attribute definitions move too, except those whose name the target already defines.
+    pub async fn merge_categories(&self, source_id: i32, target_id: i32) -> Result<CategoryResponse, ApiError> {
+        if source_id == target_id {
+            return Err(ApiError::bad_request("Cannot merge a category into itself"));
+        }
+
+        let tenant = self.tenant.clone();
+
+        // Using Sea-ORM's transaction
+        let (result, affected_product_ids) = metrics::time_transaction(
+            "merge_categories",
+            self.conn.transaction(|txn| {
+                Box::pin(async move {
+                    // Check that both categories exist
+                    Category::find_by_id(source_id)
+                        .filter(CategoryColumn::TenantId.eq(tenant.as_str()))
+                        .one(txn)
+                        .await
+                        .map_err(ApiError::Database)?
+                        .ok_or_else(|| ApiError::not_found("Category", source_id))?;
+                    let target = Category::find_by_id(target_id)
+                        .filter(CategoryColumn::TenantId.eq(tenant.as_str()))
+                        .one(txn)
+                        .await
+                        .map_err(ApiError::Database)?
+                        .ok_or_else(|| ApiError::not_found("Category", target_id))?;
+
+                    let affected_product_ids = Self::get_category_product_ids(source_id, txn).await?;
+                    let existing_product_ids = Self::get_category_product_ids(target_id, txn).await?;
+
+                    // Link the source's products to the target, skipping those already there
+                    for product_id in &affected_product_ids {
+                        if existing_product_ids.contains(product_id) {
+                            continue;
+                        }
+
+                        let product_category = ProductCategoryActiveModel {
+                            product_id: Set(*product_id),
+                            category_id: Set(target_id),
+                            tenant_id: Set(tenant.to_string()),
+                        };
+                        product_category.insert(txn).await.map_err(ApiError::Database)?;
+                    }
+
+                    // Move attribute definitions the target lacks
+                    let target_names: Vec<String> = AttributeDefinition::find()
+                        .filter(AttributeDefinitionColumn::CategoryId.eq(target_id))
+                        .all(txn)
+                        .await
+                        .map_err(ApiError::Database)?
+                        .into_iter()
+                        .map(|definition| definition.name)
+                        .collect();
+                    AttributeDefinition::update_many()
+                        .col_expr(AttributeDefinitionColumn::CategoryId, target_id.into())
+                        .filter(AttributeDefinitionColumn::CategoryId.eq(source_id))
+                        .filter(AttributeDefinitionColumn::Name.is_not_in(target_names))
+                        .exec(txn)
+                        .await
+                        .map_err(ApiError::Database)?;
+
+                    // Delete the source category and its links
+                    ProductCategory::delete_many()
+                        .filter(ProductCategoryColumn::CategoryId.eq(source_id))
+                        .exec(txn)
+                        .await
+                        .map_err(ApiError::Database)?;
+                    Category::delete_by_id(source_id)
+                        .exec(txn)
+                        .await
+                        .map_err(ApiError::Database)?;
+                    prune_product_attributes(&affected_product_ids, txn)
+                        .await
+                        .map_err(ApiError::Database)?;
+
+                    let response = CategoryResponse {
+                        id: target.id,
+                        name: target.name,
+                        description: target.description,
+                        created_at: target.created_at,
+                        updated_at: target.updated_at,
+                    };
+
+                    Ok((response, affected_product_ids))
+                })
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })?;
+
+        self.invalidate_category(source_id, &affected_product_ids).await;
+        self.invalidate_category(target_id, &[]).await;
+
+        Ok(result)
     }
 
     /// Get products by category ID
-    pub async fn get_products_by_category(&self, category_id: i32) -> Result<Vec<ProductResponse>, ApiError> {
+    ///
+    /// Only published products are returned unless `include_unpublished` is set.
+    pub async fn get_products_by_category(
+        &self,
+        category_id: i32,
+        include_unpublished: bool,
+    ) -> Result<Vec<ProductResponse>, ApiError> {
         // First check if category exists
         let category_exists = Category::find_by_id(category_id)
+            .filter(CategoryColumn::TenantId.eq(self.tenant.as_str()))
             .one(&self.conn)
             .await
             .map_err(ApiError::Database)?
@@ -218,74 +459,60 @@ impl CategoryRepository {
         }
 
         // Find all products in this category using the product_categories relation
-        let products = Product::find()
+        let mut query = Product::find()
             .join(sea_orm::JoinType::InnerJoin, ProductRelation::ProductCategories.def())
-            .filter(ProductCategoryColumn::CategoryId.eq(category_id))
-            .all(&self.conn)
+            .filter(ProductCategoryColumn::CategoryId.eq(category_id));
+        if !include_unpublished {
+            query = query.filter(ProductColumn::Status.eq(ProductStatus::Published.as_str()));
+        }
+        let products = query.all(&self.conn).await.map_err(ApiError::Database)?;
+
+        let mut product_responses = product_responses(products, &self.conn).await?;
+
+        localize_products(&mut product_responses, &self.locales, &self.conn)
             .await
             .map_err(ApiError::Database)?;
 
-        // Convert to product response objects
-        let mut product_responses = Vec::with_capacity(products.len());
-
-        for product in products {
-            // Get categories for each product
-            let categories = self.get_product_categories(product.id).await?;
-
-            // Convert price to BigDecimal
-            let price_str = product.price.to_string();
-            let price = BigDecimal::from_str(&price_str)
-                .map_err(|_| ApiError::internal_server_error("Invalid price format"))?;
-
-            product_responses.push(ProductResponse {
-                id: product.id,
-                name: product.name,
-                description: product.description,
-                price,
-                sku: product.sku,
-                categories,
-                created_at: product.created_at,
-                updated_at: product.updated_at,
-            });
-        }
-
         Ok(product_responses)
     }
 
-    /// Helper method to count products in a category
-    async fn count_products_in_category(&self, category_id: i32) -> Result<i64, ApiError> {
-        // Count products using the product_categories relation
-        let count = ProductCategory::find()
+    /// Helper method to drop cached entries touched by a category change
+    async fn invalidate_category(&self, id: i32, affected_product_ids: &[i32]) {
+        let mut stale = keys::category_lists(&self.tenant);
+        stale.push(keys::category(&self.tenant, id));
+        stale.extend(
+            affected_product_ids
+                .iter()
+                .map(|product_id| keys::product(&self.tenant, *product_id)),
+        );
+        self.cache.invalidate(stale).await;
+    }
+
+    /// Helper method to get the IDs of products in a category
+    async fn get_category_product_ids(
+        category_id: i32,
+        executor: &impl sea_orm::ConnectionTrait,
+    ) -> Result<Vec<i32>, ApiError> {
+        let links = ProductCategory::find()
             .filter(ProductCategoryColumn::CategoryId.eq(category_id))
-            .count(&self.conn)
+            .all(executor)
             .await
             .map_err(ApiError::Database)?;
 
-        Ok(count as i64)
+        Ok(links.into_iter().map(|link| link.product_id).collect())
     }
 
-    /// Helper method to get product categories (used for product responses)
-    async fn get_product_categories(
-        &self,
-        product_id: i32,
-    ) -> Result<Vec<crate::models::product::CategoryBrief>, ApiError> {
-        // Using Sea-ORM relations to fetch related categories
-        let categories = Category::find()
-            .join(sea_orm::JoinType::InnerJoin, CategoryRelation::ProductCategories.def())
-            .filter(ProductCategoryColumn::ProductId.eq(product_id))
-            .all(&self.conn)
-            .await
-            .map_err(ApiError::Database)?;
-
-        // Map to CategoryBrief
-        let category_briefs = categories
-            .into_iter()
-            .map(|category| crate::models::product::CategoryBrief {
-                id: category.id,
-                name: category.name,
-            })
-            .collect();
+    /// Helper method to count products in a category, only published ones unless `include_unpublished` is set
+    async fn count_products_in_category(&self, category_id: i32, include_unpublished: bool) -> Result<i64, ApiError> {
+        // Count products using the product_categories relation
+        let mut query = Product::find()
+            .join(sea_orm::JoinType::InnerJoin, ProductRelation::ProductCategories.def())
+            .filter(ProductCategoryColumn::CategoryId.eq(category_id));
+        if !include_unpublished {
+            query = query.filter(ProductColumn::Status.eq(ProductStatus::Published.as_str()));
+        }
+        let count = query.count(&self.conn).await.map_err(ApiError::Database)?;
 
-        Ok(category_briefs)
+        Ok(count as i64)
     }
 }
@@ -0,0 +1,152 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use sea_orm::sea_query::{CaseStatement, Expr, SelectStatement};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QuerySelect, RelationTrait};
+
+use crate::entity::{
+    Category, CategoryColumn, CategoryRelation, Product, ProductAttribute, ProductAttributeColumn,
+    ProductCategoryColumn, ProductColumn,
+};
+use crate::models::facet::{AttributeFacet, CategoryFacet, Facet, PriceRangeFacet, ProductFacets};
+use crate::models::money::Currency;
+
+/// Count the requested facets over the products whose IDs `product_ids` selects
+///
+/// Each facet is a single grouped query. Category and attribute counts are ordered by descending
+/// count, then by name or value; price ranges follow `price_buckets` and include empty buckets.
+pub async fn count_facets(
+    facets: &[Facet],
+    product_ids: &SelectStatement,
+    price_buckets: &[f64],
+    executor: &impl ConnectionTrait,
+) -> Result<ProductFacets, DbErr> {
+    let mut counts = ProductFacets::default();
+
+    for facet in facets {
+        match facet {
+            Facet::Category => {
+                counts.category = Some(count_categories(product_ids, executor).await?);
+            }
+            Facet::PriceRange => {
+                counts.price_range = Some(count_price_ranges(product_ids, price_buckets, executor).await?);
+            }
+            Facet::Attribute(name) => {
+                let values = count_attribute_values(name, product_ids, executor).await?;
+                counts.attributes.insert(name.clone(), values);
+            }
+        }
+    }
+
+    Ok(counts)
+}
+
+async fn count_categories(
+    product_ids: &SelectStatement,
+    executor: &impl ConnectionTrait,
+) -> Result<Vec<CategoryFacet>, DbErr> {
+    let rows: Vec<(i32, String, i64)> = Category::find()
+        .select_only()
+        .column(CategoryColumn::Id)
+        .column(CategoryColumn::Name)
+        .column_as(ProductCategoryColumn::ProductId.count(), "count")
+        .join(sea_orm::JoinType::InnerJoin, CategoryRelation::ProductCategories.def())
+        .filter(ProductCategoryColumn::ProductId.in_subquery(product_ids.clone()))
+        .group_by(CategoryColumn::Id)
+        .group_by(CategoryColumn::Name)
+        .into_tuple()
+        .all(executor)
+        .await?;
+
+    let mut facets: Vec<CategoryFacet> = rows
+        .into_iter()
+        .map(|(id, name, count)| CategoryFacet { id, name, count })
+        .collect();
+    facets.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(facets)
+}
+
+async fn count_price_ranges(
+    product_ids: &SelectStatement,
+    price_buckets: &[f64],
+    executor: &impl ConnectionTrait,
+) -> Result<Vec<PriceRangeFacet>, DbErr> {
+    let bounds: Vec<BigDecimal> = price_buckets
+        .iter()
+        .map(|bound| BigDecimal::from_str(&bound.to_string()))
+        .collect::<Result<_, _>>()
+        .map_err(|e| DbErr::Custom(format!("Invalid price bucket: {}", e)))?;
+
+    // Bucket i holds prices below bounds[i]; the last bucket holds the rest
+    let mut bucket = CaseStatement::new();
+    for (i, bound) in bounds.iter().enumerate() {
+        bucket = bucket.case(ProductColumn::Price.lt(bound.clone()), Expr::val(i as i32));
+    }
+    let bucket: Expr = bucket.finally(Expr::val(bounds.len() as i32)).into();
+
+    let rows: Vec<(String, i32, i64)> = Product::find()
+        .select_only()
+        .column(ProductColumn::Currency)
+        .column_as(bucket, "bucket")
+        .column_as(ProductColumn::Id.count(), "count")
+        .filter(ProductColumn::Id.in_subquery(product_ids.clone()))
+        .group_by(ProductColumn::Currency)
+        .group_by(Expr::cust("\"bucket\""))
+        .into_tuple()
+        .all(executor)
+        .await?;
+
+    // Amounts in different currencies aren't comparable, so every currency gets its own ranges
+    let mut currencies: Vec<Currency> = rows
+        .iter()
+        .map(|(currency, _, _)| Currency::from_str(currency).map_err(DbErr::Custom))
+        .collect::<Result<_, _>>()?;
+    currencies.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    currencies.dedup();
+
+    let facets = currencies
+        .into_iter()
+        .flat_map(|currency| (0..=bounds.len()).map(move |i| (currency, i)))
+        .map(|(currency, i)| PriceRangeFacet {
+            currency,
+            min: i.checked_sub(1).map(|lower| bounds[lower].clone()),
+            max: bounds.get(i).cloned(),
+            count: rows
+                .iter()
+                .find(|(code, bucket, _)| code.as_str() == currency.as_str() && *bucket as usize == i)
+                .map_or(0, |(_, _, count)| *count),
+        })
+        .collect();
+
+    Ok(facets)
+}
+
+async fn count_attribute_values(
+    name: &str,
+    product_ids: &SelectStatement,
+    executor: &impl ConnectionTrait,
+) -> Result<Vec<AttributeFacet>, DbErr> {
+    let rows: Vec<(serde_json::Value, i64)> = ProductAttribute::find()
+        .select_only()
+        .column(ProductAttributeColumn::Value)
+        .column_as(ProductAttributeColumn::ProductId.count(), "count")
+        .filter(ProductAttributeColumn::Name.eq(name))
+        .filter(ProductAttributeColumn::ProductId.in_subquery(product_ids.clone()))
+        .group_by(ProductAttributeColumn::Value)
+        .into_tuple()
+        .all(executor)
+        .await?;
+
+    let mut facets: Vec<AttributeFacet> = rows
+        .into_iter()
+        .map(|(value, count)| AttributeFacet { value, count })
+        .collect();
+    facets.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.value.to_string().cmp(&b.value.to_string()))
+    });
+
+    Ok(facets)
+}
@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter, Set};
+
+use crate::config::IdempotencyConfig;
+use crate::database::DatabaseConnection;
+use crate::entity::{IdempotencyKey, IdempotencyKeyActiveModel, IdempotencyKeyColumn};
+use crate::error::ApiError;
+use crate::tenant::Tenant;
+
+/// A response recorded for an idempotency key
+#[derive(Clone, Debug, PartialEq)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// Outcome of claiming an idempotency key for a request
+#[derive(Debug, PartialEq)]
+pub enum Claim {
+    /// The key was free; the request should run and its response be recorded
+    Acquired,
+    /// Another request with the key is still running
+    InProgress,
+    /// The key was used for a different request
+    Mismatch,
+    /// The request already ran; its response should be replayed
+    Completed(StoredResponse),
+}
+
+/// Repository for idempotency keys within one tenant's catalog
+///
+/// Keys are scoped to the client that sent them, so two clients can't see each other's responses.
+/// A claim expires after `lock_timeout` if its request never completes, e.g. because the process
+/// crashed, and a recorded response after `ttl`.
+#[derive(Clone)]
+pub struct IdempotencyRepository {
+    conn: DatabaseConnection,
+    ttl: Duration,
+    lock_timeout: Duration,
+    tenant: Tenant,
+}
+
+impl IdempotencyRepository {
+    /// Create a new idempotency key repository working on the default tenant
+    pub fn new(conn: DatabaseConnection, config: &IdempotencyConfig, lock_timeout: Duration) -> Self {
+        Self {
+            conn,
+            ttl: Duration::from_secs(config.ttl_secs),
+            lock_timeout,
+            tenant: Tenant::default(),
+        }
+    }
+
+    /// A copy of this repository working on `tenant`'s catalog
+    pub fn for_tenant(&self, tenant: &Tenant) -> Self {
+        Self {
+            tenant: tenant.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// Claim `key` for a request whose method, path and body hash to `request_hash`
+    pub async fn claim(&self, scope: &str, key: &str, request_hash: &str) -> Result<Claim, ApiError> {
+        let now = Utc::now();
+
+        // Expired entries are as good as absent
+        IdempotencyKey::delete_many()
+            .filter(IdempotencyKeyColumn::TenantId.eq(self.tenant.as_str()))
+            .filter(IdempotencyKeyColumn::Scope.eq(scope))
+            .filter(IdempotencyKeyColumn::IdempotencyKey.eq(key))
+            .filter(IdempotencyKeyColumn::ExpiresAt.lte(now))
+            .exec(&self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+
+        // Only one of several concurrent requests inserts the row
+        let claim = IdempotencyKeyActiveModel {
+            tenant_id: Set(self.tenant.to_string()),
+            scope: Set(scope.to_string()),
+            idempotency_key: Set(key.to_string()),
+            request_hash: Set(request_hash.to_string()),
+            created_at: Set(now.into()),
+            expires_at: Set((now + self.lock_timeout).into()),
+            ..Default::default()
+        };
+        let inserted = IdempotencyKey::insert(claim)
+            .on_conflict(
+                OnConflict::columns([
+                    IdempotencyKeyColumn::TenantId,
+                    IdempotencyKeyColumn::Scope,
+                    IdempotencyKeyColumn::IdempotencyKey,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec_without_returning(&self.conn)
+            .await;
+        match inserted {
+            Ok(1) => return Ok(Claim::Acquired),
+            Ok(_) | Err(DbErr::RecordNotInserted) => {}
+            Err(e) => return Err(ApiError::Database(e)),
+        }
+
+        let existing = IdempotencyKey::find_by_id((self.tenant.to_string(), scope.to_string(), key.to_string()))
+            .one(&self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+
+        // A row that vanished since the insert expired in between; its request is as good as running
+        let Some(existing) = existing else {
+            return Ok(Claim::InProgress);
+        };
+        if existing.request_hash != request_hash {
+            return Ok(Claim::Mismatch);
+        }
+
+        Ok(match existing.response_status {
+            Some(status) => Claim::Completed(StoredResponse {
+                status: status as u16,
+                content_type: existing.response_content_type,
+                body: existing.response_body.unwrap_or_default(),
+            }),
+            None => Claim::InProgress,
+        })
+    }
+
+    /// Record the response to a claimed key, keeping it for the configured TTL
+    pub async fn complete(&self, scope: &str, key: &str, response: &StoredResponse) -> Result<(), ApiError> {
+        let update = IdempotencyKeyActiveModel {
+            response_status: Set(Some(i32::from(response.status))),
+            response_content_type: Set(response.content_type.clone()),
+            response_body: Set(Some(response.body.clone())),
+            expires_at: Set((Utc::now() + self.ttl).into()),
+            ..Default::default()
+        };
+        IdempotencyKey::update_many()
+            .set(update)
+            .filter(IdempotencyKeyColumn::TenantId.eq(self.tenant.as_str()))
+            .filter(IdempotencyKeyColumn::Scope.eq(scope))
+            .filter(IdempotencyKeyColumn::IdempotencyKey.eq(key))
+            .exec(&self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(())
+    }
+
+    /// Give up a claimed key without recording a response, so the request can be retried
+    pub async fn release(&self, scope: &str, key: &str) -> Result<(), ApiError> {
+        IdempotencyKey::delete_many()
+            .filter(IdempotencyKeyColumn::TenantId.eq(self.tenant.as_str()))
+            .filter(IdempotencyKeyColumn::Scope.eq(scope))
+            .filter(IdempotencyKeyColumn::IdempotencyKey.eq(key))
+            .filter(IdempotencyKeyColumn::ResponseStatus.is_null())
+            .exec(&self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(())
+    }
+
+    /// Delete expired keys of every tenant, returning how many were removed
+    pub async fn purge_expired(&self) -> Result<u64, ApiError> {
+        let result = IdempotencyKey::delete_many()
+            .filter(IdempotencyKeyColumn::ExpiresAt.lte(Utc::now()))
+            .exec(&self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(result.rows_affected)
+    }
+}
@@ -0,0 +1,581 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use imagesize::ImageType;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::cache::{CatalogCache, keys};
+use crate::config::{DerivativeConfig, StorageConfig};
+use crate::database::DatabaseConnection;
+use crate::derivative;
+use crate::entity::{
+    MediaDerivative, MediaDerivativeActiveModel, MediaDerivativeColumn, MediaDerivativeModel, Product, ProductColumn,
+    ProductMedia, ProductMediaActiveModel, ProductMediaColumn, ProductMediaModel,
+};
+use crate::error::ApiError;
+use crate::metrics;
+use crate::models::lifecycle::ProductStatus;
+use crate::models::media::{DerivativeResponse, MediaFile, MediaResponse, UploadMediaRequest};
+use crate::storage::MediaStorage;
+use crate::tenant::Tenant;
+
+/// Repository for the media of one tenant's products
+///
+/// File contents live in the configured [`MediaStore`](crate::storage::MediaStore); the database
+/// holds their metadata and storage keys. Resized derivatives of images are stored next to the
+/// originals and recorded in `media_derivatives`, so they are removed along with them.
+#[derive(Clone)]
+pub struct MediaRepository {
+    conn: DatabaseConnection,
+    cache: CatalogCache,
+    storage: MediaStorage,
+    max_upload_bytes: usize,
+    allowed_types: Vec<String>,
+    derivatives: Vec<DerivativeConfig>,
+    eager_derivatives: bool,
+    tenant: Tenant,
+}
+
+impl MediaRepository {
+    /// Create a new media repository enforcing the configured upload limits and derivatives, working
+    /// on the default tenant
+    pub fn new(conn: DatabaseConnection, cache: CatalogCache, storage: MediaStorage, config: &StorageConfig) -> Self {
+        Self {
+            conn,
+            cache,
+            storage,
+            max_upload_bytes: config.max_upload_bytes,
+            allowed_types: config.allowed_types.clone(),
+            derivatives: config.derivatives.clone(),
+            eager_derivatives: config.eager_derivatives,
+            tenant: Tenant::default(),
+        }
+    }
+
+    /// A copy of this repository working on `tenant`'s catalog
+    pub fn for_tenant(&self, tenant: &Tenant) -> Self {
+        Self {
+            tenant: tenant.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// Largest accepted file, in bytes
+    pub fn max_upload_bytes(&self) -> usize {
+        self.max_upload_bytes
+    }
+
+    /// Store a file and attach it to a product
+    pub async fn upload_media(&self, product_id: i32, req: UploadMediaRequest) -> Result<MediaResponse, ApiError> {
+        if req.data.is_empty() {
+            return Err(ApiError::bad_request("Uploaded file is empty"));
+        }
+        if req.data.len() > self.max_upload_bytes {
+            return Err(ApiError::PayloadTooLarge(format!(
+                "File exceeds the maximum upload size of {} bytes",
+                self.max_upload_bytes
+            )));
+        }
+
+        // Trust the contents rather than the declared type
+        let (mime_type, dimensions) = match sniff_image(&req.data) {
+            Some((mime_type, width, height)) => (mime_type.to_string(), Some((width, height))),
+            None => match req.declared_type.as_deref() {
+                Some(declared) if declared.starts_with("image/") => {
+                    return Err(ApiError::UnsupportedMediaType(format!(
+                        "File is not a valid {} image",
+                        declared
+                    )));
+                }
+                Some(declared) => (declared.to_string(), None),
+                None => ("application/octet-stream".to_string(), None),
+            },
+        };
+        if !self.allowed_types.contains(&mime_type) {
+            return Err(ApiError::UnsupportedMediaType(format!(
+                "Files of type {} are not accepted; allowed types are {}",
+                mime_type,
+                self.allowed_types.join(", ")
+            )));
+        }
+
+        self.check_product(product_id, true).await?;
+
+        let storage_key = format!(
+            "products/{}/{}.{}",
+            product_id,
+            Uuid::new_v4().simple(),
+            extension(&mime_type)
+        );
+        let checksum = hex::encode(Sha256::digest(&req.data));
+        let size_bytes = req.data.len() as i64;
+        let original = req.data.clone();
+
+        self.storage
+            .put(&storage_key, req.data, &mime_type)
+            .await
+            .map_err(|e| self.storage_error("store", &storage_key, e))?;
+
+        let key = storage_key.clone();
+        let result = metrics::time_transaction(
+            "upload_media",
+            self.conn.transaction(|txn| {
+                Box::pin(async move {
+                    let sort_order = match req.sort_order {
+                        Some(sort_order) => sort_order,
+                        None => {
+                            let last: Option<Option<i32>> = ProductMedia::find()
+                                .select_only()
+                                .column_as(ProductMediaColumn::SortOrder.max(), "max")
+                                .filter(ProductMediaColumn::ProductId.eq(product_id))
+                                .into_tuple()
+                                .one(txn)
+                                .await
+                                .map_err(ApiError::Database)?;
+                            last.flatten().map_or(0, |last| last + 1)
+                        }
+                    };
+
+                    let media = ProductMediaActiveModel {
+                        product_id: Set(product_id),
+                        storage_key: Set(key),
+                        file_name: Set(req.file_name),
+                        mime_type: Set(mime_type),
+                        size_bytes: Set(size_bytes),
+                        width: Set(dimensions.map(|(width, _)| width)),
+                        height: Set(dimensions.map(|(_, height)| height)),
+                        checksum: Set(checksum),
+                        alt_text: Set(req.alt_text),
+                        sort_order: Set(sort_order),
+                        ..Default::default()
+                    };
+
+                    media.insert(txn).await.map_err(ApiError::Database)
+                })
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        });
+
+        let media = match result {
+            Ok(media) => media,
+            Err(e) => {
+                // Don't leave an orphaned file behind
+                self.delete_file(&storage_key).await;
+                return Err(e);
+            }
+        };
+
+        // A failed derivative is logged rather than failing the upload; it is retried on first request
+        let mut derivatives = Vec::new();
+        if self.eager_derivatives && media.width.is_some() {
+            for spec in &self.derivatives {
+                match self.generate_derivative(&media, spec, Some(original.clone())).await {
+                    Ok((derivative, _)) => derivatives.push(derivative),
+                    Err(e) => tracing::warn!("Failed to render {} derivative of media {}: {}", spec.name, media.id, e),
+                }
+            }
+        }
+
+        self.cache
+            .invalidate(vec![keys::product(&self.tenant, product_id)])
+            .await;
+
+        Ok(MediaResponse::from((media, derivatives)))
+    }
+
+    /// List a product's media in display order
+    ///
+    /// Products that aren't published are reported as missing unless `include_unpublished` is set.
+    pub async fn list_media(&self, product_id: i32, include_unpublished: bool) -> Result<Vec<MediaResponse>, ApiError> {
+        self.check_product(product_id, include_unpublished).await?;
+
+        get_product_media(product_id, &self.conn)
+            .await
+            .map_err(ApiError::Database)
+    }
+
+    /// Fetch the contents of a media file, hidden like [`Self::list_media`] for unpublished products
+    pub async fn get_media_file(
+        &self,
+        product_id: i32,
+        media_id: i32,
+        include_unpublished: bool,
+    ) -> Result<MediaFile, ApiError> {
+        let media = self.find_media(product_id, media_id, include_unpublished).await?;
+        let data = self.read_file(&media.storage_key).await?;
+
+        Ok(MediaFile {
+            mime_type: media.mime_type,
+            checksum: media.checksum,
+            data,
+        })
+    }
+
+    /// Fetch a derivative of an image, rendering and storing it first if needed
+    ///
+    /// Derivatives are hidden like [`Self::list_media`] for unpublished products.
+    pub async fn get_derivative_file(
+        &self,
+        product_id: i32,
+        media_id: i32,
+        name: &str,
+        include_unpublished: bool,
+    ) -> Result<MediaFile, ApiError> {
+        let spec = self
+            .derivatives
+            .iter()
+            .find(|spec| spec.name == name)
+            .ok_or_else(|| ApiError::not_found_simple(format!("Unknown derivative: {}", name)))?;
+        let media = self.find_media(product_id, media_id, include_unpublished).await?;
+        if media.width.is_none() {
+            return Err(ApiError::not_found_simple(format!(
+                "Media with ID {} is not an image and has no derivatives",
+                media_id
+            )));
+        }
+
+        // Serve the stored copy unless the derivative's configuration has changed since
+        let key = derivative::storage_key(&media.storage_key, spec);
+        let existing = MediaDerivative::find()
+            .filter(MediaDerivativeColumn::MediaId.eq(media.id))
+            .filter(MediaDerivativeColumn::Name.eq(name))
+            .one(&self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+        if let Some(existing) = existing.filter(|existing| existing.storage_key == key) {
+            let data = self
+                .storage
+                .get(&key)
+                .await
+                .map_err(|e| self.storage_error("read", &key, e))?;
+            if let Some(data) = data {
+                return Ok(MediaFile {
+                    mime_type: existing.mime_type,
+                    checksum: existing.checksum,
+                    data,
+                });
+            }
+        }
+
+        let (derivative, data) = self.generate_derivative(&media, spec, None).await?;
+        self.cache
+            .invalidate(vec![keys::product(&self.tenant, product_id)])
+            .await;
+
+        Ok(MediaFile {
+            mime_type: derivative.mime_type,
+            checksum: derivative.checksum,
+            data,
+        })
+    }
+
+    /// Detach a media item from its product and remove the file
+    pub async fn delete_media(&self, product_id: i32, media_id: i32) -> Result<(), ApiError> {
+        let storage_keys = metrics::time_transaction("delete_media", async {
+            let media = self.find_media(product_id, media_id, true).await?;
+            let mut storage_keys: Vec<String> = MediaDerivative::find()
+                .select_only()
+                .column(MediaDerivativeColumn::StorageKey)
+                .filter(MediaDerivativeColumn::MediaId.eq(media.id))
+                .into_tuple()
+                .all(&self.conn)
+                .await
+                .map_err(ApiError::Database)?;
+            storage_keys.push(media.storage_key);
+
+            // Derivative rows cascade with the media row
+            ProductMedia::delete_by_id(media.id)
+                .exec(&self.conn)
+                .await
+                .map_err(ApiError::Database)?;
+
+            Ok::<_, ApiError>(storage_keys)
+        })
+        .await?;
+
+        delete_files(&self.storage, &storage_keys).await;
+        self.cache
+            .invalidate(vec![keys::product(&self.tenant, product_id)])
+            .await;
+
+        Ok(())
+    }
+
+    /// Check that a product exists and belongs to this repository's tenant, and is published
+    /// unless `include_unpublished` is set
+    async fn check_product(&self, product_id: i32, include_unpublished: bool) -> Result<(), ApiError> {
+        Product::find_by_id(product_id)
+            .filter(ProductColumn::TenantId.eq(self.tenant.as_str()))
+            .one(&self.conn)
+            .await
+            .map_err(ApiError::Database)?
+            .filter(|product| include_unpublished || product.status == ProductStatus::Published.as_str())
+            .ok_or_else(|| ApiError::not_found_simple("Product not found"))?;
+
+        Ok(())
+    }
+
+    async fn find_media(
+        &self,
+        product_id: i32,
+        media_id: i32,
+        include_unpublished: bool,
+    ) -> Result<ProductMediaModel, ApiError> {
+        self.check_product(product_id, include_unpublished).await?;
+
+        ProductMedia::find_by_id(media_id)
+            .filter(ProductMediaColumn::ProductId.eq(product_id))
+            .one(&self.conn)
+            .await
+            .map_err(ApiError::Database)?
+            .ok_or_else(|| ApiError::not_found("Media", media_id))
+    }
+
+    /// Render a derivative, store it and record it, replacing any previous version
+    async fn generate_derivative(
+        &self,
+        media: &ProductMediaModel,
+        spec: &DerivativeConfig,
+        original: Option<Bytes>,
+    ) -> Result<(MediaDerivativeModel, Bytes), ApiError> {
+        let original = match original {
+            Some(original) => original,
+            None => self.read_file(&media.storage_key).await?,
+        };
+
+        let render_spec = spec.clone();
+        let rendered = tokio::task::spawn_blocking(move || derivative::render(&original, &render_spec))
+            .await
+            .map_err(|e| ApiError::internal_server_error(format!("Derivative rendering panicked: {}", e)))?
+            .map_err(|e| {
+                tracing::error!("Failed to render {} derivative of media {}: {}", spec.name, media.id, e);
+                ApiError::internal_server_error("Failed to render image derivative")
+            })?;
+
+        let key = derivative::storage_key(&media.storage_key, spec);
+        self.storage
+            .put(&key, rendered.data.clone(), rendered.mime_type)
+            .await
+            .map_err(|e| self.storage_error("store", &key, e))?;
+
+        let previous = MediaDerivative::find()
+            .filter(MediaDerivativeColumn::MediaId.eq(media.id))
+            .filter(MediaDerivativeColumn::Name.eq(&spec.name))
+            .one(&self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+
+        let row = MediaDerivativeActiveModel {
+            media_id: Set(media.id),
+            name: Set(spec.name.clone()),
+            storage_key: Set(key.clone()),
+            mime_type: Set(rendered.mime_type.to_string()),
+            size_bytes: Set(rendered.data.len() as i64),
+            width: Set(rendered.width as i32),
+            height: Set(rendered.height as i32),
+            checksum: Set(hex::encode(Sha256::digest(&rendered.data))),
+            ..Default::default()
+        };
+        // Concurrent first requests may render the same derivative; the last write wins
+        let derivative = MediaDerivative::insert(row)
+            .on_conflict(
+                OnConflict::columns([MediaDerivativeColumn::MediaId, MediaDerivativeColumn::Name])
+                    .update_columns([
+                        MediaDerivativeColumn::StorageKey,
+                        MediaDerivativeColumn::MimeType,
+                        MediaDerivativeColumn::SizeBytes,
+                        MediaDerivativeColumn::Width,
+                        MediaDerivativeColumn::Height,
+                        MediaDerivativeColumn::Checksum,
+                    ])
+                    .to_owned(),
+            )
+            .exec_with_returning(&self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+
+        if let Some(previous) = previous.filter(|previous| previous.storage_key != key) {
+            delete_files(&self.storage, &[previous.storage_key]).await;
+        }
+
+        Ok((derivative, rendered.data))
+    }
+
+    /// Read a stored file that the database says exists
+    async fn read_file(&self, storage_key: &str) -> Result<Bytes, ApiError> {
+        self.storage
+            .get(storage_key)
+            .await
+            .map_err(|e| self.storage_error("read", storage_key, e))?
+            .ok_or_else(|| {
+                tracing::error!("Media file {} is missing from storage", storage_key);
+                ApiError::not_found_simple("Media file is missing from storage")
+            })
+    }
+
+    fn storage_error(&self, action: &str, storage_key: &str, error: anyhow::Error) -> ApiError {
+        tracing::error!(
+            "Failed to {} {} in {} storage: {}",
+            action,
+            storage_key,
+            self.storage.backend(),
+            error
+        );
+        ApiError::internal_server_error(format!("Failed to {} media file", action))
+    }
+
+    /// Remove a stored file, logging rather than failing: the row is already gone
+    async fn delete_file(&self, storage_key: &str) {
+        delete_files(&self.storage, &[storage_key.to_string()]).await;
+    }
+}
+
+/// Remove stored files, logging failures
+pub async fn delete_files(storage: &MediaStorage, storage_keys: &[String]) {
+    for key in storage_keys {
+        if let Err(e) = storage.delete(key).await {
+            tracing::warn!("Failed to delete {} from {} storage: {}", key, storage.backend(), e);
+        }
+    }
+}
+
+/// Fetch a product's media in display order
+pub async fn get_product_media(
+    product_id: i32,
+    executor: &impl sea_orm::ConnectionTrait,
+) -> Result<Vec<MediaResponse>, sea_orm::DbErr> {
+    let media = ProductMedia::find()
+        .filter(ProductMediaColumn::ProductId.eq(product_id))
+        .order_by_asc(ProductMediaColumn::SortOrder)
+        .order_by_asc(ProductMediaColumn::Id)
+        .find_with_related(MediaDerivative)
+        .order_by_asc(MediaDerivativeColumn::Id)
+        .all(executor)
+        .await?;
+
+    Ok(media.into_iter().map(MediaResponse::from).collect())
+}
+
+/// Fetch the media of several products in display order, keyed by product ID
+pub async fn get_products_media(
+    product_ids: &[i32],
+    executor: &impl sea_orm::ConnectionTrait,
+) -> Result<HashMap<i32, Vec<MediaResponse>>, sea_orm::DbErr> {
+    let mut media: HashMap<i32, Vec<MediaResponse>> = HashMap::new();
+    if product_ids.is_empty() {
+        return Ok(media);
+    }
+
+    let rows = ProductMedia::find()
+        .filter(ProductMediaColumn::ProductId.is_in(product_ids.iter().copied()))
+        .order_by_asc(ProductMediaColumn::SortOrder)
+        .order_by_asc(ProductMediaColumn::Id)
+        .find_with_related(MediaDerivative)
+        .order_by_asc(MediaDerivativeColumn::Id)
+        .all(executor)
+        .await?;
+    for (item, derivatives) in rows {
+        media
+            .entry(item.product_id)
+            .or_default()
+            .push(MediaResponse::from((item, derivatives)));
+    }
+
+    Ok(media)
+}
+
+/// Storage keys of a product's media files and their derivatives
+pub async fn get_product_storage_keys(
+    product_id: i32,
+    executor: &impl sea_orm::ConnectionTrait,
+) -> Result<Vec<String>, sea_orm::DbErr> {
+    let media = ProductMedia::find()
+        .filter(ProductMediaColumn::ProductId.eq(product_id))
+        .find_with_related(MediaDerivative)
+        .all(executor)
+        .await?;
+
+    Ok(media
+        .into_iter()
+        .flat_map(|(media, derivatives)| {
+            std::iter::once(media.storage_key).chain(derivatives.into_iter().map(|derivative| derivative.storage_key))
+        })
+        .collect())
+}
+
+impl From<(ProductMediaModel, Vec<MediaDerivativeModel>)> for MediaResponse {
+    fn from((model, derivatives): (ProductMediaModel, Vec<MediaDerivativeModel>)) -> Self {
+        let url = format!("/api/products/{}/media/{}", model.product_id, model.id);
+        let derivatives = derivatives
+            .into_iter()
+            .map(|derivative| DerivativeResponse {
+                url: format!("{}/{}", url, derivative.name),
+                name: derivative.name,
+                mime_type: derivative.mime_type,
+                size_bytes: derivative.size_bytes,
+                width: derivative.width,
+                height: derivative.height,
+            })
+            .collect();
+
+        Self {
+            id: model.id,
+            url,
+            file_name: model.file_name,
+            mime_type: model.mime_type,
+            size_bytes: model.size_bytes,
+            width: model.width,
+            height: model.height,
+            checksum: model.checksum,
+            alt_text: model.alt_text,
+            sort_order: model.sort_order,
+            derivatives,
+            created_at: model.created_at,
+        }
+    }
+}
+
+/// Detect the image format and dimensions from the file header
+fn sniff_image(data: &[u8]) -> Option<(&'static str, i32, i32)> {
+    let mime_type = match imagesize::image_type(data).ok()? {
+        ImageType::Jpeg => "image/jpeg",
+        ImageType::Png => "image/png",
+        ImageType::Gif => "image/gif",
+        ImageType::Webp => "image/webp",
+        ImageType::Bmp => "image/bmp",
+        ImageType::Tiff => "image/tiff",
+        ImageType::Heif(_) => "image/heif",
+        _ => return None,
+    };
+    let size = imagesize::blob_size(data).ok()?;
+
+    Some((
+        mime_type,
+        i32::try_from(size.width).ok()?,
+        i32::try_from(size.height).ok()?,
+    ))
+}
+
+/// File extension used for stored objects of a MIME type
+fn extension(mime_type: &str) -> &'static str {
+    match mime_type {
+        "image/jpeg" => "jpg",
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/bmp" => "bmp",
+        "image/tiff" => "tiff",
+        "image/heif" => "heif",
+        "application/pdf" => "pdf",
+        _ => "bin",
+    }
+}
@@ -1,5 +1,46 @@
+pub mod api_key;
+pub mod attribute;
 pub mod category;
+pub mod facet;
+pub mod idempotency;
+pub mod media;
 pub mod product;
+pub mod revision;
+pub mod store;
+pub mod tag;
+pub mod translation;
 
+pub use api_key::ApiKeyRepository;
+pub use attribute::AttributeRepository;
 pub use category::CategoryRepository;
+pub use idempotency::IdempotencyRepository;
+pub use media::MediaRepository;
 pub use product::ProductRepository;
+pub use store::{CategoryStore, ProductStore};
+pub use tag::TagRepository;
+pub use translation::TranslationRepository;
+
+use sea_orm::prelude::Decimal;
+
+use crate::error::ApiError;
+use crate::models::money::{Currency, Money};
+
+/// Read money from the `price` and `currency` columns
+///
+/// SQLite stores prices as floating point, so `20.00` reads back as `20`, or as `19.999999` after
+/// arithmetic, unless it's rounded back to the column's two decimal places.
+pub(crate) fn money_from_columns(price: Decimal, currency: &str) -> Result<Money, ApiError> {
+    let currency: Currency = currency
+        .parse()
+        .map_err(|e: String| ApiError::internal_server_error(format!("Invalid stored currency: {}", e)))?;
+    Money::new(price.round_dp(Money::SCALE), currency)
+        .map_err(|e| ApiError::internal_server_error(format!("Invalid stored price: {}", e)))
+}
+
+/// Check money about to be written as a product or revision price
+///
+/// Request bodies are checked as they're read, but prices built in code, such as by the catalog
+/// import, or restored from a revision reach the repository unchecked.
+pub(crate) fn check_price(price: Money) -> Result<Money, ApiError> {
+    Money::price(price.amount(), price.currency()).map_err(ApiError::Validation)
+}
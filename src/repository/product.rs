@@ -1,55 +1,109 @@
-use std::str::FromStr;
+use std::collections::HashMap;
 
 use anyhow::Result;
-use bigdecimal::BigDecimal;
 use chrono::Utc;
-use sea_orm::prelude::{DateTimeWithTimeZone, Decimal};
+use sea_orm::prelude::DateTimeWithTimeZone;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, Condition, EntityTrait, ModelTrait, PaginatorTrait, QueryFilter, QueryOrder,
-    QuerySelect, RelationTrait, Set, TransactionTrait,
+    QuerySelect, QueryTrait, RelationTrait, Set, TransactionTrait,
 };
 
+use crate::cache::{CatalogCache, keys};
+use crate::config::FacetConfig;
 use crate::database::DatabaseConnection;
 use crate::entity::{
-    Category, CategoryModel, CategoryRelation, Product, ProductActiveModel, ProductCategory,
+    Category, CategoryColumn, CategoryModel, CategoryRelation, Product, ProductActiveModel, ProductCategory,
     ProductCategoryActiveModel, ProductCategoryColumn, ProductCategoryModel, ProductColumn, ProductModel,
-    ProductRelation,
+    ProductRelation, ProductRevisionActiveModel,
 };
 use crate::error::ApiError;
+use crate::locale::LocaleChain;
+use crate::metrics;
+use crate::models::attribute::AttributeValues;
+use crate::models::facet::Facet;
+use crate::models::lifecycle::{ProductLifecycle, ProductStatus, ScheduleRequest, Transition};
+use crate::models::media::MediaResponse;
+use crate::models::money::Money;
 use crate::models::product::{
-    CategoryBrief, CreateProductRequest, ProductListResponse, ProductQueryParams, ProductResponse,
-    UpdateProductRequest,
+    CategoryBrief, CreateProductRequest, ProductListResponse, ProductQueryParams, ProductResponse, UpdateProductRequest,
 };
+use crate::models::revision::{
+    CreateRevisionRequest, RevisionContent, RevisionDiffResponse, RevisionResponse, RevisionStatus,
+};
+use crate::repository::attribute::{
+    attribute_filter, check_attributes, get_category_definitions, get_product_attributes, get_products_attributes,
+    set_product_attributes,
+};
+use crate::repository::facet::count_facets;
+use crate::repository::media::{delete_files, get_product_media, get_product_storage_keys, get_products_media};
+use crate::repository::revision::{
+    find_revision, get_revisions, insert_revision, live_revision, product_content, record_revision, revision_response,
+};
+use crate::repository::tag::{get_product_tags, get_products_tags, set_product_tags, tag_filter};
+use crate::repository::translation::{localize_products, search_filter};
+use crate::repository::{check_price, money_from_columns};
+use crate::storage::MediaStorage;
+use crate::tenant::Tenant;
 
-/// Repository for product operations
+/// Repository for product operations within one tenant's catalog
 #[derive(Clone)]
 pub struct ProductRepository {
     conn: DatabaseConnection,
+    cache: CatalogCache,
+    storage: MediaStorage,
+    price_buckets: Vec<f64>,
+    tenant: Tenant,
+    locales: LocaleChain,
 }
 
 impl ProductRepository {
-    /// Create a new product repository
-    pub fn new(conn: DatabaseConnection) -> Self {
-        Self { conn }
+    /// Create a new product repository working on the default tenant
+    ///
+    /// `storage` is used to remove a product's media files when it is deleted.
+    pub fn new(conn: DatabaseConnection, cache: CatalogCache, storage: MediaStorage, facets: &FacetConfig) -> Self {
+        Self {
+            conn,
+            cache,
+            storage,
+            price_buckets: facets.price_buckets.clone(),
+            tenant: Tenant::default(),
+            locales: LocaleChain::default(),
+        }
+    }
+
+    /// A copy of this repository working on `tenant`'s catalog
+    pub fn for_tenant(&self, tenant: &Tenant) -> Self {
+        Self {
+            tenant: tenant.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// A copy of this repository reading product text along `locales`
+    pub fn for_locale(&self, locales: &LocaleChain) -> Self {
+        Self {
+            locales: locales.clone(),
+            ..self.clone()
+        }
     }
 
     /// Create a new product
     pub async fn create_product(&self, req: CreateProductRequest) -> Result<ProductResponse, ApiError> {
+        let tenant = self.tenant.clone();
+        check_price(req.price)?;
+
         // Start transaction
-        let result = self
-            .conn
-            .transaction(|txn| {
+        let result = metrics::time_transaction(
+            "create_product",
+            self.conn.transaction(|txn| {
                 Box::pin(async move {
-                    // Convert BigDecimal to Decimal
-                    let price_str = req.price.to_string();
-                    let sea_orm_price = Decimal::from_str(&price_str)
-                        .map_err(|_| ApiError::internal_server_error("Invalid price format"))?;
-
                     let product = ProductActiveModel {
                         name: Set(req.name.clone()),
                         description: Set(req.description.clone()),
-                        price: Set(sea_orm_price),
+                        price: Set(req.price.amount()),
+                        currency: Set(req.price.currency().to_string()),
                         sku: Set(req.sku.clone()),
+                        tenant_id: Set(tenant.to_string()),
                         ..Default::default()
                     };
 
@@ -58,18 +112,36 @@ impl ProductRepository {
 
                     // Insert product categories
                     for category_id in &req.category_ids {
+                        // Categories of other tenants fail the link's foreign key
                         let product_category = ProductCategoryActiveModel {
                             product_id: Set(product_model.id),
                             category_id: Set(*category_id),
+                            tenant_id: Set(tenant.to_string()),
                         };
 
                         product_category.insert(txn).await.map_err(ApiError::Database)?;
                     }
 
+                    // Check attribute values against the categories' definitions
+                    let definitions = get_category_definitions(&req.category_ids, txn)
+                        .await
+                        .map_err(ApiError::Database)?;
+                    check_attributes(&definitions, &req.attributes)?;
+                    set_product_attributes(product_model.id, &req.attributes, txn)
+                        .await
+                        .map_err(ApiError::Database)?;
+                    let tags = set_product_tags(product_model.id, &req.tags, &tenant, txn)
+                        .await
+                        .map_err(ApiError::Database)?;
+
+                    // Start the product's history with its first revision
+                    record_revision(&product_model, None, product_model.created_at, txn).await?;
+
                     // Fetch categories for response
                     let categories = Self::get_product_categories(product_model.id, txn)
                         .await
                         .map_err(ApiError::Database)?;
+                    let lifecycle = ProductLifecycle::try_from(&product_model)?;
 
                     Ok(ProductResponse {
                         id: product_model.id,
@@ -77,25 +149,49 @@ impl ProductRepository {
                         description: product_model.description,
                         price: req.price,
                         sku: product_model.sku,
+                        lifecycle,
                         categories,
+                        attributes: req.attributes,
+                        tags,
+                        media: Vec::new(),
                         created_at: product_model.created_at,
                         updated_at: product_model.updated_at,
                     })
                 })
-            })
-            .await
-            .map_err(|e| match e {
-                sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
-                sea_orm::TransactionError::Transaction(api_err) => api_err,
-            })?;
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })?;
+
+        // Category product counts have changed
+        self.cache.invalidate(keys::category_lists(&self.tenant)).await;
 
         Ok(result)
     }
 
-    /// Get a product by ID
+    /// Get a product by ID, translated along the repository's locales
     pub async fn get_product(&self, id: i32) -> Result<ProductResponse, ApiError> {
+        let mut product = self.get_untranslated_product(id).await?;
+        localize_products(std::slice::from_mut(&mut product), &self.locales, &self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(product)
+    }
+
+    /// Get a product by ID in the default locale, as cached
+    async fn get_untranslated_product(&self, id: i32) -> Result<ProductResponse, ApiError> {
+        let cache_key = keys::product(&self.tenant, id);
+        if let Some(product) = self.cache.get(&cache_key).await {
+            return Ok(product);
+        }
+
         // Find product by ID
         let product = Product::find_by_id(id)
+            .filter(ProductColumn::TenantId.eq(self.tenant.as_str()))
             .one(&self.conn)
             .await
             .map_err(ApiError::Database)?
@@ -105,31 +201,60 @@ impl ProductRepository {
         let categories = Self::get_product_categories(id, &self.conn)
             .await
             .map_err(ApiError::Database)?;
+        let attributes = get_product_attributes(id, &self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+        let tags = get_product_tags(id, &self.conn).await.map_err(ApiError::Database)?;
+        let media = get_product_media(id, &self.conn).await.map_err(ApiError::Database)?;
 
-        // Convert price from Sea-ORM Decimal to BigDecimal for the response
-        let price_str = product.price.to_string();
-        let price =
-            BigDecimal::from_str(&price_str).map_err(|_| ApiError::internal_server_error("Invalid price format"))?;
+        let price = money_from_columns(product.price, &product.currency)?;
 
-        Ok(ProductResponse {
+        let response = ProductResponse {
+            lifecycle: ProductLifecycle::try_from(&product)?,
             id: product.id,
             name: product.name,
             description: product.description,
             price,
             sku: product.sku,
             categories,
+            attributes,
+            tags,
+            media,
             created_at: product.created_at,
             updated_at: product.updated_at,
-        })
+        };
+
+        self.cache.put(&cache_key, &response).await;
+
+        Ok(response)
+    }
+
+    /// Find the ID of the product with the given SKU, if any
+    pub async fn find_product_id_by_sku(&self, sku: &str) -> Result<Option<i32>, ApiError> {
+        let product = Product::find()
+            .filter(ProductColumn::TenantId.eq(self.tenant.as_str()))
+            .filter(ProductColumn::Sku.eq(sku))
+            .one(&self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(product.map(|product| product.id))
     }
 
     /// List products with pagination and filters
+    ///
+    /// Requested facets are counted over every matching product, not just the returned page. Product
+    /// text is translated, and searched, along the repository's locales.
     pub async fn list_products(&self, params: ProductQueryParams) -> Result<ProductListResponse, ApiError> {
         let page = params.page();
         let page_size = params.page_size();
+        let facets = match &params.facets {
+            Some(list) => Facet::parse_list(list)?,
+            None => Vec::new(),
+        };
 
         // Build query
-        let mut query = Product::find();
+        let mut query = Product::find().filter(ProductColumn::TenantId.eq(self.tenant.as_str()));
 
         // Apply category filter if present
         if let Some(category_id) = params.category_id {
@@ -139,12 +264,45 @@ impl ProductRepository {
                 .filter(ProductCategoryColumn::CategoryId.eq(category_id));
         }
 
+        // Anonymous clients only see published products
+        if !params.include_unpublished {
+            query = query.filter(ProductColumn::Status.eq(ProductStatus::Published.as_str()));
+        } else if let Some(status) = params.status {
+            query = query.filter(ProductColumn::Status.eq(status.as_str()));
+        }
+
+        // Apply attribute filters if present
+        if !params.attributes.is_empty() {
+            query = query.filter(attribute_filter(&params.attributes, self.conn.get_database_backend()));
+        }
+
+        // Apply tag filters if present
+        if !params.tags.is_empty() {
+            query = query.filter(tag_filter(&params.tags));
+        }
+
+        // Apply full-text search if present
+        if let Some(q) = params.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            query = query.filter(search_filter(q, &self.locales, self.conn.get_database_backend()));
+        }
+
         // Count total records for pagination
         let total = query.clone().count(&self.conn).await.map_err(ApiError::Database)?;
 
+        // Count facets over the same filtered set
+        let facets = if params.facets.is_some() {
+            let product_ids = query.clone().select_only().column(ProductColumn::Id).into_query();
+            let counts = count_facets(&facets, &product_ids, &self.price_buckets, &self.conn)
+                .await
+                .map_err(ApiError::Database)?;
+            Some(counts)
+        } else {
+            None
+        };
+
         // Apply pagination and ordering
         // Convert i64 values to u64 to match Sea-ORM's expectation
-        let offset = ((page - 1) * page_size) as u64;
+        let offset = params.offset() as u64;
         let limit = page_size as u64;
 
         let products = query
@@ -155,47 +313,33 @@ impl ProductRepository {
             .await
             .map_err(ApiError::Database)?;
 
-        // Convert to response objects
-        let mut product_responses = Vec::with_capacity(products.len());
-        for product in products {
-            let categories = Self::get_product_categories(product.id, &self.conn)
-                .await
-                .map_err(ApiError::Database)?;
+        let mut product_responses = product_responses(products, &self.conn).await?;
 
-            // Convert price from Sea-ORM Decimal to BigDecimal for the response
-            let price_str = product.price.to_string();
-            let price = BigDecimal::from_str(&price_str)
-                .map_err(|_| ApiError::internal_server_error("Invalid price format"))?;
-
-            product_responses.push(ProductResponse {
-                id: product.id,
-                name: product.name,
-                description: product.description,
-                price,
-                sku: product.sku,
-                categories,
-                created_at: product.created_at,
-                updated_at: product.updated_at,
-            });
-        }
+        localize_products(&mut product_responses, &self.locales, &self.conn)
+            .await
+            .map_err(ApiError::Database)?;
 
         Ok(ProductListResponse {
             products: product_responses,
             total: total as i64, // Convert u64 to i64 to match expected type
             page,
             page_size,
+            facets,
         })
     }
 
     /// Update a product
     pub async fn update_product(&self, id: i32, req: UpdateProductRequest) -> Result<ProductResponse, ApiError> {
+        let tenant = self.tenant.clone();
+
         // Start transaction
-        let result = self
-            .conn
-            .transaction(|txn| {
+        let result = metrics::time_transaction(
+            "update_product",
+            self.conn.transaction(|txn| {
                 Box::pin(async move {
                     // Find product by ID
                     let product = Product::find_by_id(id)
+                        .filter(ProductColumn::TenantId.eq(tenant.as_str()))
                         .one(txn)
                         .await
                         .map_err(ApiError::Database)?
@@ -213,12 +357,15 @@ impl ProductRepository {
                         product_active.description = Set(Some(description));
                     }
 
-                    if let Some(price) = &req.price {
-                        let price_str: String = price.to_string();
-                        let sea_orm_price: Decimal = Decimal::from_str(&price_str)
-                            .map_err(|_| ApiError::internal_server_error("Invalid price format"))?;
-                        product_active.price = Set(sea_orm_price);
-                    }
+                    // A new amount keeps the current currency, and a new currency the current amount
+                    let current = money_from_columns(product.price, &product.currency)?;
+                    let price = Money::price(
+                        req.price.unwrap_or(current.amount()),
+                        req.currency.unwrap_or(current.currency()),
+                    )
+                    .map_err(ApiError::Validation)?;
+                    product_active.price = Set(price.amount());
+                    product_active.currency = Set(price.currency().to_string());
 
                     if let Some(sku) = req.sku {
                         product_active.sku = Set(Some(sku));
@@ -241,6 +388,7 @@ impl ProductRepository {
                             let product_category = ProductCategoryActiveModel {
                                 product_id: Set(id),
                                 category_id: Set(*category_id),
+                                tenant_id: Set(tenant.to_string()),
                             };
 
                             product_category.insert(txn).await.map_err(ApiError::Database)?;
@@ -252,45 +400,81 @@ impl ProductRepository {
                         .await
                         .map_err(ApiError::Database)?;
 
-                    // Convert price for the response
-                    // Use original price if provided, otherwise convert from the model
-                    let price = if let Some(p) = req.price {
-                        p
+                    // Re-check attributes when they or the categories defining them change; values
+                    // carried over that the new categories no longer define are dropped
+                    let existing = get_product_attributes(id, txn).await.map_err(ApiError::Database)?;
+                    let attributes = if req.attributes.is_some() || req.category_ids.is_some() {
+                        let category_ids: Vec<i32> = categories.iter().map(|category| category.id).collect();
+                        let definitions = get_category_definitions(&category_ids, txn)
+                            .await
+                            .map_err(ApiError::Database)?;
+                        let attributes: AttributeValues = match req.attributes {
+                            Some(attributes) => attributes,
+                            None => existing
+                                .into_iter()
+                                .filter(|(name, _)| definitions.iter().any(|definition| definition.name == *name))
+                                .collect(),
+                        };
+                        check_attributes(&definitions, &attributes)?;
+                        set_product_attributes(id, &attributes, txn)
+                            .await
+                            .map_err(ApiError::Database)?;
+                        attributes
                     } else {
-                        let price_str = product_model.price.to_string();
-                        BigDecimal::from_str(&price_str)
-                            .map_err(|_| ApiError::internal_server_error("Invalid price format"))?
+                        existing
                     };
+                    let tags = match &req.tags {
+                        Some(tags) => set_product_tags(id, tags, &tenant, txn).await,
+                        None => get_product_tags(id, txn).await,
+                    }
+                    .map_err(ApiError::Database)?;
+                    let media = get_product_media(id, txn).await.map_err(ApiError::Database)?;
+
+                    // Keep a revision of the new content
+                    record_revision(&product_model, None, product_model.updated_at, txn).await?;
 
                     Ok(ProductResponse {
+                        lifecycle: ProductLifecycle::try_from(&product_model)?,
                         id: product_model.id,
                         name: product_model.name,
                         description: product_model.description,
                         price,
                         sku: product_model.sku,
                         categories,
+                        attributes,
+                        tags,
+                        media,
                         created_at: product_model.created_at,
                         updated_at: product_model.updated_at,
                     })
                 })
-            })
-            .await
-            .map_err(|e| match e {
-                sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
-                sea_orm::TransactionError::Transaction(api_err) => api_err,
-            })?;
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })?;
+
+        let mut stale = keys::category_lists(&self.tenant);
+        stale.push(keys::product(&self.tenant, id));
+        self.cache.invalidate(stale).await;
 
         Ok(result)
     }
 
     /// Delete a product
     pub async fn delete_product(&self, id: i32) -> Result<(), ApiError> {
+        let tenant = self.tenant.clone();
+
         // Start transaction
-        self.conn
-            .transaction(|txn| {
+        let storage_keys = metrics::time_transaction(
+            "delete_product",
+            self.conn.transaction(|txn| {
                 Box::pin(async move {
                     // Check if product exists
                     let product_exists = Product::find_by_id(id)
+                        .filter(ProductColumn::TenantId.eq(tenant.as_str()))
                         .one(txn)
                         .await
                         .map_err(ApiError::Database)?
@@ -308,17 +492,536 @@ impl ProductRepository {
                         .await
                         .map_err(ApiError::Database)?;
 
+                    // Media rows cascade with the product; collect their files to remove afterwards
+                    let storage_keys = get_product_storage_keys(id, txn).await.map_err(ApiError::Database)?;
+
                     // Delete the product
                     Product::delete_by_id(id).exec(txn).await.map_err(ApiError::Database)?;
 
+                    Ok(storage_keys)
+                })
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })?;
+
+        delete_files(&self.storage, &storage_keys).await;
+
+        let mut stale = keys::category_lists(&self.tenant);
+        stale.push(keys::product(&self.tenant, id));
+        self.cache.invalidate(stale).await;
+
+        Ok(())
+    }
+
+    /// Move a product through the publishing workflow
+    ///
+    /// Fails with a conflict when the product's current status doesn't allow the transition.
+    pub async fn transition_product(&self, id: i32, transition: Transition) -> Result<ProductResponse, ApiError> {
+        let tenant = self.tenant.clone();
+
+        metrics::time_transaction(
+            transition.as_str(),
+            self.conn.transaction(|txn| {
+                Box::pin(async move {
+                    let product = Product::find_by_id(id)
+                        .filter(ProductColumn::TenantId.eq(tenant.as_str()))
+                        .one(txn)
+                        .await
+                        .map_err(ApiError::Database)?
+                        .ok_or_else(|| ApiError::not_found_simple("Product not found"))?;
+
+                    let status = ProductLifecycle::try_from(&product)?.status;
+                    if !transition.sources().contains(&status) {
+                        let allowed: Vec<&str> = transition.sources().iter().map(|s| s.as_str()).collect();
+                        return Err(ApiError::Conflict(format!(
+                            "Cannot {} a product that is {}; allowed from {}",
+                            transition.as_str(),
+                            status,
+                            allowed.join(", ")
+                        )));
+                    }
+
+                    let now: DateTimeWithTimeZone = Utc::now().into();
+                    let mut active: ProductActiveModel = product.into();
+                    active.status = Set(transition.target().as_str().to_string());
+                    match transition {
+                        Transition::Submit => {}
+                        // A scheduled publication only applies while the product is in review
+                        Transition::Reject => active.publish_at = Set(None),
+                        Transition::Publish => {
+                            active.published_at = Set(Some(now));
+                            active.publish_at = Set(None);
+                        }
+                        Transition::Archive => {
+                            active.archived_at = Set(Some(now));
+                            active.publish_at = Set(None);
+                            active.unpublish_at = Set(None);
+                        }
+                        Transition::Restore => active.archived_at = Set(None),
+                    }
+                    active.updated_at = Set(now);
+                    active.update(txn).await.map_err(ApiError::Database)?;
+
                     Ok(())
                 })
-            })
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })?;
+
+        // Anonymous category product counts only include published products
+        let mut stale = keys::category_lists(&self.tenant);
+        stale.push(keys::product(&self.tenant, id));
+        self.cache.invalidate(stale).await;
+
+        self.get_product(id).await
+    }
+
+    /// Replace a product's publishing schedule
+    ///
+    /// `publish_at` needs a product in review and `unpublish_at` one in review or published, so the
+    /// scheduler never skips a step of the workflow.
+    pub async fn schedule_product(&self, id: i32, req: ScheduleRequest) -> Result<ProductResponse, ApiError> {
+        let tenant = self.tenant.clone();
+
+        if let (Some(publish_at), Some(unpublish_at)) = (req.publish_at, req.unpublish_at)
+            && unpublish_at <= publish_at
+        {
+            return Err(ApiError::bad_request("unpublish_at: must be after publish_at"));
+        }
+
+        metrics::time_transaction(
+            "schedule_product",
+            self.conn.transaction(|txn| {
+                Box::pin(async move {
+                    let product = Product::find_by_id(id)
+                        .filter(ProductColumn::TenantId.eq(tenant.as_str()))
+                        .one(txn)
+                        .await
+                        .map_err(ApiError::Database)?
+                        .ok_or_else(|| ApiError::not_found_simple("Product not found"))?;
+
+                    let status = ProductLifecycle::try_from(&product)?.status;
+                    if req.publish_at.is_some() && status != ProductStatus::InReview {
+                        return Err(ApiError::Conflict(format!(
+                            "Cannot schedule publishing of a product that is {}; submit it for review first",
+                            status
+                        )));
+                    }
+                    if req.unpublish_at.is_some()
+                        && !matches!(status, ProductStatus::InReview | ProductStatus::Published)
+                    {
+                        return Err(ApiError::Conflict(format!(
+                            "Cannot schedule unpublishing of a product that is {}",
+                            status
+                        )));
+                    }
+
+                    let mut active: ProductActiveModel = product.into();
+                    active.publish_at = Set(req.publish_at);
+                    active.unpublish_at = Set(req.unpublish_at);
+                    active.updated_at = Set(Utc::now().into());
+                    active.update(txn).await.map_err(ApiError::Database)?;
+
+                    Ok(())
+                })
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })?;
+
+        self.cache.invalidate(vec![keys::product(&self.tenant, id)]).await;
+
+        self.get_product(id).await
+    }
+
+    /// Set a product's status directly, bypassing the workflow, for operator tools such as imports
+    ///
+    /// Entering `published` or `archived` stamps the matching timestamp; the schedule is left alone.
+    pub async fn set_status(&self, id: i32, status: ProductStatus) -> Result<(), ApiError> {
+        let product = Product::find_by_id(id)
+            .filter(ProductColumn::TenantId.eq(self.tenant.as_str()))
+            .one(&self.conn)
             .await
-            .map_err(|e| match e {
-                sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
-                sea_orm::TransactionError::Transaction(api_err) => api_err,
-            })
+            .map_err(ApiError::Database)?
+            .ok_or_else(|| ApiError::not_found_simple("Product not found"))?;
+
+        if ProductLifecycle::try_from(&product)?.status == status {
+            return Ok(());
+        }
+
+        let now: DateTimeWithTimeZone = Utc::now().into();
+        let mut active: ProductActiveModel = product.into();
+        active.status = Set(status.as_str().to_string());
+        match status {
+            ProductStatus::Published => active.published_at = Set(Some(now)),
+            ProductStatus::Archived => active.archived_at = Set(Some(now)),
+            ProductStatus::Draft | ProductStatus::InReview => active.archived_at = Set(None),
+        }
+        active.updated_at = Set(now);
+        active.update(&self.conn).await.map_err(ApiError::Database)?;
+
+        let mut stale = keys::category_lists(&self.tenant);
+        stale.push(keys::product(&self.tenant, id));
+        self.cache.invalidate(stale).await;
+
+        Ok(())
+    }
+
+    /// Publish and archive products whose scheduled time has passed, returning their IDs
+    ///
+    /// Publications are applied first, so a product whose whole schedule has passed ends up archived.
+    /// Schedules are applied across every tenant, whichever one this repository works on.
+    pub async fn apply_due_schedules(&self, now: DateTimeWithTimeZone) -> Result<Vec<i32>, ApiError> {
+        let changed = metrics::time_transaction(
+            "apply_due_schedules",
+            self.conn.transaction(|txn| {
+                Box::pin(async move {
+                    let due_for_publish =
+                        Self::find_due_ids(ProductStatus::InReview, ProductColumn::PublishAt, now, txn).await?;
+                    Product::update_many()
+                        .col_expr(ProductColumn::Status, ProductStatus::Published.as_str().into())
+                        .col_expr(ProductColumn::PublishedAt, now.into())
+                        .col_expr(ProductColumn::PublishAt, Option::<DateTimeWithTimeZone>::None.into())
+                        .col_expr(ProductColumn::UpdatedAt, now.into())
+                        .filter(ProductColumn::Id.is_in(due_for_publish.iter().map(|(id, _)| *id)))
+                        .exec(txn)
+                        .await
+                        .map_err(ApiError::Database)?;
+
+                    let due_for_archive =
+                        Self::find_due_ids(ProductStatus::Published, ProductColumn::UnpublishAt, now, txn).await?;
+                    Product::update_many()
+                        .col_expr(ProductColumn::Status, ProductStatus::Archived.as_str().into())
+                        .col_expr(ProductColumn::ArchivedAt, now.into())
+                        .col_expr(ProductColumn::UnpublishAt, Option::<DateTimeWithTimeZone>::None.into())
+                        .col_expr(ProductColumn::UpdatedAt, now.into())
+                        .filter(ProductColumn::Id.is_in(due_for_archive.iter().map(|(id, _)| *id)))
+                        .exec(txn)
+                        .await
+                        .map_err(ApiError::Database)?;
+
+                    let mut changed = due_for_publish;
+                    changed.extend(due_for_archive);
+                    changed.sort();
+                    changed.dedup();
+                    Ok(changed)
+                })
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })?;
+
+        if !changed.is_empty() {
+            let mut stale = Vec::with_capacity(changed.len());
+            for (id, tenant) in &changed {
+                let tenant: Tenant = tenant.parse().map_err(ApiError::internal_server_error)?;
+                stale.extend(keys::category_lists(&tenant));
+                stale.push(keys::product(&tenant, *id));
+            }
+            self.cache.invalidate(stale).await;
+        }
+
+        Ok(changed.into_iter().map(|(id, _)| id).collect())
+    }
+
+    /// List a product's revisions, newest first
+    pub async fn list_revisions(&self, id: i32) -> Result<Vec<RevisionResponse>, ApiError> {
+        Self::find_product(id, &self.tenant, &self.conn).await?;
+
+        let live_number = live_revision(id, &self.conn)
+            .await
+            .map_err(ApiError::Database)?
+            .map(|live| live.number);
+        get_revisions(id, &self.conn)
+            .await
+            .map_err(ApiError::Database)?
+            .iter()
+            .map(|revision| revision_response(revision, live_number))
+            .collect()
+    }
+
+    /// Compare the content of two of a product's revisions
+    pub async fn diff_revisions(&self, id: i32, from: i32, to: i32) -> Result<RevisionDiffResponse, ApiError> {
+        Self::find_product(id, &self.tenant, &self.conn).await?;
+
+        let from_content = RevisionContent::try_from(&find_revision(id, from, &self.conn).await?)?;
+        let to_content = RevisionContent::try_from(&find_revision(id, to, &self.conn).await?)?;
+
+        Ok(RevisionDiffResponse {
+            from,
+            to,
+            changes: from_content.diff(&to_content),
+        })
+    }
+
+    /// Prepare a draft revision from the live content, leaving the product itself untouched
+    pub async fn create_revision(&self, id: i32, req: CreateRevisionRequest) -> Result<RevisionResponse, ApiError> {
+        let tenant = self.tenant.clone();
+
+        metrics::time_transaction(
+            "create_revision",
+            self.conn.transaction(|txn| {
+                Box::pin(async move {
+                    let product = Self::find_product(id, &tenant, txn).await?;
+                    let live = live_revision(id, txn).await.map_err(ApiError::Database)?;
+
+                    let mut content = product_content(&product, txn).await?;
+                    if let Some(name) = req.name {
+                        content.name = name;
+                    }
+                    if let Some(description) = req.description {
+                        content.description = Some(description);
+                    }
+                    content.price = Money::price(
+                        req.price.unwrap_or(content.price.amount()),
+                        req.currency.unwrap_or(content.price.currency()),
+                    )
+                    .map_err(ApiError::Validation)?;
+                    if let Some(mut category_ids) = req.category_ids {
+                        category_ids.sort();
+                        category_ids.dedup();
+
+                        // Drafts aren't linked to categories yet, so check they exist up front
+                        let found = Category::find()
+                            .filter(CategoryColumn::TenantId.eq(tenant.as_str()))
+                            .filter(CategoryColumn::Id.is_in(category_ids.iter().copied()))
+                            .count(txn)
+                            .await
+                            .map_err(ApiError::Database)?;
+                        if found as usize != category_ids.len() {
+                            return Err(ApiError::bad_request("category_ids: every category must exist"));
+                        }
+                        content.category_ids = category_ids;
+                    }
+
+                    let base_number = live.map(|live| live.number);
+                    let revision = insert_revision(
+                        id,
+                        &content,
+                        RevisionStatus::Draft,
+                        base_number,
+                        req.note,
+                        Utc::now().into(),
+                        txn,
+                    )
+                    .await?;
+
+                    revision_response(&revision, base_number)
+                })
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })
+    }
+
+    /// Apply a draft revision over the live product
+    ///
+    /// The draft must be based on the revision that is still live, so edits made since the draft
+    /// was prepared are never silently overwritten.
+    pub async fn publish_revision(&self, id: i32, number: i32) -> Result<ProductResponse, ApiError> {
+        let tenant = self.tenant.clone();
+
+        metrics::time_transaction(
+            "publish_revision",
+            self.conn.transaction(|txn| {
+                Box::pin(async move {
+                    let product = Self::find_product(id, &tenant, txn).await?;
+                    let draft = find_revision(id, number, txn).await?;
+                    if draft.status != RevisionStatus::Draft.as_str() {
+                        return Err(ApiError::Conflict(format!("Revision {} is already published", number)));
+                    }
+
+                    let live_number = live_revision(id, txn)
+                        .await
+                        .map_err(ApiError::Database)?
+                        .map(|live| live.number);
+                    if draft.base_number != live_number {
+                        return Err(ApiError::Conflict(format!(
+                            "Revision {} was drafted from revision {}, but revision {} is live now; create a new draft",
+                            number,
+                            draft.base_number.map_or("-".to_string(), |base| base.to_string()),
+                            live_number.map_or("-".to_string(), |live| live.to_string()),
+                        )));
+                    }
+
+                    let now: DateTimeWithTimeZone = Utc::now().into();
+                    Self::apply_content(product, &RevisionContent::try_from(&draft)?, now, txn).await?;
+
+                    let mut draft: ProductRevisionActiveModel = draft.into();
+                    draft.status = Set(RevisionStatus::Published.as_str().to_string());
+                    draft.published_at = Set(Some(now));
+                    draft.update(txn).await.map_err(ApiError::Database)?;
+
+                    Ok(())
+                })
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })?;
+
+        let mut stale = keys::category_lists(&self.tenant);
+        stale.push(keys::product(&self.tenant, id));
+        self.cache.invalidate(stale).await;
+
+        self.get_product(id).await
+    }
+
+    /// Restore the content of an earlier published revision, recording it as a new revision
+    pub async fn revert_to_revision(&self, id: i32, number: i32) -> Result<ProductResponse, ApiError> {
+        let tenant = self.tenant.clone();
+
+        metrics::time_transaction(
+            "revert_to_revision",
+            self.conn.transaction(|txn| {
+                Box::pin(async move {
+                    let product = Self::find_product(id, &tenant, txn).await?;
+                    let target = find_revision(id, number, txn).await?;
+                    if target.status != RevisionStatus::Published.as_str() {
+                        return Err(ApiError::Conflict(format!(
+                            "Revision {} is a draft that was never live; publish it instead",
+                            number
+                        )));
+                    }
+                    let live = live_revision(id, txn).await.map_err(ApiError::Database)?;
+                    if live.is_some_and(|live| live.number == number) {
+                        return Err(ApiError::Conflict(format!("Revision {} is already live", number)));
+                    }
+
+                    let now: DateTimeWithTimeZone = Utc::now().into();
+                    let content = RevisionContent::try_from(&target)?;
+                    Self::apply_content(product, &content, now, txn).await?;
+                    insert_revision(
+                        id,
+                        &content,
+                        RevisionStatus::Published,
+                        None,
+                        Some(format!("Reverted to revision {}", number)),
+                        now,
+                        txn,
+                    )
+                    .await?;
+
+                    Ok(())
+                })
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })?;
+
+        let mut stale = keys::category_lists(&self.tenant);
+        stale.push(keys::product(&self.tenant, id));
+        self.cache.invalidate(stale).await;
+
+        self.get_product(id).await
+    }
+
+    /// Helper method to write revision content over a product's live row and categories
+    ///
+    /// Attribute values the new categories no longer define are dropped, and attributes they
+    /// require must already have values.
+    async fn apply_content(
+        product: ProductModel,
+        content: &RevisionContent,
+        now: DateTimeWithTimeZone,
+        executor: &impl sea_orm::ConnectionTrait,
+    ) -> Result<ProductModel, ApiError> {
+        let price = check_price(content.price)?;
+        let id = product.id;
+        let tenant_id = product.tenant_id.clone();
+        let mut active: ProductActiveModel = product.into();
+        active.name = Set(content.name.clone());
+        active.description = Set(content.description.clone());
+        active.price = Set(price.amount());
+        active.currency = Set(price.currency().to_string());
+        active.updated_at = Set(now);
+        let product = active.update(executor).await.map_err(ApiError::Database)?;
+
+        ProductCategory::delete_many()
+            .filter(ProductCategoryColumn::ProductId.eq(id))
+            .exec(executor)
+            .await
+            .map_err(ApiError::Database)?;
+        for category_id in &content.category_ids {
+            let product_category = ProductCategoryActiveModel {
+                product_id: Set(id),
+                category_id: Set(*category_id),
+                tenant_id: Set(tenant_id.clone()),
+            };
+            product_category.insert(executor).await.map_err(ApiError::Database)?;
+        }
+
+        let definitions = get_category_definitions(&content.category_ids, executor)
+            .await
+            .map_err(ApiError::Database)?;
+        let attributes: AttributeValues = get_product_attributes(id, executor)
+            .await
+            .map_err(ApiError::Database)?
+            .into_iter()
+            .filter(|(name, _)| definitions.iter().any(|definition| definition.name == *name))
+            .collect();
+        check_attributes(&definitions, &attributes)?;
+        set_product_attributes(id, &attributes, executor)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(product)
+    }
+
+    /// Helper method to find a tenant's product by ID
+    async fn find_product(
+        id: i32,
+        tenant: &Tenant,
+        executor: &impl sea_orm::ConnectionTrait,
+    ) -> Result<ProductModel, ApiError> {
+        Product::find_by_id(id)
+            .filter(ProductColumn::TenantId.eq(tenant.as_str()))
+            .one(executor)
+            .await
+            .map_err(ApiError::Database)?
+            .ok_or_else(|| ApiError::not_found_simple("Product not found"))
+    }
+
+    /// Helper method to find the IDs and tenants of products in `status` whose schedule `column` has
+    /// passed
+    async fn find_due_ids(
+        status: ProductStatus,
+        column: ProductColumn,
+        now: DateTimeWithTimeZone,
+        executor: &impl sea_orm::ConnectionTrait,
+    ) -> Result<Vec<(i32, String)>, ApiError> {
+        Product::find()
+            .select_only()
+            .column(ProductColumn::Id)
+            .column(ProductColumn::TenantId)
+            .filter(ProductColumn::Status.eq(status.as_str()))
+            .filter(column.lte(now))
+            .into_tuple()
+            .all(executor)
+            .await
+            .map_err(ApiError::Database)
     }
 
     /// Helper method to get product categories
@@ -345,3 +1048,90 @@ impl ProductRepository {
         Ok(category_briefs)
     }
 }
+
+impl TryFrom<&ProductModel> for ProductLifecycle {
+    type Error = ApiError;
+
+    fn try_from(product: &ProductModel) -> Result<Self, Self::Error> {
+        Ok(ProductLifecycle {
+            status: product.status.parse().map_err(ApiError::internal_server_error)?,
+            published_at: product.published_at,
+            archived_at: product.archived_at,
+            publish_at: product.publish_at,
+            unpublish_at: product.unpublish_at,
+        })
+    }
+}
+
+/// Build the responses for a page of products, in the same order
+///
+/// Categories, attributes, tags and media are each loaded for the whole page in one query. Text
+/// is left untranslated; see [`localize_products`].
+pub(crate) async fn product_responses(
+    products: Vec<ProductModel>,
+    executor: &impl sea_orm::ConnectionTrait,
+) -> Result<Vec<ProductResponse>, ApiError> {
+    let product_ids: Vec<i32> = products.iter().map(|product| product.id).collect();
+    let mut categories = get_products_categories(&product_ids, executor)
+        .await
+        .map_err(ApiError::Database)?;
+    let mut attributes = get_products_attributes(&product_ids, executor)
+        .await
+        .map_err(ApiError::Database)?;
+    let mut tags = get_products_tags(&product_ids, executor)
+        .await
+        .map_err(ApiError::Database)?;
+    let mut media = get_products_media(&product_ids, executor)
+        .await
+        .map_err(ApiError::Database)?;
+
+    products
+        .into_iter()
+        .map(|product| {
+            Ok(ProductResponse {
+                lifecycle: ProductLifecycle::try_from(&product)?,
+                price: money_from_columns(product.price, &product.currency)?,
+                categories: categories.remove(&product.id).unwrap_or_default(),
+                attributes: attributes.remove(&product.id).unwrap_or_default(),
+                tags: tags.remove(&product.id).unwrap_or_default(),
+                media: media.remove(&product.id).unwrap_or_default(),
+                id: product.id,
+                name: product.name,
+                description: product.description,
+                sku: product.sku,
+                created_at: product.created_at,
+                updated_at: product.updated_at,
+            })
+        })
+        .collect()
+}
+
+/// Get the categories of several products, keyed by product ID
+async fn get_products_categories(
+    product_ids: &[i32],
+    executor: &impl sea_orm::ConnectionTrait,
+) -> Result<HashMap<i32, Vec<CategoryBrief>>, sea_orm::DbErr> {
+    let mut categories: HashMap<i32, Vec<CategoryBrief>> = HashMap::new();
+    if product_ids.is_empty() {
+        return Ok(categories);
+    }
+
+    let rows: Vec<(i32, i32, String)> = Category::find()
+        .select_only()
+        .column(ProductCategoryColumn::ProductId)
+        .column(CategoryColumn::Id)
+        .column(CategoryColumn::Name)
+        .join(sea_orm::JoinType::InnerJoin, CategoryRelation::ProductCategories.def())
+        .filter(ProductCategoryColumn::ProductId.is_in(product_ids.iter().copied()))
+        .into_tuple()
+        .all(executor)
+        .await?;
+    for (product_id, id, name) in rows {
+        categories
+            .entry(product_id)
+            .or_default()
+            .push(CategoryBrief { id, name });
+    }
+
+    Ok(categories)
+}
@@ -0,0 +1,174 @@
+use std::str::FromStr;
+
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, Set};
+
+use crate::entity::{
+    ProductCategory, ProductCategoryColumn, ProductModel, ProductRevision, ProductRevisionActiveModel,
+    ProductRevisionColumn, ProductRevisionModel,
+};
+use crate::error::ApiError;
+use crate::models::revision::{RevisionContent, RevisionResponse, RevisionStatus};
+use crate::repository::{check_price, money_from_columns};
+
+/// Read the live content of a product
+pub async fn product_content(
+    product: &ProductModel,
+    executor: &impl ConnectionTrait,
+) -> Result<RevisionContent, ApiError> {
+    let mut category_ids: Vec<i32> = ProductCategory::find()
+        .filter(ProductCategoryColumn::ProductId.eq(product.id))
+        .all(executor)
+        .await
+        .map_err(ApiError::Database)?
+        .into_iter()
+        .map(|link| link.category_id)
+        .collect();
+    category_ids.sort();
+
+    Ok(RevisionContent {
+        name: product.name.clone(),
+        description: product.description.clone(),
+        price: money_from_columns(product.price, &product.currency)?,
+        category_ids,
+    })
+}
+
+/// Record a product's live content as a new published revision, unless the live revision already
+/// holds it
+pub async fn record_revision(
+    product: &ProductModel,
+    note: Option<String>,
+    now: DateTimeWithTimeZone,
+    executor: &impl ConnectionTrait,
+) -> Result<(), ApiError> {
+    let content = product_content(product, executor).await?;
+    if let Some(live) = live_revision(product.id, executor).await.map_err(ApiError::Database)?
+        && RevisionContent::try_from(&live)? == content
+    {
+        return Ok(());
+    }
+
+    insert_revision(
+        product.id,
+        &content,
+        RevisionStatus::Published,
+        None,
+        note,
+        now,
+        executor,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Get a product's revisions, newest first
+pub async fn get_revisions(
+    product_id: i32,
+    executor: &impl ConnectionTrait,
+) -> Result<Vec<ProductRevisionModel>, DbErr> {
+    ProductRevision::find()
+        .filter(ProductRevisionColumn::ProductId.eq(product_id))
+        .order_by_desc(ProductRevisionColumn::Number)
+        .all(executor)
+        .await
+}
+
+/// Get a product's revision by number
+pub async fn find_revision(
+    product_id: i32,
+    number: i32,
+    executor: &impl ConnectionTrait,
+) -> Result<ProductRevisionModel, ApiError> {
+    ProductRevision::find()
+        .filter(ProductRevisionColumn::ProductId.eq(product_id))
+        .filter(ProductRevisionColumn::Number.eq(number))
+        .one(executor)
+        .await
+        .map_err(ApiError::Database)?
+        .ok_or_else(|| ApiError::not_found_simple(format!("Revision {} not found", number)))
+}
+
+/// Get the revision holding a product's live content: the most recent published one
+pub async fn live_revision(
+    product_id: i32,
+    executor: &impl ConnectionTrait,
+) -> Result<Option<ProductRevisionModel>, DbErr> {
+    ProductRevision::find()
+        .filter(ProductRevisionColumn::ProductId.eq(product_id))
+        .filter(ProductRevisionColumn::Status.eq(RevisionStatus::Published.as_str()))
+        .order_by_desc(ProductRevisionColumn::Number)
+        .one(executor)
+        .await
+}
+
+/// Store a new revision, numbered after the product's latest one
+///
+/// Published revisions are stamped with `now`; drafts record the revision they were based on.
+pub async fn insert_revision(
+    product_id: i32,
+    content: &RevisionContent,
+    status: RevisionStatus,
+    base_number: Option<i32>,
+    note: Option<String>,
+    now: DateTimeWithTimeZone,
+    executor: &impl ConnectionTrait,
+) -> Result<ProductRevisionModel, ApiError> {
+    let price = check_price(content.price)?;
+    let latest = ProductRevision::find()
+        .filter(ProductRevisionColumn::ProductId.eq(product_id))
+        .order_by_desc(ProductRevisionColumn::Number)
+        .one(executor)
+        .await
+        .map_err(ApiError::Database)?;
+
+    let revision = ProductRevisionActiveModel {
+        product_id: Set(product_id),
+        number: Set(latest.map_or(1, |latest| latest.number + 1)),
+        status: Set(status.as_str().to_string()),
+        base_number: Set(base_number),
+        name: Set(content.name.clone()),
+        description: Set(content.description.clone()),
+        price: Set(price.amount()),
+        currency: Set(price.currency().to_string()),
+        category_ids: Set(serde_json::json!(content.category_ids)),
+        note: Set(note),
+        created_at: Set(now),
+        published_at: Set((status == RevisionStatus::Published).then_some(now)),
+        ..Default::default()
+    };
+
+    revision.insert(executor).await.map_err(ApiError::Database)
+}
+
+impl TryFrom<&ProductRevisionModel> for RevisionContent {
+    type Error = ApiError;
+
+    fn try_from(revision: &ProductRevisionModel) -> Result<Self, Self::Error> {
+        Ok(RevisionContent {
+            name: revision.name.clone(),
+            description: revision.description.clone(),
+            price: money_from_columns(revision.price, &revision.currency)?,
+            category_ids: serde_json::from_value(revision.category_ids.clone())
+                .map_err(|_| ApiError::internal_server_error("Invalid revision category IDs"))?,
+        })
+    }
+}
+
+/// Build the response for a revision; `live_number` is the product's live revision number
+pub fn revision_response(
+    revision: &ProductRevisionModel,
+    live_number: Option<i32>,
+) -> Result<RevisionResponse, ApiError> {
+    Ok(RevisionResponse {
+        number: revision.number,
+        status: RevisionStatus::from_str(&revision.status).map_err(ApiError::internal_server_error)?,
+        base_number: revision.base_number,
+        live: live_number == Some(revision.number),
+        content: RevisionContent::try_from(revision)?,
+        note: revision.note.clone(),
+        created_at: revision.created_at,
+        published_at: revision.published_at,
+    })
+}
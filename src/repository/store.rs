@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::error::ApiError;
+use crate::locale::LocaleChain;
+use crate::models::category::{
+    CategoryListResponse, CategoryQueryParams, CategoryResponse, CreateCategoryRequest, UpdateCategoryRequest,
+};
+use crate::models::lifecycle::{ScheduleRequest, Transition};
+use crate::models::product::{
+    CreateProductRequest, ProductListResponse, ProductQueryParams, ProductResponse, UpdateProductRequest,
+};
+use crate::repository::category::CategoryRepository;
+use crate::repository::product::ProductRepository;
+use crate::tenant::Tenant;
+
+/// Product operations behind the product endpoints
+///
+/// Implemented by [`ProductRepository`]; handlers hold it as `Arc<dyn ProductStore>` so they can be
+/// tested against fakes and mocks.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ProductStore: Send + Sync {
+    /// The store limited to `tenant`'s catalog
+    fn for_tenant(&self, tenant: &Tenant) -> Arc<dyn ProductStore>;
+
+    /// The store translating products along `locales`
+    fn for_locale(&self, locales: &LocaleChain) -> Arc<dyn ProductStore>;
+
+    async fn list_products(&self, params: ProductQueryParams) -> Result<ProductListResponse, ApiError>;
+
+    async fn get_product(&self, id: i32) -> Result<ProductResponse, ApiError>;
+
+    async fn create_product(&self, req: CreateProductRequest) -> Result<ProductResponse, ApiError>;
+
+    async fn update_product(&self, id: i32, req: UpdateProductRequest) -> Result<ProductResponse, ApiError>;
+
+    async fn delete_product(&self, id: i32) -> Result<(), ApiError>;
+
+    async fn transition_product(&self, id: i32, transition: Transition) -> Result<ProductResponse, ApiError>;
+
+    async fn schedule_product(&self, id: i32, req: ScheduleRequest) -> Result<ProductResponse, ApiError>;
+}
+
+/// Category operations behind the category endpoints
+///
+/// Implemented by [`CategoryRepository`]; handlers hold it as `Arc<dyn CategoryStore>` so they can
+/// be tested against fakes and mocks.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait CategoryStore: Send + Sync {
+    /// The store limited to `tenant`'s catalog
+    fn for_tenant(&self, tenant: &Tenant) -> Arc<dyn CategoryStore>;
+
+    /// The store translating categories and their products along `locales`
+    fn for_locale(&self, locales: &LocaleChain) -> Arc<dyn CategoryStore>;
+
+    /// Categories with product counts; only published products are counted unless `include_unpublished` is set
+    async fn list_categories(
+        &self,
+        params: CategoryQueryParams,
+        include_unpublished: bool,
+    ) -> Result<CategoryListResponse, ApiError>;
+
+    async fn get_category(&self, id: i32) -> Result<CategoryResponse, ApiError>;
+
+    async fn create_category(&self, req: CreateCategoryRequest) -> Result<CategoryResponse, ApiError>;
+
+    async fn update_category(&self, id: i32, req: UpdateCategoryRequest) -> Result<CategoryResponse, ApiError>;
+
+    async fn delete_category(&self, id: i32) -> Result<(), ApiError>;
+
+    /// Products in the category; only published ones unless `include_unpublished` is set
+    async fn get_products_by_category(
+        &self,
+        category_id: i32,
+        include_unpublished: bool,
+    ) -> Result<Vec<ProductResponse>, ApiError>;
+}
+
+#[async_trait]
+impl ProductStore for ProductRepository {
+    fn for_tenant(&self, tenant: &Tenant) -> Arc<dyn ProductStore> {
+        Arc::new(ProductRepository::for_tenant(self, tenant))
+    }
+
+    fn for_locale(&self, locales: &LocaleChain) -> Arc<dyn ProductStore> {
+        Arc::new(ProductRepository::for_locale(self, locales))
+    }
+
+    async fn list_products(&self, params: ProductQueryParams) -> Result<ProductListResponse, ApiError> {
+        ProductRepository::list_products(self, params).await
+    }
+
+    async fn get_product(&self, id: i32) -> Result<ProductResponse, ApiError> {
+        ProductRepository::get_product(self, id).await
+    }
+
+    async fn create_product(&self, req: CreateProductRequest) -> Result<ProductResponse, ApiError> {
+        ProductRepository::create_product(self, req).await
+    }
+
+    async fn update_product(&self, id: i32, req: UpdateProductRequest) -> Result<ProductResponse, ApiError> {
+        ProductRepository::update_product(self, id, req).await
+    }
+
+    async fn delete_product(&self, id: i32) -> Result<(), ApiError> {
+        ProductRepository::delete_product(self, id).await
+    }
+
+    async fn transition_product(&self, id: i32, transition: Transition) -> Result<ProductResponse, ApiError> {
+        ProductRepository::transition_product(self, id, transition).await
+    }
+
+    async fn schedule_product(&self, id: i32, req: ScheduleRequest) -> Result<ProductResponse, ApiError> {
+        ProductRepository::schedule_product(self, id, req).await
+    }
+}
+
+#[async_trait]
+impl CategoryStore for CategoryRepository {
+    fn for_tenant(&self, tenant: &Tenant) -> Arc<dyn CategoryStore> {
+        Arc::new(CategoryRepository::for_tenant(self, tenant))
+    }
+
+    fn for_locale(&self, locales: &LocaleChain) -> Arc<dyn CategoryStore> {
+        Arc::new(CategoryRepository::for_locale(self, locales))
+    }
+
+    async fn list_categories(
+        &self,
+        params: CategoryQueryParams,
+        include_unpublished: bool,
+    ) -> Result<CategoryListResponse, ApiError> {
+        CategoryRepository::list_categories(self, params, include_unpublished).await
+    }
+
+    async fn get_category(&self, id: i32) -> Result<CategoryResponse, ApiError> {
+        CategoryRepository::get_category(self, id).await
+    }
+
+    async fn create_category(&self, req: CreateCategoryRequest) -> Result<CategoryResponse, ApiError> {
+        CategoryRepository::create_category(self, req).await
+    }
+
+    async fn update_category(&self, id: i32, req: UpdateCategoryRequest) -> Result<CategoryResponse, ApiError> {
+        CategoryRepository::update_category(self, id, req).await
+    }
+
+    async fn delete_category(&self, id: i32) -> Result<(), ApiError> {
+        CategoryRepository::delete_category(self, id).await
+    }
+
+    async fn get_products_by_category(
+        &self,
+        category_id: i32,
+        include_unpublished: bool,
+    ) -> Result<Vec<ProductResponse>, ApiError> {
+        CategoryRepository::get_products_by_category(self, category_id, include_unpublished).await
+    }
+}
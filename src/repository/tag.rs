@@ -0,0 +1,346 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use sea_orm::sea_query::{Expr, ExprTrait, IntoCondition, OnConflict, Query};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, RelationTrait, Set, TransactionTrait,
+};
+
+use crate::cache::{CatalogCache, keys};
+use crate::database::DatabaseConnection;
+use crate::entity::{
+    ProductColumn, ProductTag, ProductTagActiveModel, ProductTagColumn, ProductTagRelation, Tag, TagActiveModel,
+    TagColumn, TagModel, TagRelation,
+};
+use crate::error::ApiError;
+use crate::metrics;
+use crate::models::lifecycle::ProductStatus;
+use crate::models::tag::{RenameTagRequest, TagResponse};
+use crate::tenant::Tenant;
+
+/// Repository for product tags within one tenant's catalog
+#[derive(Clone)]
+pub struct TagRepository {
+    conn: DatabaseConnection,
+    cache: CatalogCache,
+    tenant: Tenant,
+}
+
+impl TagRepository {
+    /// Create a new tag repository working on the default tenant
+    pub fn new(conn: DatabaseConnection, cache: CatalogCache) -> Self {
+        Self {
+            conn,
+            cache,
+            tenant: Tenant::default(),
+        }
+    }
+
+    /// A copy of this repository working on `tenant`'s catalog
+    pub fn for_tenant(&self, tenant: &Tenant) -> Self {
+        Self {
+            tenant: tenant.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// List every tag with the number of products carrying it, ordered by name
+    ///
+    /// Only published products are counted unless `include_unpublished` is set.
+    pub async fn list_tags(&self, include_unpublished: bool) -> Result<Vec<TagResponse>, ApiError> {
+        let mut query = Tag::find()
+            .select_only()
+            .column(TagColumn::Id)
+            .column(TagColumn::Name)
+            .join(sea_orm::JoinType::LeftJoin, TagRelation::ProductTags.def());
+        query = if include_unpublished {
+            query.column_as(ProductTagColumn::ProductId.count(), "product_count")
+        } else {
+            // Tags only carried by unpublished products are still listed, with a count of zero
+            let published = ProductTagRelation::Products.def().on_condition(|_, products| {
+                Expr::col((products, ProductColumn::Status))
+                    .eq(ProductStatus::Published.as_str())
+                    .into_condition()
+            });
+            query
+                .column_as(ProductColumn::Id.count(), "product_count")
+                .join(sea_orm::JoinType::LeftJoin, published)
+        };
+
+        let rows: Vec<(i32, String, i64)> = query
+            .filter(TagColumn::TenantId.eq(self.tenant.as_str()))
+            .group_by(TagColumn::Id)
+            .group_by(TagColumn::Name)
+            .order_by_asc(TagColumn::Name)
+            .into_tuple()
+            .all(&self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, name, product_count)| TagResponse {
+                id,
+                name,
+                product_count,
+            })
+            .collect())
+    }
+
+    /// Rename a tag
+    ///
+    /// Renaming to the name of another tag violates a unique constraint (409); merge the tags
+    /// instead.
+    pub async fn rename_tag(&self, id: i32, req: RenameTagRequest) -> Result<TagResponse, ApiError> {
+        let tenant = self.tenant.clone();
+
+        let (result, affected_product_ids) = metrics::time_transaction(
+            "rename_tag",
+            self.conn.transaction(|txn| {
+                Box::pin(async move {
+                    let tag = Self::find_tag(id, &tenant, txn).await?;
+
+                    let mut active: TagActiveModel = tag.into();
+                    active.name = Set(normalize_tag(&req.name));
+                    active.updated_at = Set(Utc::now().into());
+                    let tag = active.update(txn).await.map_err(ApiError::Database)?;
+
+                    let affected_product_ids = Self::get_tag_product_ids(id, txn).await?;
+                    let response = TagResponse {
+                        id: tag.id,
+                        name: tag.name,
+                        product_count: affected_product_ids.len() as i64,
+                    };
+
+                    Ok((response, affected_product_ids))
+                })
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })?;
+
+        self.invalidate_products(&affected_product_ids).await;
+
+        Ok(result)
+    }
+
+    /// Merge one tag into another
+    ///
+    /// Products tagged with `source_id` are tagged with `target_id` instead, and the source tag is
+    /// deleted.
+    pub async fn merge_tags(&self, source_id: i32, target_id: i32) -> Result<TagResponse, ApiError> {
+        if source_id == target_id {
+            return Err(ApiError::bad_request("Cannot merge a tag into itself"));
+        }
+        let tenant = self.tenant.clone();
+
+        let (result, affected_product_ids) = metrics::time_transaction(
+            "merge_tags",
+            self.conn.transaction(|txn| {
+                Box::pin(async move {
+                    // Check that both tags exist
+                    Tag::find_by_id(source_id)
+                        .filter(TagColumn::TenantId.eq(tenant.as_str()))
+                        .one(txn)
+                        .await
+                        .map_err(ApiError::Database)?
+                        .ok_or_else(|| ApiError::not_found("Tag", source_id))?;
+                    let target = Tag::find_by_id(target_id)
+                        .filter(TagColumn::TenantId.eq(tenant.as_str()))
+                        .one(txn)
+                        .await
+                        .map_err(ApiError::Database)?
+                        .ok_or_else(|| ApiError::not_found("Tag", target_id))?;
+
+                    let affected_product_ids = Self::get_tag_product_ids(source_id, txn).await?;
+                    let existing_product_ids = Self::get_tag_product_ids(target_id, txn).await?;
+
+                    // Tag the source's products with the target, skipping those already tagged
+                    for product_id in &affected_product_ids {
+                        if existing_product_ids.contains(product_id) {
+                            continue;
+                        }
+
+                        let product_tag = ProductTagActiveModel {
+                            product_id: Set(*product_id),
+                            tag_id: Set(target_id),
+                        };
+                        product_tag.insert(txn).await.map_err(ApiError::Database)?;
+                    }
+
+                    // The source's links cascade with it
+                    Tag::delete_by_id(source_id)
+                        .exec(txn)
+                        .await
+                        .map_err(ApiError::Database)?;
+
+                    let product_count = Self::get_tag_product_ids(target_id, txn).await?.len() as i64;
+                    let response = TagResponse {
+                        id: target.id,
+                        name: target.name,
+                        product_count,
+                    };
+
+                    Ok((response, affected_product_ids))
+                })
+            }),
+        )
+        .await
+        .map_err(|e| match e {
+            sea_orm::TransactionError::Connection(db_err) => ApiError::Database(db_err),
+            sea_orm::TransactionError::Transaction(api_err) => api_err,
+        })?;
+
+        self.invalidate_products(&affected_product_ids).await;
+
+        Ok(result)
+    }
+
+    /// Helper method to drop cached products whose tags changed
+    async fn invalidate_products(&self, product_ids: &[i32]) {
+        let stale = product_ids
+            .iter()
+            .map(|product_id| keys::product(&self.tenant, *product_id))
+            .collect();
+        self.cache.invalidate(stale).await;
+    }
+
+    /// Helper method to find a tenant's tag by ID
+    async fn find_tag(id: i32, tenant: &Tenant, executor: &impl ConnectionTrait) -> Result<TagModel, ApiError> {
+        Tag::find_by_id(id)
+            .filter(TagColumn::TenantId.eq(tenant.as_str()))
+            .one(executor)
+            .await
+            .map_err(ApiError::Database)?
+            .ok_or_else(|| ApiError::not_found_simple("Tag not found"))
+    }
+
+    /// Helper method to get the IDs of products carrying a tag
+    async fn get_tag_product_ids(tag_id: i32, executor: &impl ConnectionTrait) -> Result<Vec<i32>, ApiError> {
+        let links = ProductTag::find()
+            .filter(ProductTagColumn::TagId.eq(tag_id))
+            .all(executor)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(links.into_iter().map(|link| link.product_id).collect())
+    }
+}
+
+/// Normalize a tag name: tags are matched and stored trimmed and lowercased
+pub fn normalize_tag(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Get a product's tag names, in alphabetical order
+pub async fn get_product_tags(product_id: i32, executor: &impl ConnectionTrait) -> Result<Vec<String>, DbErr> {
+    let tags = Tag::find()
+        .join(sea_orm::JoinType::InnerJoin, TagRelation::ProductTags.def())
+        .filter(ProductTagColumn::ProductId.eq(product_id))
+        .order_by_asc(TagColumn::Name)
+        .all(executor)
+        .await?;
+
+    Ok(tags.into_iter().map(|tag| tag.name).collect())
+}
+
+/// Get the tag names of several products in alphabetical order, keyed by product ID
+pub async fn get_products_tags(
+    product_ids: &[i32],
+    executor: &impl ConnectionTrait,
+) -> Result<HashMap<i32, Vec<String>>, DbErr> {
+    let mut tags: HashMap<i32, Vec<String>> = HashMap::new();
+    if product_ids.is_empty() {
+        return Ok(tags);
+    }
+
+    let rows: Vec<(i32, String)> = Tag::find()
+        .select_only()
+        .column(ProductTagColumn::ProductId)
+        .column(TagColumn::Name)
+        .join(sea_orm::JoinType::InnerJoin, TagRelation::ProductTags.def())
+        .filter(ProductTagColumn::ProductId.is_in(product_ids.iter().copied()))
+        .order_by_asc(TagColumn::Name)
+        .into_tuple()
+        .all(executor)
+        .await?;
+    for (product_id, name) in rows {
+        tags.entry(product_id).or_default().push(name);
+    }
+
+    Ok(tags)
+}
+
+/// Replace a product's tags, creating tags that don't exist yet in `tenant`, and return the stored
+/// names
+pub async fn set_product_tags(
+    product_id: i32,
+    names: &[String],
+    tenant: &Tenant,
+    executor: &impl ConnectionTrait,
+) -> Result<Vec<String>, DbErr> {
+    let mut names: Vec<String> = names.iter().map(|name| normalize_tag(name)).collect();
+    names.sort();
+    names.dedup();
+
+    ProductTag::delete_many()
+        .filter(ProductTagColumn::ProductId.eq(product_id))
+        .exec(executor)
+        .await?;
+
+    if names.is_empty() {
+        return Ok(names);
+    }
+
+    let new_tags = names.iter().map(|name| TagActiveModel {
+        name: Set(name.clone()),
+        tenant_id: Set(tenant.to_string()),
+        ..Default::default()
+    });
+    Tag::insert_many(new_tags)
+        .on_conflict(
+            OnConflict::columns([TagColumn::TenantId, TagColumn::Name])
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(executor)
+        .await?;
+
+    let tags = Tag::find()
+        .filter(TagColumn::TenantId.eq(tenant.as_str()))
+        .filter(TagColumn::Name.is_in(names.iter().cloned()))
+        .all(executor)
+        .await?;
+    let links = tags.iter().map(|tag| ProductTagActiveModel {
+        product_id: Set(product_id),
+        tag_id: Set(tag.id),
+    });
+    ProductTag::insert_many(links).exec(executor).await?;
+
+    Ok(names)
+}
+
+/// Condition matching products that carry every one of the given tags
+pub fn tag_filter(tags: &[String]) -> Condition {
+    let mut condition = Condition::all();
+
+    for tag in tags {
+        let tag_ids = Query::select()
+            .column(TagColumn::Id)
+            .from(Tag)
+            .and_where(TagColumn::Name.eq(normalize_tag(tag)))
+            .to_owned();
+        let tagged_products = Query::select()
+            .column(ProductTagColumn::ProductId)
+            .from(ProductTag)
+            .and_where(ProductTagColumn::TagId.in_subquery(tag_ids))
+            .to_owned();
+        condition = condition.add(ProductColumn::Id.in_subquery(tagged_products));
+    }
+
+    condition
+}
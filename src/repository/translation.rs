@@ -0,0 +1,467 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use sea_orm::sea_query::{Expr, OnConflict, Query};
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DbBackend, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
+
+use crate::config::LocalizationConfig;
+use crate::database::DatabaseConnection;
+use crate::entity::{
+    Category, CategoryColumn, CategoryTranslation, CategoryTranslationActiveModel, CategoryTranslationColumn,
+    CategoryTranslationModel, Product, ProductColumn, ProductTranslation, ProductTranslationActiveModel,
+    ProductTranslationColumn, ProductTranslationModel,
+};
+use crate::error::ApiError;
+use crate::locale::{Locale, LocaleChain};
+use crate::metrics;
+use crate::models::lifecycle::ProductStatus;
+use crate::models::product::ProductResponse;
+use crate::models::translation::{CategoryTranslationRequest, ProductTranslationRequest, TranslationResponse};
+use crate::tenant::Tenant;
+
+/// Repository for product and category translations within one tenant's catalog
+///
+/// Translations aren't part of cached responses; they are applied after the cache is read, so
+/// changing them needs no invalidation.
+#[derive(Clone)]
+pub struct TranslationRepository {
+    conn: DatabaseConnection,
+    config: LocalizationConfig,
+    tenant: Tenant,
+}
+
+impl TranslationRepository {
+    /// Create a new translation repository working on the default tenant
+    pub fn new(conn: DatabaseConnection, config: &LocalizationConfig) -> Self {
+        Self {
+            conn,
+            config: config.clone(),
+            tenant: Tenant::default(),
+        }
+    }
+
+    /// A copy of this repository working on `tenant`'s catalog
+    pub fn for_tenant(&self, tenant: &Tenant) -> Self {
+        Self {
+            tenant: tenant.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// List a product's translations, ordered by locale
+    ///
+    /// Products that aren't published are reported as missing unless `include_unpublished` is set.
+    pub async fn list_product_translations(
+        &self,
+        product_id: i32,
+        include_unpublished: bool,
+    ) -> Result<Vec<TranslationResponse>, ApiError> {
+        let product = Product::find_by_id(product_id)
+            .filter(ProductColumn::TenantId.eq(self.tenant.as_str()))
+            .one(&self.conn)
+            .await
+            .map_err(ApiError::Database)?
+            .filter(|product| include_unpublished || product.status == ProductStatus::Published.as_str())
+            .ok_or_else(|| ApiError::not_found_simple("Product not found"))?;
+
+        let translations = ProductTranslation::find()
+            .filter(ProductTranslationColumn::ProductId.eq(product.id))
+            .order_by_asc(ProductTranslationColumn::Locale)
+            .all(&self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+
+        translations.into_iter().map(TranslationResponse::try_from).collect()
+    }
+
+    /// Create or replace a product's translation into `locale`
+    pub async fn put_product_translation(
+        &self,
+        product_id: i32,
+        locale: &str,
+        req: ProductTranslationRequest,
+    ) -> Result<TranslationResponse, ApiError> {
+        let locale = self.check_locale(locale)?;
+        self.check_product(product_id).await?;
+
+        let translation = ProductTranslationActiveModel {
+            product_id: Set(product_id),
+            locale: Set(locale.to_string()),
+            name: Set(req.name),
+            description: Set(req.description),
+            updated_at: Set(Utc::now().into()),
+            ..Default::default()
+        };
+        let translation = metrics::time_transaction(
+            "put_product_translation",
+            ProductTranslation::insert(translation)
+                .on_conflict(
+                    OnConflict::columns([ProductTranslationColumn::ProductId, ProductTranslationColumn::Locale])
+                        .update_columns([
+                            ProductTranslationColumn::Name,
+                            ProductTranslationColumn::Description,
+                            ProductTranslationColumn::UpdatedAt,
+                        ])
+                        .to_owned(),
+                )
+                .exec_with_returning(&self.conn),
+        )
+        .await
+        .map_err(ApiError::Database)?;
+
+        TranslationResponse::try_from(translation)
+    }
+
+    /// Delete a product's translation into `locale`
+    pub async fn delete_product_translation(&self, product_id: i32, locale: &str) -> Result<(), ApiError> {
+        let locale = self.check_locale(locale)?;
+        self.check_product(product_id).await?;
+
+        let result = metrics::time_transaction(
+            "delete_product_translation",
+            ProductTranslation::delete_by_id((product_id, locale.to_string())).exec(&self.conn),
+        )
+        .await
+        .map_err(ApiError::Database)?;
+        if result.rows_affected == 0 {
+            return Err(ApiError::not_found_simple("Translation not found"));
+        }
+
+        Ok(())
+    }
+
+    /// List a category's translations, ordered by locale
+    pub async fn list_category_translations(&self, category_id: i32) -> Result<Vec<TranslationResponse>, ApiError> {
+        self.check_category(category_id).await?;
+
+        let translations = CategoryTranslation::find()
+            .filter(CategoryTranslationColumn::CategoryId.eq(category_id))
+            .order_by_asc(CategoryTranslationColumn::Locale)
+            .all(&self.conn)
+            .await
+            .map_err(ApiError::Database)?;
+
+        translations.into_iter().map(TranslationResponse::try_from).collect()
+    }
+
+    /// Create or replace a category's translation into `locale`
+    pub async fn put_category_translation(
+        &self,
+        category_id: i32,
+        locale: &str,
+        req: CategoryTranslationRequest,
+    ) -> Result<TranslationResponse, ApiError> {
+        let locale = self.check_locale(locale)?;
+        self.check_category(category_id).await?;
+
+        let translation = CategoryTranslationActiveModel {
+            category_id: Set(category_id),
+            locale: Set(locale.to_string()),
+            name: Set(req.name),
+            description: Set(req.description),
+            updated_at: Set(Utc::now().into()),
+            ..Default::default()
+        };
+        let translation = metrics::time_transaction(
+            "put_category_translation",
+            CategoryTranslation::insert(translation)
+                .on_conflict(
+                    OnConflict::columns([CategoryTranslationColumn::CategoryId, CategoryTranslationColumn::Locale])
+                        .update_columns([
+                            CategoryTranslationColumn::Name,
+                            CategoryTranslationColumn::Description,
+                            CategoryTranslationColumn::UpdatedAt,
+                        ])
+                        .to_owned(),
+                )
+                .exec_with_returning(&self.conn),
+        )
+        .await
+        .map_err(ApiError::Database)?;
+
+        TranslationResponse::try_from(translation)
+    }
+
+    /// Delete a category's translation into `locale`
+    pub async fn delete_category_translation(&self, category_id: i32, locale: &str) -> Result<(), ApiError> {
+        let locale = self.check_locale(locale)?;
+        self.check_category(category_id).await?;
+
+        let result = metrics::time_transaction(
+            "delete_category_translation",
+            CategoryTranslation::delete_by_id((category_id, locale.to_string())).exec(&self.conn),
+        )
+        .await
+        .map_err(ApiError::Database)?;
+        if result.rows_affected == 0 {
+            return Err(ApiError::not_found_simple("Translation not found"));
+        }
+
+        Ok(())
+    }
+
+    /// Parse a locale that content can be translated into: any supported locale but the default
+    fn check_locale(&self, locale: &str) -> Result<Locale, ApiError> {
+        let locale: Locale = locale
+            .parse()
+            .map_err(|e| ApiError::bad_request(format!("locale: {}", e)))?;
+
+        if locale == self.config.default_locale {
+            return Err(ApiError::bad_request(format!(
+                "locale: text in the default locale {} is edited on the product or category itself",
+                locale
+            )));
+        }
+        if !self.config.supported_locales.contains(&locale) {
+            return Err(ApiError::bad_request(format!("locale: {} is not supported", locale)));
+        }
+
+        Ok(locale)
+    }
+
+    /// Check that a product exists and belongs to this repository's tenant
+    async fn check_product(&self, product_id: i32) -> Result<(), ApiError> {
+        Product::find_by_id(product_id)
+            .filter(ProductColumn::TenantId.eq(self.tenant.as_str()))
+            .one(&self.conn)
+            .await
+            .map_err(ApiError::Database)?
+            .ok_or_else(|| ApiError::not_found_simple("Product not found"))?;
+
+        Ok(())
+    }
+
+    /// Check that a category exists and belongs to this repository's tenant
+    async fn check_category(&self, category_id: i32) -> Result<(), ApiError> {
+        Category::find_by_id(category_id)
+            .filter(CategoryColumn::TenantId.eq(self.tenant.as_str()))
+            .one(&self.conn)
+            .await
+            .map_err(ApiError::Database)?
+            .ok_or_else(|| ApiError::not_found_simple("Category not found"))?;
+
+        Ok(())
+    }
+}
+
+impl TryFrom<ProductTranslationModel> for TranslationResponse {
+    type Error = ApiError;
+
+    fn try_from(model: ProductTranslationModel) -> Result<Self, Self::Error> {
+        Ok(Self {
+            locale: model.locale.parse().map_err(ApiError::internal_server_error)?,
+            name: model.name,
+            description: model.description,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+        })
+    }
+}
+
+impl TryFrom<CategoryTranslationModel> for TranslationResponse {
+    type Error = ApiError;
+
+    fn try_from(model: CategoryTranslationModel) -> Result<Self, Self::Error> {
+        Ok(Self {
+            locale: model.locale.parse().map_err(ApiError::internal_server_error)?,
+            name: model.name,
+            description: model.description,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+        })
+    }
+}
+
+/// Name and description of a product or category in the most preferred locale translating them
+///
+/// A translation without a description takes it from the next locale in the chain that has one.
+#[derive(Debug, Default)]
+pub struct LocalizedText {
+    name: Option<String>,
+    description: Option<String>,
+}
+
+impl LocalizedText {
+    /// Replace untranslated text with this translation
+    pub fn apply(self, name: &mut String, description: &mut Option<String>) {
+        if let Some(translated) = self.name {
+            *name = translated;
+        }
+        if self.description.is_some() {
+            *description = self.description;
+        }
+    }
+}
+
+/// Translate products and the names of their categories along `chain`
+pub async fn localize_products(
+    products: &mut [ProductResponse],
+    chain: &LocaleChain,
+    executor: &impl ConnectionTrait,
+) -> Result<(), DbErr> {
+    if chain.translated().is_empty() || products.is_empty() {
+        return Ok(());
+    }
+
+    let product_ids: Vec<i32> = products.iter().map(|product| product.id).collect();
+    let mut product_texts = get_product_texts(&product_ids, chain, executor).await?;
+    let category_ids: Vec<i32> = products
+        .iter()
+        .flat_map(|product| product.categories.iter().map(|category| category.id))
+        .collect();
+    let category_texts = get_category_texts(&category_ids, chain, executor).await?;
+
+    for product in products {
+        if let Some(text) = product_texts.remove(&product.id) {
+            text.apply(&mut product.name, &mut product.description);
+        }
+        for category in &mut product.categories {
+            if let Some(name) = category_texts.get(&category.id).and_then(|text| text.name.clone()) {
+                category.name = name;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the translated text of products along `chain`, keyed by product ID
+pub async fn get_product_texts(
+    product_ids: &[i32],
+    chain: &LocaleChain,
+    executor: &impl ConnectionTrait,
+) -> Result<HashMap<i32, LocalizedText>, DbErr> {
+    if chain.translated().is_empty() || product_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let rows: Vec<(i32, String, String, Option<String>)> = ProductTranslation::find()
+        .select_only()
+        .column(ProductTranslationColumn::ProductId)
+        .column(ProductTranslationColumn::Locale)
+        .column(ProductTranslationColumn::Name)
+        .column(ProductTranslationColumn::Description)
+        .filter(ProductTranslationColumn::ProductId.is_in(product_ids.iter().copied()))
+        .filter(ProductTranslationColumn::Locale.is_in(chain.translated().iter().map(Locale::as_str)))
+        .into_tuple()
+        .all(executor)
+        .await?;
+
+    Ok(resolve_texts(rows, chain))
+}
+
+/// Get the translated text of categories along `chain`, keyed by category ID
+pub async fn get_category_texts(
+    category_ids: &[i32],
+    chain: &LocaleChain,
+    executor: &impl ConnectionTrait,
+) -> Result<HashMap<i32, LocalizedText>, DbErr> {
+    if chain.translated().is_empty() || category_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let rows: Vec<(i32, String, String, Option<String>)> = CategoryTranslation::find()
+        .select_only()
+        .column(CategoryTranslationColumn::CategoryId)
+        .column(CategoryTranslationColumn::Locale)
+        .column(CategoryTranslationColumn::Name)
+        .column(CategoryTranslationColumn::Description)
+        .filter(CategoryTranslationColumn::CategoryId.is_in(category_ids.iter().copied()))
+        .filter(CategoryTranslationColumn::Locale.is_in(chain.translated().iter().map(Locale::as_str)))
+        .into_tuple()
+        .all(executor)
+        .await?;
+
+    Ok(resolve_texts(rows, chain))
+}
+
+/// Condition matching products whose text matches the web search query `query`
+///
+/// Untranslated text is searched with the default locale's text search configuration, and each
+/// translation along `chain` with its own locale's, so stemming suits the language searched.
+/// SQLite has no text search configurations; there every word of the query must appear in the
+/// text as typed, ignoring ASCII case, and words prefixed with `-` must not.
+pub fn search_filter(query: &str, chain: &LocaleChain, backend: DbBackend) -> Condition {
+    let text = r#""products"."name" || ' ' || coalesce("products"."description", '')"#;
+    let config = chain.default_locale().text_search_config();
+    let mut condition = Condition::any().add(text_matches(text, config, query, backend));
+
+    for locale in chain.translated() {
+        let text = r#""name" || ' ' || coalesce("description", '')"#;
+        let matching_products = Query::select()
+            .column(ProductTranslationColumn::ProductId)
+            .from(ProductTranslation)
+            .and_where(ProductTranslationColumn::Locale.eq(locale.as_str()))
+            .cond_where(text_matches(text, locale.text_search_config(), query, backend))
+            .to_owned();
+        condition = condition.add(ProductColumn::Id.in_subquery(matching_products));
+    }
+
+    condition
+}
+
+/// Condition matching rows whose `text` expression matches the web search query `query`
+fn text_matches(text: &str, config: &str, query: &str, backend: DbBackend) -> Condition {
+    if backend != DbBackend::Sqlite {
+        return Condition::all().add(Expr::cust_with_values(
+            format!("to_tsvector('{config}', {text}) @@ websearch_to_tsquery('{config}', $1)"),
+            [query.to_string()],
+        ));
+    }
+
+    let mut condition = Condition::all();
+    let mut words = 0;
+    for word in query.split_whitespace().map(|word| word.trim_matches('"')) {
+        let (word, operator) = match word.strip_prefix('-') {
+            Some(excluded) => (excluded, "NOT LIKE"),
+            None => (word, "LIKE"),
+        };
+        if word.is_empty() {
+            continue;
+        }
+
+        let pattern = format!(
+            "%{}%",
+            word.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+        );
+        condition = condition.add(Expr::cust_with_values(
+            format!("{text} {operator} ? ESCAPE '\\'"),
+            [pattern],
+        ));
+        words += 1;
+    }
+
+    // Like an empty text search query, a query of only operators matches nothing
+    if words == 0 {
+        condition = condition.add(Expr::cust("1 = 0"));
+    }
+
+    condition
+}
+
+/// Fold translation rows into each entity's text, preferring locales earlier in `chain`
+fn resolve_texts(
+    mut rows: Vec<(i32, String, String, Option<String>)>,
+    chain: &LocaleChain,
+) -> HashMap<i32, LocalizedText> {
+    let rank = |locale: &str| {
+        chain
+            .translated()
+            .iter()
+            .position(|candidate| candidate.as_str() == locale)
+    };
+    rows.sort_by_key(|(_, locale, _, _)| rank(locale));
+
+    let mut texts: HashMap<i32, LocalizedText> = HashMap::new();
+    for (id, _, name, description) in rows {
+        let text = texts.entry(id).or_default();
+        text.name.get_or_insert(name);
+        if text.description.is_none() {
+            text.description = description;
+        }
+    }
+
+    texts
+}
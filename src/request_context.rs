@@ -0,0 +1,148 @@
+use std::convert::Infallible;
+
+use async_trait::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, HeaderValue, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use opentelemetry::propagation::{Extractor, TextMapPropagator};
+use opentelemetry::trace::{SpanContext, TraceContextExt};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use tracing::Instrument;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Longest client-supplied request ID we accept before generating our own
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Identifiers correlating a request across logs, traces and services
+#[derive(Clone, Debug)]
+pub struct RequestContext {
+    pub request_id: String,
+    pub trace_id: String,
+    pub span_id: String,
+    pub sampled: bool,
+}
+
+impl RequestContext {
+    /// Build a context from incoming headers, generating any identifiers that are missing or invalid
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let request_id = headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .filter(|value| is_valid_request_id(value))
+            .map(str::to_string)
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+        let parent = remote_parent(headers);
+        let (trace_id, sampled) = match parent.as_ref() {
+            Some(parent) => (parent.trace_id().to_string(), parent.is_sampled()),
+            None => (Uuid::new_v4().simple().to_string(), true),
+        };
+
+        Self {
+            request_id,
+            trace_id,
+            span_id: new_span_id(),
+            sampled,
+        }
+    }
+
+    /// W3C `traceparent` value identifying this hop of the trace
+    pub fn traceparent(&self) -> String {
+        format!(
+            "00-{}-{}-{}",
+            self.trace_id,
+            self.span_id,
+            if self.sampled { "01" } else { "00" }
+        )
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for RequestContext {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<RequestContext>()
+            .cloned()
+            .unwrap_or_else(|| RequestContext::from_headers(&parts.headers)))
+    }
+}
+
+/// Middleware that honors or generates `X-Request-Id` and `traceparent`
+///
+/// Each request runs inside a `request` span carrying both identifiers, the span is parented to
+/// the caller's trace when one was propagated, and the identifiers are echoed on the response.
+pub async fn propagate_request_context<B>(mut request: Request<B>, next: Next<B>) -> Response {
+    let mut ctx = RequestContext::from_headers(request.headers());
+
+    let span = tracing::info_span!(
+        "request",
+        method = %request.method(),
+        path = %request.uri().path(),
+        request_id = %ctx.request_id,
+        trace_id = tracing::field::Empty,
+    );
+
+    if let Some(parent) = remote_parent(request.headers()) {
+        let _ = span.set_parent(opentelemetry::Context::new().with_remote_span_context(parent));
+    }
+
+    // Prefer the identifiers of the exported span when an OpenTelemetry layer is installed
+    let otel_context = span.context();
+    let otel_span = otel_context.span();
+    let span_context = otel_span.span_context();
+    if span_context.is_valid() {
+        ctx.trace_id = span_context.trace_id().to_string();
+        ctx.span_id = span_context.span_id().to_string();
+        ctx.sampled = span_context.is_sampled();
+    }
+    span.record("trace_id", ctx.trace_id.as_str());
+
+    request.extensions_mut().insert(ctx.clone());
+    let mut response = next.run(request).instrument(span).await;
+
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&ctx.request_id) {
+        headers.insert(REQUEST_ID_HEADER, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&ctx.traceparent()) {
+        headers.insert(TRACEPARENT_HEADER, value);
+    }
+
+    response
+}
+
+fn is_valid_request_id(value: &str) -> bool {
+    !value.is_empty() && value.len() <= MAX_REQUEST_ID_LEN && value.bytes().all(|b| b.is_ascii_graphic())
+}
+
+fn new_span_id() -> String {
+    Uuid::new_v4().simple().to_string()[..16].to_string()
+}
+
+/// Parse the caller's span from a W3C `traceparent` header
+fn remote_parent(headers: &HeaderMap) -> Option<SpanContext> {
+    let context = TraceContextPropagator::new().extract(&HeaderExtractor(headers));
+    let span_context = context.span().span_context().clone();
+    span_context.is_valid().then_some(span_context)
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
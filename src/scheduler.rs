@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::time::MissedTickBehavior;
+
+use crate::repository::ProductRepository;
+use crate::shutdown::Shutdown;
+
+/// Apply due publish and unpublish schedules every `interval` until shutdown begins
+///
+/// A failed run is logged and retried on the next tick, so a database outage only delays
+/// scheduled changes.
+pub async fn run(products: ProductRepository, interval: Duration, shutdown: Shutdown) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = shutdown.wait() => return,
+        }
+
+        match products.apply_due_schedules(Utc::now().into()).await {
+            Ok(changed) if !changed.is_empty() => {
+                tracing::info!("Applied publishing schedules to products {:?}", changed)
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to apply publishing schedules: {}", e),
+        }
+    }
+}
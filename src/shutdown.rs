@@ -0,0 +1,101 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// A named background task tracked for shutdown
+type Worker = (String, JoinHandle<()>);
+
+/// Coordinates graceful shutdown of the server and any background workers
+#[derive(Clone)]
+pub struct Shutdown {
+    sender: Arc<watch::Sender<bool>>,
+    workers: Arc<Mutex<Vec<Worker>>>,
+}
+
+impl Shutdown {
+    /// Create a new, untriggered shutdown coordinator
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(false);
+        Self {
+            sender: Arc::new(sender),
+            workers: Arc::default(),
+        }
+    }
+
+    /// Begin shutting down; every waiter is woken
+    pub fn trigger(&self) {
+        self.sender.send_replace(true);
+    }
+
+    /// Whether shutdown has begun
+    pub fn is_triggered(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    /// Wait until shutdown begins
+    pub async fn wait(&self) {
+        let mut receiver = self.sender.subscribe();
+        // The sender lives as long as `self`, so this only returns once triggered
+        let _ = receiver.wait_for(|triggered| *triggered).await;
+    }
+
+    /// Spawn a background worker that is expected to return once [`Shutdown::wait`] resolves
+    pub fn spawn_worker<F, Fut>(&self, name: impl Into<String>, worker: F)
+    where
+        F: FnOnce(Shutdown) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handle = tokio::spawn(worker(self.clone()));
+        self.workers
+            .lock()
+            .expect("worker list lock poisoned")
+            .push((name.into(), handle));
+    }
+
+    /// Wait for every background worker to finish, aborting any still running after `timeout`
+    pub async fn join_workers(&self, timeout: Duration) {
+        let workers = std::mem::take(&mut *self.workers.lock().expect("worker list lock poisoned"));
+
+        for (name, mut handle) in workers {
+            match tokio::time::timeout(timeout, &mut handle).await {
+                Ok(_) => tracing::info!("Background worker {} stopped", name),
+                Err(_) => {
+                    tracing::warn!("Background worker {} did not stop in time, aborting", name);
+                    handle.abort();
+                }
+            }
+        }
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Wait for SIGINT or SIGTERM
+pub async fn signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.expect("failed to install SIGINT handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => tracing::info!("Received SIGINT, shutting down"),
+        _ = terminate => tracing::info!("Received SIGTERM, shutting down"),
+    }
+}
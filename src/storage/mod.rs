@@ -0,0 +1,76 @@
+pub mod local;
+pub mod s3;
+
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use bytes::Bytes;
+
+pub use local::LocalStore;
+pub use s3::S3Store;
+
+use crate::config::StorageConfig;
+
+/// Shared handle to the configured media store
+pub type MediaStorage = Arc<dyn MediaStore>;
+
+/// Build the media store selected by the configuration
+pub async fn connect(config: &StorageConfig) -> Result<MediaStorage> {
+    let storage: MediaStorage = match config.backend.as_str() {
+        "local" => Arc::new(LocalStore::new(&config.local_dir)?),
+        "s3" => {
+            let (Some(endpoint), Some(bucket), Some(access_key_id), Some(secret_access_key)) = (
+                &config.s3_endpoint,
+                &config.s3_bucket,
+                &config.s3_access_key_id,
+                &config.s3_secret_access_key,
+            ) else {
+                bail!("storage.s3_endpoint, s3_bucket and credentials must be set for the s3 backend");
+            };
+            Arc::new(S3Store::new(
+                endpoint,
+                bucket,
+                &config.s3_region,
+                access_key_id,
+                secret_access_key,
+            )?)
+        }
+        other => bail!("Unknown storage backend: {}", other),
+    };
+
+    Ok(storage)
+}
+
+/// A blob store holding uploaded media files under opaque keys
+///
+/// Keys are generated by the application and only contain `[A-Za-z0-9._/-]`.
+#[async_trait]
+pub trait MediaStore: Send + Sync {
+    /// Store `data` under `key`, replacing any existing object
+    async fn put(&self, key: &str, data: Bytes, content_type: &str) -> Result<()>;
+
+    /// Fetch the object stored under `key`, if any
+    async fn get(&self, key: &str) -> Result<Option<Bytes>>;
+
+    /// Remove the object stored under `key`; removing a missing object succeeds
+    async fn delete(&self, key: &str) -> Result<()>;
+
+    /// Short name of the backend, used in logs
+    fn backend(&self) -> &'static str;
+}
+
+/// Reject keys that could escape the store's namespace
+fn check_key(key: &str) -> Result<()> {
+    let valid = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '/' | '-'))
+        && key
+            .split('/')
+            .all(|segment| !segment.is_empty() && segment != "." && segment != "..");
+    if !valid {
+        bail!("Invalid storage key: {:?}", key);
+    }
+    Ok(())
+}
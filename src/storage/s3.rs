@@ -0,0 +1,220 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use axum::http::{Method, Request, StatusCode, Uri};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use hyper::Body;
+use hyper::client::HttpConnector;
+use hyper_rustls::HttpsConnector;
+use sha2::{Digest, Sha256};
+
+use super::{MediaStore, check_key};
+
+/// Upper bound on a single request to the object store
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Media store backed by an S3-compatible bucket, addressed path-style
+///
+/// Requests are signed with AWS Signature Version 4, which AWS S3, MinIO, Ceph and most other
+/// S3-compatible services accept.
+pub struct S3Store {
+    client: hyper::Client<HttpsConnector<HttpConnector>>,
+    endpoint: String,
+    bucket: String,
+    credentials: Credentials,
+}
+
+/// Access key and the region requests are signed for
+pub struct Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub region: String,
+}
+
+impl S3Store {
+    /// Create a store for `bucket` at `endpoint`, e.g. `https://s3.us-east-1.amazonaws.com`
+    pub fn new(
+        endpoint: &str,
+        bucket: &str,
+        region: &str,
+        access_key_id: &str,
+        secret_access_key: &str,
+    ) -> Result<Self> {
+        endpoint
+            .parse::<Uri>()
+            .with_context(|| format!("invalid S3 endpoint {}", endpoint))?;
+
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .https_or_http()
+            .enable_http1()
+            .build();
+
+        Ok(Self {
+            client: hyper::Client::builder().build(connector),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket: bucket.to_string(),
+            credentials: Credentials {
+                access_key_id: access_key_id.to_string(),
+                secret_access_key: secret_access_key.to_string(),
+                region: region.to_string(),
+            },
+        })
+    }
+
+    /// Send a signed request for the object under `key`
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        body: Bytes,
+        content_type: Option<&str>,
+    ) -> Result<(StatusCode, Bytes)> {
+        check_key(key)?;
+        let uri: Uri = format!("{}/{}/{}", self.endpoint, self.bucket, key).parse()?;
+        let host = uri
+            .authority()
+            .ok_or_else(|| anyhow!("S3 endpoint has no host"))?
+            .to_string();
+
+        let payload_hash = hex::encode(Sha256::digest(&body));
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let mut headers = vec![
+            ("host", host.as_str()),
+            ("x-amz-content-sha256", payload_hash.as_str()),
+            ("x-amz-date", amz_date.as_str()),
+        ];
+        if let Some(content_type) = content_type {
+            headers.push(("content-type", content_type));
+        }
+        let authorization = sign(
+            &self.credentials,
+            method.as_str(),
+            uri.path(),
+            &headers,
+            &payload_hash,
+            now,
+        );
+
+        let mut request = Request::builder().method(method).uri(uri);
+        for (name, value) in &headers {
+            if *name != "host" {
+                request = request.header(*name, *value);
+            }
+        }
+        let request = request.header("authorization", authorization).body(Body::from(body))?;
+
+        let response = tokio::time::timeout(REQUEST_TIMEOUT, self.client.request(request))
+            .await
+            .context("S3 request timed out")??;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+
+        Ok((status, body))
+    }
+}
+
+#[async_trait]
+impl MediaStore for S3Store {
+    async fn put(&self, key: &str, data: Bytes, content_type: &str) -> Result<()> {
+        let (status, body) = self.send(Method::PUT, key, data, Some(content_type)).await?;
+        if !status.is_success() {
+            bail!(
+                "S3 PUT {} failed with {}: {}",
+                key,
+                status,
+                String::from_utf8_lossy(&body)
+            );
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Bytes>> {
+        let (status, body) = self.send(Method::GET, key, Bytes::new(), None).await?;
+        match status {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => Ok(Some(body)),
+            status => bail!(
+                "S3 GET {} failed with {}: {}",
+                key,
+                status,
+                String::from_utf8_lossy(&body)
+            ),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        let (status, body) = self.send(Method::DELETE, key, Bytes::new(), None).await?;
+        if !status.is_success() && status != StatusCode::NOT_FOUND {
+            bail!(
+                "S3 DELETE {} failed with {}: {}",
+                key,
+                status,
+                String::from_utf8_lossy(&body)
+            );
+        }
+        Ok(())
+    }
+
+    fn backend(&self) -> &'static str {
+        "s3"
+    }
+}
+
+/// Compute the SigV4 `Authorization` header for a request without a query string
+///
+/// `headers` are the lower-case headers to sign, including `host`; `path` must already be
+/// URI-encoded.
+pub fn sign(
+    credentials: &Credentials,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    payload_hash: &str,
+    now: DateTime<Utc>,
+) -> String {
+    let mut headers = headers.to_vec();
+    headers.sort_by_key(|(name, _)| *name);
+
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+        .collect();
+    let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+    let canonical_request = format!(
+        "{}\n{}\n\n{}\n{}\n{}",
+        method, path, canonical_headers, signed_headers, payload_hash
+    );
+
+    let date = now.format("%Y%m%d").to_string();
+    let scope = format!("{}/{}/s3/aws4_request", date, credentials.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        now.format("%Y%m%dT%H%M%SZ"),
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let key = [date.as_str(), credentials.region.as_str(), "s3", "aws4_request"]
+        .iter()
+        .fold(
+            format!("AWS4{}", credentials.secret_access_key).into_bytes(),
+            |key, part| hmac(&key, part.as_bytes()),
+        );
+    let signature = hex::encode(hmac(&key, string_to_sign.as_bytes()));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        credentials.access_key_id, scope, signed_headers, signature
+    )
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
@@ -0,0 +1,74 @@
+use anyhow::{Result, anyhow};
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{Protocol, WithExportConfig};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, fmt};
+
+use crate::config::TelemetryConfig;
+
+/// Handle to the installed tracing pipeline, flushed on shutdown
+pub struct Telemetry {
+    provider: Option<SdkTracerProvider>,
+}
+
+impl Telemetry {
+    /// Flush and stop the OTLP exporter, if any
+    pub fn shutdown(self) {
+        if let Some(provider) = self.provider
+            && let Err(e) = provider.shutdown()
+        {
+            eprintln!("Failed to shut down OpenTelemetry exporter: {:?}", e);
+        }
+    }
+}
+
+/// Install the global tracing subscriber: log output plus an optional OTLP exporter
+pub fn init(config: &TelemetryConfig) -> Result<Telemetry> {
+    let provider = match &config.otlp_endpoint {
+        Some(endpoint) => Some(tracer_provider(endpoint, &config.service_name)?),
+        None => None,
+    };
+
+    let otel_layer = provider
+        .as_ref()
+        .map(|provider| tracing_opentelemetry::layer().with_tracer(provider.tracer(config.service_name.clone())));
+    let (json_layer, text_layer) = match config.log_format.as_str() {
+        "json" => (
+            Some(fmt::layer().json().with_current_span(true).with_span_list(true)),
+            None,
+        ),
+        "text" => (None, Some(fmt::layer())),
+        other => return Err(anyhow!("Unknown log format: {}", other)),
+    };
+
+    tracing_subscriber::registry()
+        .with(EnvFilter::new(&config.log_level))
+        .with(json_layer)
+        .with(text_layer)
+        .with(otel_layer)
+        .try_init()?;
+
+    if let Some(endpoint) = &config.otlp_endpoint {
+        tracing::info!("Exporting traces over OTLP to {}", endpoint);
+    }
+
+    Ok(Telemetry { provider })
+}
+
+/// Create a tracer provider exporting spans as OTLP/HTTP JSON to the collector at `endpoint`
+pub fn tracer_provider(endpoint: &str, service_name: &str) -> Result<SdkTracerProvider> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_protocol(Protocol::HttpJson)
+        .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+        .build()
+        .map_err(|e| anyhow!("Failed to build OTLP exporter: {:?}", e))?;
+
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(service_name.to_string()).build())
+        .build())
+}
@@ -0,0 +1,157 @@
+use std::fmt;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use axum::extract::{FromRequestParts, State};
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, Request, header};
+use axum::middleware::Next;
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::Editor;
+use crate::config::TenancyConfig;
+use crate::error::ApiError;
+
+/// Tenant of requests that don't name one, and of every row created before tenancy
+pub const DEFAULT_TENANT: &str = "default";
+
+/// Longest tenant ID accepted, so it always fits in a DNS label
+const MAX_TENANT_LEN: usize = 63;
+
+/// The catalog a request works on
+///
+/// Tenant IDs are slugs of lowercase letters, digits and hyphens that start with a letter or
+/// digit, so the same ID works in a header, a subdomain and a cache key.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Tenant(String);
+
+impl Tenant {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for Tenant {
+    fn default() -> Self {
+        Self(DEFAULT_TENANT.to_string())
+    }
+}
+
+impl fmt::Display for Tenant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.0)
+    }
+}
+
+impl FromStr for Tenant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let valid = !s.is_empty()
+            && s.len() <= MAX_TENANT_LEN
+            && !s.starts_with('-')
+            && s.chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if valid {
+            Ok(Self(s.to_string()))
+        } else {
+            Err(format!(
+                "invalid tenant {:?}; expected up to {} lowercase letters, digits or hyphens",
+                s, MAX_TENANT_LEN
+            ))
+        }
+    }
+}
+
+impl TryFrom<String> for Tenant {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Tenant> for String {
+    fn from(tenant: Tenant) -> Self {
+        tenant.0
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Tenant {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        // Falling back to a default here could serve one tenant's data to another
+        parts
+            .extensions
+            .get::<Tenant>()
+            .cloned()
+            .ok_or_else(|| ApiError::internal_server_error("Tenant was not resolved for this request"))
+    }
+}
+
+/// Middleware that resolves the [`Tenant`] of a request
+///
+/// An editor's API key belongs to a tenant, which is used whatever the request asks for; naming a
+/// different tenant in the header or subdomain is forbidden. Anonymous requests use the tenant
+/// header, then the subdomain of `base_domain`, then [`DEFAULT_TENANT`]. Must run after
+/// [`crate::auth::authenticate`].
+pub async fn resolve_tenant<B>(
+    State(config): State<TenancyConfig>,
+    mut request: Request<B>,
+    next: Next<B>,
+) -> Result<Response, ApiError> {
+    let requested = requested_tenant(&config, request.headers())?;
+
+    let tenant = match (request.extensions().get::<Editor>(), requested) {
+        (Some(editor), Some(requested)) if requested != editor.tenant => {
+            return Err(ApiError::Forbidden(format!(
+                "API key does not grant access to tenant {}",
+                requested
+            )));
+        }
+        (Some(editor), _) => editor.tenant.clone(),
+        (None, requested) => requested.unwrap_or_default(),
+    };
+    request.extensions_mut().insert(tenant);
+
+    Ok(next.run(request).await)
+}
+
+/// The tenant named by the tenant header or, failing that, by the request's subdomain
+fn requested_tenant(config: &TenancyConfig, headers: &HeaderMap) -> Result<Option<Tenant>, ApiError> {
+    if let Some(value) = headers.get(config.header.as_str()) {
+        let value = value
+            .to_str()
+            .map_err(|_| ApiError::bad_request(format!("{}: must be a tenant ID", config.header)))?;
+        return value
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|e| ApiError::bad_request(format!("{}: {}", config.header, e)));
+    }
+
+    let Some(base_domain) = &config.base_domain else {
+        return Ok(None);
+    };
+    let host = headers
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let host = host.split(':').next().unwrap_or_default();
+
+    match host
+        .strip_suffix(base_domain.as_str())
+        .and_then(|rest| rest.strip_suffix('.'))
+    {
+        Some(subdomain) if !subdomain.is_empty() && !subdomain.contains('.') => subdomain
+            .parse()
+            .map(Some)
+            .map_err(|e| ApiError::bad_request(format!("Host: {}", e))),
+        _ => Ok(None),
+    }
+}
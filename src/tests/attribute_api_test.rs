@@ -0,0 +1,321 @@
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+use super::common::{create_test_app, create_test_category, initialize};
+
+/// Send a request with an optional JSON body and return the status and parsed JSON response
+async fn send(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Content-Type", "application/json");
+    let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+
+    let response = app.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+    let status = response.status();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+/// Create a category with `voltage` (required, volts), `color` (red or blue) and `wireless` attributes
+async fn create_electronics(app: &Router) -> i64 {
+    let (status, category) = send(app, "POST", "/api/categories", Some(json!({ "name": "Electronics" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    let id = category["id"].as_i64().unwrap();
+
+    for attribute in [
+        json!({ "name": "voltage", "type": "unit", "unit": "V", "required": true }),
+        json!({ "name": "color", "type": "enum", "values": ["red", "blue"] }),
+        json!({ "name": "wireless", "type": "bool" }),
+    ] {
+        let (status, _) = send(
+            app,
+            "POST",
+            &format!("/api/categories/{}/attributes", id),
+            Some(attribute),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    id
+}
+
+async fn create_product(app: &Router, sku: &str, category_ids: Vec<i64>, attributes: Value) -> (StatusCode, Value) {
+    let body = json!({
+        "name": sku,
+        "price": "10.00",
+        "sku": sku,
+        "category_ids": category_ids,
+        "attributes": attributes,
+    });
+    send(app, "POST", "/api/products", Some(body)).await
+}
+
+fn listed_skus(list: &Value) -> Vec<&str> {
+    let mut skus: Vec<&str> = list["products"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|product| product["sku"].as_str().unwrap())
+        .collect();
+    skus.sort();
+    skus
+}
+
+#[tokio::test]
+async fn test_attribute_definitions() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+
+    let category_id = create_electronics(&app).await;
+    let uri = format!("/api/categories/{}/attributes", category_id);
+
+    let (status, list) = send(&app, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let names: Vec<&str> = list
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|attribute| attribute["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["color", "voltage", "wireless"]);
+    assert_eq!(list[1]["type"], "unit");
+    assert_eq!(list[1]["unit"], "V");
+    assert_eq!(list[1]["required"], true);
+    assert_eq!(list[0]["values"], json!(["red", "blue"]));
+
+    // Names are unique within a category
+    let (status, _) = send(&app, "POST", &uri, Some(json!({ "name": "color", "type": "string" }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // Allowed values and units must suit the type, and names must be usable in query parameters
+    for (attribute, message) in [
+        (
+            json!({ "name": "size", "type": "enum" }),
+            "values: required for enum attributes",
+        ),
+        (
+            json!({ "name": "size", "type": "number", "unit": "cm" }),
+            "unit: only allowed for unit attributes",
+        ),
+        (
+            json!({ "name": "Size", "type": "string" }),
+            "name: Attribute name must start",
+        ),
+    ] {
+        let (status, error) = send(&app, "POST", &uri, Some(attribute)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(
+            error["error"]["message"].as_str().unwrap().contains(message),
+            "{}",
+            error
+        );
+    }
+
+    let (status, error) = send(&app, "POST", &uri, Some(json!({ "name": "size", "type": "weight" }))).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{}", error);
+
+    let voltage_id = list[1]["id"].as_i64().unwrap();
+    let (status, updated) = send(
+        &app,
+        "PUT",
+        &format!("{}/{}", uri, voltage_id),
+        Some(json!({ "required": false, "unit": "kV" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["required"], false);
+    assert_eq!(updated["unit"], "kV");
+
+    // Definitions are addressed through their own category
+    let (status, _) = send(&app, "GET", "/api/categories/999999/attributes", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let other = create_test_category(&app).await;
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/api/categories/{}/attributes/{}", other.id, voltage_id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send(&app, "DELETE", &format!("{}/{}", uri, voltage_id), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, list) = send(&app, "GET", &uri, None).await;
+    assert_eq!(list.as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_product_attributes_are_validated() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+
+    let category_id = create_electronics(&app).await;
+
+    let (status, product) = create_product(
+        &app,
+        "ATTR-LAMP",
+        vec![category_id],
+        json!({ "voltage": 220, "color": "red", "wireless": false }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        product["attributes"],
+        json!({ "color": "red", "voltage": 220, "wireless": false })
+    );
+
+    let (_, fetched) = send(&app, "GET", &format!("/api/products/{}", product["id"]), None).await;
+    assert_eq!(fetched["attributes"], product["attributes"]);
+
+    // Every problem is reported at once
+    let (status, error) = create_product(
+        &app,
+        "ATTR-BAD",
+        vec![category_id],
+        json!({ "color": "green", "wireless": "yes", "weight": 3 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let message = error["error"]["message"].as_str().unwrap();
+    assert!(
+        message.contains("attributes.color: must be one of red, blue"),
+        "{}",
+        message
+    );
+    assert!(message.contains("attributes.voltage: is required"), "{}", message);
+    assert!(message.contains("attributes.weight: not defined"), "{}", message);
+    assert!(
+        message.contains("attributes.wireless: must be true or false"),
+        "{}",
+        message
+    );
+
+    // A failed create leaves nothing behind
+    let (_, list) = send(&app, "GET", "/api/products", None).await;
+    assert_eq!(list["total"], 1);
+
+    // Updates replace the whole set of values
+    let uri = format!("/api/products/{}", product["id"]);
+    let (status, updated) = send(&app, "PUT", &uri, Some(json!({ "attributes": { "voltage": 110 } }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["attributes"], json!({ "voltage": 110 }));
+
+    let (status, _) = send(&app, "PUT", &uri, Some(json!({ "attributes": { "voltage": "110V" } }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Updates that touch neither attributes nor categories leave the values alone
+    let (status, updated) = send(&app, "PUT", &uri, Some(json!({ "name": "Desk Lamp" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["attributes"], json!({ "voltage": 110 }));
+}
+
+#[tokio::test]
+async fn test_filter_products_by_attribute() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+
+    let category_id = create_electronics(&app).await;
+    for (sku, attributes) in [
+        ("FILTER-EU", json!({ "voltage": 220, "color": "red", "wireless": true })),
+        (
+            "FILTER-US",
+            json!({ "voltage": 110, "color": "blue", "wireless": true }),
+        ),
+        ("FILTER-UK", json!({ "voltage": 230.0, "color": "red" })),
+    ] {
+        let (status, _) = create_product(&app, sku, vec![category_id], attributes).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    for (query, expected) in [
+        ("attr.voltage=220", vec!["FILTER-EU"]),
+        ("attr.voltage=230", vec!["FILTER-UK"]),
+        ("attr.voltage=220.00", vec!["FILTER-EU"]),
+        ("attr.color=red", vec!["FILTER-EU", "FILTER-UK"]),
+        ("attr.color=red&attr.wireless=true", vec!["FILTER-EU"]),
+        ("attr.voltage=110&attr.voltage=230", vec!["FILTER-UK", "FILTER-US"]),
+        ("attr.color=green", vec![]),
+        ("attr.missing=1", vec![]),
+    ] {
+        let (status, list) = send(&app, "GET", &format!("/api/products?{}", query), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(listed_skus(&list), expected, "{}", query);
+        assert_eq!(list["total"], expected.len(), "{}", query);
+    }
+
+    // Attribute filters combine with the category filter
+    let (_, list) = send(
+        &app,
+        "GET",
+        &format!("/api/products?category_id={}&attr.color=blue", category_id),
+        None,
+    )
+    .await;
+    assert_eq!(listed_skus(&list), vec!["FILTER-US"]);
+}
+
+#[tokio::test]
+async fn test_removed_definitions_drop_product_values() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+
+    let electronics_id = create_electronics(&app).await;
+    let (_, outdoor) = send(&app, "POST", "/api/categories", Some(json!({ "name": "Outdoor" }))).await;
+    let outdoor_id = outdoor["id"].as_i64().unwrap();
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/api/categories/{}/attributes", outdoor_id),
+        Some(json!({ "name": "color", "type": "string" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // A value must satisfy every category defining the attribute
+    let (status, product) = create_product(
+        &app,
+        "PRUNE-1",
+        vec![electronics_id, outdoor_id],
+        json!({ "voltage": 12, "color": "blue", "wireless": true }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let uri = format!("/api/products/{}", product["id"]);
+    // Cache the product so the pruning must invalidate it
+    send(&app, "GET", &uri, None).await;
+
+    // Deleting a definition another category still defines keeps the value
+    let (_, definitions) = send(
+        &app,
+        "GET",
+        &format!("/api/categories/{}/attributes", electronics_id),
+        None,
+    )
+    .await;
+    for definition in definitions.as_array().unwrap() {
+        if definition["name"] != "voltage" {
+            let uri = format!("/api/categories/{}/attributes/{}", electronics_id, definition["id"]);
+            assert_eq!(send(&app, "DELETE", &uri, None).await.0, StatusCode::OK);
+        }
+    }
+    let (_, fetched) = send(&app, "GET", &uri, None).await;
+    assert_eq!(fetched["attributes"], json!({ "color": "blue", "voltage": 12 }));
+
+    // Moving the product out of a category drops the values only it defined
+    let (status, updated) = send(&app, "PUT", &uri, Some(json!({ "category_ids": [outdoor_id] }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["attributes"], json!({ "color": "blue" }));
+
+    // Deleting the last category defining an attribute drops it too
+    let (status, _) = send(&app, "DELETE", &format!("/api/categories/{}", outdoor_id), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, fetched) = send(&app, "GET", &uri, None).await;
+    assert_eq!(fetched["attributes"], json!({}));
+}
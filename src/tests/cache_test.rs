@@ -0,0 +1,173 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use tower::ServiceExt;
+
+use super::common::{
+    create_test_app_with_cache, create_test_category, create_test_product, initialize, spawn_redis_stand_in, test_cache,
+};
+use crate::cache::{CacheStats, CacheStore, CatalogCache, MemoryCache, RedisCache, keys};
+use crate::models::category::{CategoryListResponse, UpdateCategoryRequest};
+use crate::models::product::{ProductResponse, UpdateProductRequest};
+use crate::tenant::Tenant;
+
+async fn get_json<T: serde::de::DeserializeOwned>(app: &Router, uri: &str) -> T {
+    let response = app
+        .clone()
+        .oneshot(Request::builder().method("GET").uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+async fn put_json(app: &Router, uri: &str, body: &impl serde::Serialize) -> StatusCode {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(uri)
+                .header("Content-Type", "application/json")
+                .body(Body::from(serde_json::to_string(body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    response.status()
+}
+
+#[tokio::test]
+async fn test_product_cache_hit_and_invalidation() {
+    // Initialize test environment
+    let pool = initialize().await;
+    let cache = test_cache();
+    let app = create_test_app_with_cache(pool.clone(), cache.clone());
+
+    // Create test data
+    let category = create_test_category(&app).await;
+    let product = create_test_product(&app, vec![category.id]).await;
+
+    // First read misses, second read hits
+    let uri = format!("/api/products/{}", product.id);
+    let _: ProductResponse = get_json(&app, &uri).await;
+    let _: ProductResponse = get_json(&app, &uri).await;
+
+    let stats: CacheStats = get_json(&app, "/api/cache/stats").await;
+    assert_eq!(stats.backend, "memory");
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.hits, 1);
+
+    // Updating the product must not serve the stale entry
+    let update_body = UpdateProductRequest {
+        name: Some("Renamed Product".to_string()),
+        description: None,
+        price: None,
+        currency: None,
+        sku: None,
+        category_ids: None,
+        attributes: None,
+        tags: None,
+    };
+    assert_eq!(put_json(&app, &uri, &update_body).await, StatusCode::OK);
+
+    let updated: ProductResponse = get_json(&app, &uri).await;
+    assert_eq!(updated.name, "Renamed Product");
+}
+
+#[tokio::test]
+async fn test_category_rename_invalidates_products() {
+    // Initialize test environment
+    let pool = initialize().await;
+    let cache = test_cache();
+    let app = create_test_app_with_cache(pool.clone(), cache.clone());
+
+    // Create test data and warm the cache
+    let category = create_test_category(&app).await;
+    let product = create_test_product(&app, vec![category.id]).await;
+    let product_uri = format!("/api/products/{}", product.id);
+    let _: ProductResponse = get_json(&app, &product_uri).await;
+    let _: CategoryListResponse = get_json(&app, "/api/categories?include_product_count=true").await;
+
+    // Rename the category
+    let update_body = UpdateCategoryRequest {
+        name: Some("Renamed Category".to_string()),
+        description: None,
+    };
+    let category_uri = format!("/api/categories/{}", category.id);
+    assert_eq!(put_json(&app, &category_uri, &update_body).await, StatusCode::OK);
+
+    // The product embeds the category name, so it must be re-read
+    let product: ProductResponse = get_json(&app, &product_uri).await;
+    assert_eq!(product.categories[0].name, "Renamed Category");
+
+    let categories: CategoryListResponse = get_json(&app, "/api/categories?include_product_count=true").await;
+    assert_eq!(categories.categories[0].name, "Renamed Category");
+    assert_eq!(categories.categories[0].product_count, Some(1));
+
+    // Deleting the product must refresh the product counts
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri(&product_uri)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let categories: CategoryListResponse = get_json(&app, "/api/categories?include_product_count=true").await;
+    assert_eq!(categories.categories[0].product_count, Some(0));
+}
+
+#[tokio::test]
+async fn test_memory_cache_eviction_and_expiry() {
+    let store = MemoryCache::new(2);
+
+    store.set("a", "1".to_string(), Duration::from_secs(60)).await.unwrap();
+    store.set("b", "2".to_string(), Duration::from_secs(60)).await.unwrap();
+    store.set("c", "3".to_string(), Duration::from_secs(60)).await.unwrap();
+
+    // Least recently used entry is evicted
+    assert_eq!(store.get("a").await.unwrap(), None);
+    assert_eq!(store.get("c").await.unwrap(), Some("3".to_string()));
+
+    // Expired entries are treated as missing
+    store.set("d", "4".to_string(), Duration::ZERO).await.unwrap();
+    assert_eq!(store.get("d").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_redis_cache_against_stand_in() {
+    let url = spawn_redis_stand_in().await;
+    let store = RedisCache::connect(&url, "test:").await.unwrap();
+    let cache = CatalogCache::new(Arc::new(store), Duration::from_secs(60));
+    let tenant = Tenant::default();
+
+    assert_eq!(cache.get::<String>(&keys::product(&tenant, 1)).await, None);
+
+    cache.put(&keys::product(&tenant, 1), &"cached".to_string()).await;
+    assert_eq!(
+        cache.get::<String>(&keys::product(&tenant, 1)).await,
+        Some("cached".to_string())
+    );
+
+    cache.invalidate(vec![keys::product(&tenant, 1)]).await;
+    assert_eq!(cache.get::<String>(&keys::product(&tenant, 1)).await, None);
+
+    let stats = cache.stats();
+    assert_eq!(stats.backend, "redis");
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 2);
+    assert_eq!(stats.errors, 0);
+}
@@ -1,12 +1,12 @@
-use std::str::FromStr;
-
 use axum::body::Body;
 use axum::http::{Request, StatusCode};
-use bigdecimal::BigDecimal;
 use tower::ServiceExt;
 
 // Import from common module
-use super::common::{cleanup_test_data, create_test_app, create_test_category, create_test_product, initialize};
+use super::common::{
+    create_public_test_app_with_cache, create_test_app, create_test_app_with_cache, create_test_category,
+    create_test_product, initialize, test_cache,
+};
 use crate::entity::{
     Category, CategoryActiveModel, CategoryModel, Product, ProductActiveModel, ProductCategory, ProductCategoryModel,
     ProductModel,
@@ -86,9 +86,59 @@ async fn test_list_categories() {
 
     assert_eq!(categories.categories.len(), 2);
     assert!(categories.categories.iter().all(|c| c.product_count.is_some()));
+}
+
+/// Product counts from `GET /api/categories?include_product_count=true`
+async fn product_counts(app: &axum::Router) -> Vec<Option<i64>> {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/api/categories?include_product_count=true")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let categories: CategoryListResponse = serde_json::from_slice(&body).unwrap();
+    categories.categories.iter().map(|c| c.product_count).collect()
+}
+
+#[tokio::test]
+async fn test_anonymous_category_counts_only_include_published_products() {
+    let pool = initialize().await;
+    let cache = test_cache();
+    let app = create_test_app_with_cache(pool.clone(), cache.clone());
+    let public = create_public_test_app_with_cache(pool.clone(), cache);
 
-    // Clean up test data
-    cleanup_test_data(&pool).await;
+    let category = create_test_category(&app).await;
+    let product = create_test_product(&app, vec![category.id]).await;
+
+    // Editors and anonymous callers are cached separately
+    assert_eq!(product_counts(&app).await, vec![Some(1)]);
+    assert_eq!(product_counts(&public).await, vec![Some(0)]);
+
+    // Publishing the draft changes the anonymous count
+    for action in ["submit", "publish"] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/api/products/{}/{}", product.id, action))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    assert_eq!(product_counts(&public).await, vec![Some(1)]);
+    assert_eq!(product_counts(&app).await, vec![Some(1)]);
 }
 
 #[tokio::test]
@@ -136,9 +186,6 @@ async fn test_get_category() {
         .unwrap();
 
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
-
-    // Clean up test data
-    cleanup_test_data(&pool).await;
 }
 
 #[tokio::test]
@@ -194,9 +241,6 @@ async fn test_create_category() {
         .unwrap();
 
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
-
-    // Clean up test data
-    cleanup_test_data(&pool).await;
 }
 
 #[tokio::test]
@@ -251,9 +295,6 @@ async fn test_update_category() {
         .unwrap();
 
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
-
-    // Clean up test data
-    cleanup_test_data(&pool).await;
 }
 
 #[tokio::test]
@@ -309,9 +350,6 @@ async fn test_delete_category() {
         .unwrap();
 
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
-
-    // Clean up test data
-    cleanup_test_data(&pool).await;
 }
 
 #[tokio::test]
@@ -328,9 +366,11 @@ async fn test_get_category_products() {
     let request_body = CreateProductRequest {
         name: "Second Product".to_string(),
         description: Some("Another product in the category".to_string()),
-        price: BigDecimal::from_str("29.99").unwrap(),
+        price: "29.99 USD".parse().unwrap(),
         category_ids: vec![category.id],
         sku: Some("CAT-SKU-456".to_string()),
+        attributes: Default::default(),
+        tags: Vec::new(),
     };
 
     let response: axum::response::Response = app
@@ -384,7 +424,4 @@ async fn test_get_category_products() {
         .unwrap();
 
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
-
-    // Clean up test data
-    cleanup_test_data(&pool).await;
 }
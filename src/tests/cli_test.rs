@@ -0,0 +1,349 @@
+use clap::Parser;
+use serde_json::json;
+
+use super::common::{initialize, test_cache, test_storage};
+use crate::cli::catalog::{self, CatalogFile, CategoryRecord, ImportSummary, ProductRecord};
+use crate::cli::category::category_tree;
+use crate::cli::output::{OutputFormat, Table};
+use crate::cli::{Cli, Command, Repositories};
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::models::api_key::CreateApiKeyRequest;
+use crate::models::attribute::{AttributeType, CreateAttributeRequest};
+use crate::models::category::CreateCategoryRequest;
+use crate::models::lifecycle::ProductStatus;
+use crate::models::product::CreateProductRequest;
+use crate::tenant::Tenant;
+
+fn sample_catalog() -> CatalogFile {
+    CatalogFile {
+        categories: vec![
+            CategoryRecord {
+                name: "Books".to_string(),
+                description: Some("Printed books".to_string()),
+                attributes: vec![CreateAttributeRequest {
+                    name: "pages".to_string(),
+                    value_type: AttributeType::Number,
+                    required: true,
+                    values: None,
+                    unit: None,
+                }],
+            },
+            CategoryRecord {
+                name: "Music".to_string(),
+                description: None,
+                attributes: Vec::new(),
+            },
+        ],
+        products: vec![
+            ProductRecord {
+                name: "Songbook".to_string(),
+                description: Some("Sheet music".to_string()),
+                price: "12.50 USD".parse().unwrap(),
+                sku: Some("CLI-SONGBOOK".to_string()),
+                categories: vec!["Books".to_string(), "Music".to_string()],
+                attributes: [("pages".to_string(), json!(96))].into(),
+                tags: vec!["sheet-music".to_string()],
+                status: Some(ProductStatus::Published),
+            },
+            ProductRecord {
+                name: "Novel".to_string(),
+                description: None,
+                price: "9.99 USD".parse().unwrap(),
+                sku: Some("CLI-NOVEL".to_string()),
+                categories: vec!["Books".to_string()],
+                attributes: [("pages".to_string(), json!(320))].into(),
+                tags: Vec::new(),
+                status: None,
+            },
+        ],
+    }
+}
+
+#[tokio::test]
+async fn test_import_export_round_trip() {
+    let db = initialize().await;
+    let repositories = Repositories::new(db.clone(), test_cache(), test_storage(), &Config::default());
+
+    let summary = catalog::import(&sample_catalog(), &repositories).await.unwrap();
+    assert_eq!(
+        summary,
+        ImportSummary {
+            categories_created: 2,
+            categories_updated: 0,
+            products_created: 2,
+            products_updated: 0,
+        }
+    );
+
+    let exported = catalog::export(&repositories).await.unwrap();
+    assert_eq!(exported.categories.len(), 2);
+    assert_eq!(exported.products.len(), 2);
+    let songbook = exported
+        .products
+        .iter()
+        .find(|product| product.sku.as_deref() == Some("CLI-SONGBOOK"))
+        .unwrap();
+    assert_eq!(songbook.price.to_string(), "12.50 USD");
+    let mut categories = songbook.categories.clone();
+    categories.sort();
+    assert_eq!(categories, vec!["Books", "Music"]);
+    assert_eq!(songbook.attributes["pages"], json!(96));
+    assert_eq!(songbook.tags, vec!["sheet-music"]);
+    assert_eq!(songbook.status, Some(ProductStatus::Published));
+    let novel = exported
+        .products
+        .iter()
+        .find(|product| product.sku.as_deref() == Some("CLI-NOVEL"))
+        .unwrap();
+    assert_eq!(novel.status, Some(ProductStatus::Draft));
+    let books = exported
+        .categories
+        .iter()
+        .find(|category| category.name == "Books")
+        .unwrap();
+    assert_eq!(books.attributes.len(), 1);
+    assert_eq!(books.attributes[0].name, "pages");
+    assert!(books.attributes[0].required);
+
+    // Re-importing the export updates everything in place
+    let summary = catalog::import(&exported, &repositories).await.unwrap();
+    assert_eq!(
+        summary,
+        ImportSummary {
+            categories_created: 0,
+            categories_updated: 2,
+            products_created: 0,
+            products_updated: 2,
+        }
+    );
+    assert_eq!(catalog::export(&repositories).await.unwrap().products.len(), 2);
+}
+
+#[tokio::test]
+async fn test_import_rejects_unknown_category() {
+    let db = initialize().await;
+    let repositories = Repositories::new(db.clone(), test_cache(), test_storage(), &Config::default());
+
+    let mut file = sample_catalog();
+    file.products[1].categories = vec!["Films".to_string()];
+
+    let error = catalog::import(&file, &repositories).await.unwrap_err();
+    assert!(error.to_string().contains("unknown category \"Films\""), "{}", error);
+}
+
+#[tokio::test]
+async fn test_import_rejects_non_positive_prices() {
+    let db = initialize().await;
+    let repositories = Repositories::new(db.clone(), test_cache(), test_storage(), &Config::default());
+
+    let mut file = sample_catalog();
+    file.products[0].price = "0 USD".parse().unwrap();
+
+    let error = catalog::import(&file, &repositories).await.unwrap_err();
+    assert!(
+        error.to_string().contains("price must be a positive number"),
+        "{}",
+        error
+    );
+    assert!(catalog::export(&repositories).await.unwrap().products.is_empty());
+
+    // Files are checked as they're read
+    let record = json!({ "name": "Free", "price": "-5.00", "categories": [] });
+    let error = serde_json::from_value::<ProductRecord>(record).unwrap_err();
+    assert!(
+        error.to_string().contains("price must be a positive number"),
+        "{}",
+        error
+    );
+}
+
+#[tokio::test]
+async fn test_seed_fixtures_are_idempotent() {
+    let db = initialize().await;
+    let repositories = Repositories::new(db.clone(), test_cache(), test_storage(), &Config::default());
+
+    let fixtures = catalog::read_catalog(include_str!("../../fixtures/catalog.json")).unwrap();
+    let first = catalog::import(&fixtures, &repositories).await.unwrap();
+    let second = catalog::import(&fixtures, &repositories).await.unwrap();
+
+    assert_eq!(first.products_created, fixtures.products.len());
+    assert_eq!(second.products_created, 0);
+    assert_eq!(second.products_updated, fixtures.products.len());
+    assert_eq!(
+        catalog::export(&repositories).await.unwrap().products.len(),
+        fixtures.products.len()
+    );
+}
+
+#[tokio::test]
+async fn test_merge_categories_moves_products() {
+    let db = initialize().await;
+    let repositories = Repositories::new(db.clone(), test_cache(), test_storage(), &Config::default());
+
+    let source = repositories
+        .categories
+        .create_category(CreateCategoryRequest {
+            name: "Merge Source".to_string(),
+            description: None,
+        })
+        .await
+        .unwrap();
+    let target = repositories
+        .categories
+        .create_category(CreateCategoryRequest {
+            name: "Merge Target".to_string(),
+            description: None,
+        })
+        .await
+        .unwrap();
+
+    // The source's attribute definitions move with its products
+    repositories
+        .attributes
+        .create_attribute(
+            source.id,
+            CreateAttributeRequest {
+                name: "tempo".to_string(),
+                value_type: AttributeType::Number,
+                required: false,
+                values: None,
+                unit: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    // One product only in the source, one already in both
+    for (sku, category_ids) in [("MERGE-1", vec![source.id]), ("MERGE-2", vec![source.id, target.id])] {
+        repositories
+            .products
+            .create_product(CreateProductRequest {
+                name: sku.to_string(),
+                description: None,
+                price: "1.00 USD".parse().unwrap(),
+                sku: Some(sku.to_string()),
+                category_ids,
+                attributes: [("tempo".to_string(), json!(120))].into(),
+                tags: Vec::new(),
+            })
+            .await
+            .unwrap();
+    }
+
+    let merged = repositories
+        .categories
+        .merge_categories(source.id, target.id)
+        .await
+        .unwrap();
+    assert_eq!(merged.id, target.id);
+
+    let tree = category_tree(&repositories).await.unwrap();
+    assert_eq!(tree.len(), 1);
+    assert_eq!(tree[0].id, target.id);
+    assert_eq!(tree[0].products.len(), 2);
+    let attributes = repositories.attributes.list_attributes(target.id).await.unwrap();
+    assert_eq!(attributes.len(), 1);
+    assert_eq!(attributes[0].name, "tempo");
+    let product = repositories.products.get_product(tree[0].products[0].id).await.unwrap();
+    assert_eq!(product.attributes["tempo"], json!(120));
+
+    let error = repositories
+        .categories
+        .merge_categories(target.id, target.id)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, ApiError::BadRequest(_)));
+
+    let error = repositories
+        .categories
+        .merge_categories(source.id, target.id)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, ApiError::NotFound(_)));
+}
+
+#[tokio::test]
+async fn test_api_key_create_and_revoke() {
+    let db = initialize().await;
+    let repositories = Repositories::new(db.clone(), test_cache(), test_storage(), &Config::default());
+
+    let created = repositories
+        .api_keys
+        .create_api_key(CreateApiKeyRequest {
+            name: "cli test".to_string(),
+            tenant: Tenant::default(),
+        })
+        .await
+        .unwrap();
+    assert!(created.secret.starts_with("pcat_"));
+    assert!(created.secret.starts_with(&created.api_key.prefix));
+
+    let found = repositories
+        .api_keys
+        .find_active_by_secret(&created.secret)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.id, created.api_key.id);
+    assert!(
+        repositories
+            .api_keys
+            .find_active_by_secret("pcat_wrong")
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let revoked = repositories.api_keys.revoke_api_key(created.api_key.id).await.unwrap();
+    assert!(revoked.revoked_at.is_some());
+    assert!(
+        repositories
+            .api_keys
+            .find_active_by_secret(&created.secret)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let error = repositories
+        .api_keys
+        .revoke_api_key(created.api_key.id)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, ApiError::Conflict(_)));
+}
+
+#[test]
+fn test_cli_parses_admin_commands() {
+    let cli = Cli::try_parse_from(["catalog", "product", "list", "--page-size", "5", "-o", "json"]).unwrap();
+    assert_eq!(cli.output, OutputFormat::Json);
+    assert!(matches!(cli.command, Some(Command::Product { .. })));
+
+    let cli = Cli::try_parse_from(["catalog", "category", "merge", "3", "--into", "4"]).unwrap();
+    assert_eq!(cli.output, OutputFormat::Table);
+    assert!(matches!(cli.command, Some(Command::Category { .. })));
+
+    let cli = Cli::try_parse_from(["catalog", "api-key", "revoke", "7"]).unwrap();
+    assert!(matches!(cli.command, Some(Command::ApiKey { .. })));
+
+    let cli = Cli::try_parse_from(["catalog", "api-key", "create", "ci", "--tenant", "acme"]).unwrap();
+    assert_eq!(cli.tenant.as_str(), "acme");
+    assert!(Cli::try_parse_from(["catalog", "export", "--tenant", "Not A Slug"]).is_err());
+
+    let cli = Cli::try_parse_from(["catalog", "seed"]).unwrap();
+    assert!(matches!(cli.command, Some(Command::Seed { fixtures: None })));
+
+    // Creating a product requires at least one category
+    assert!(Cli::try_parse_from(["catalog", "product", "create", "--name", "x", "--price", "1"]).is_err());
+}
+
+#[test]
+fn test_table_aligns_columns() {
+    let table = Table::new(vec!["ID", "NAME"])
+        .row(vec!["1".to_string(), "Widget".to_string()])
+        .row(vec!["100".to_string(), "Gadget".to_string()])
+        .render();
+
+    assert_eq!(table, "ID   NAME\n1    Widget\n100  Gadget");
+}
@@ -1,31 +1,59 @@
-use std::str::FromStr;
-use std::sync::Once;
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Arc, Mutex, Once};
 use std::time::Duration;
 
-use axum::Router;
 use axum::body::Body;
-use axum::http::{Request, StatusCode};
-use bigdecimal::BigDecimal;
+use axum::extract::State;
+use axum::http::{HeaderMap, Method, Request, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{any, post};
+use axum::{Extension, Router};
+use bytes::Bytes;
+use chrono::NaiveDateTime;
 use dotenvy::dotenv;
 use hyper::body::to_bytes;
-use sea_orm::{ColumnTrait, ConnectOptions, Database, DatabaseConnection, DeleteResult, EntityTrait, QueryFilter};
+use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DbBackend, Statement};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::net::tcp::OwnedReadHalf;
+use tokio::sync::OnceCell;
 use tower::ServiceExt;
+use uuid::Uuid;
 
-use crate::config::Config;
-use crate::entity::{
-    Category, CategoryActiveModel, CategoryModel, Product, ProductActiveModel, ProductCategory, ProductCategoryModel,
-    ProductModel,
-};
-use crate::models::category::{CategoryResponse, CreateCategoryRequest};
-use crate::models::product::{CreateProductRequest, ProductResponse};
+use super::fixtures::{CategoryFixture, ProductFixture};
+use crate::auth::Editor;
+use crate::cache::{CatalogCache, MemoryCache};
+use crate::config::{Config, RateLimitConfig};
+use crate::health::Health;
+use crate::migration::Migrator;
+use crate::models::category::CategoryResponse;
+use crate::models::product::ProductResponse;
+use crate::rate_limit::{MemoryRateLimitStore, RateLimiter};
 use crate::repository::category::CategoryRepository;
 use crate::repository::product::ProductRepository;
+use crate::shutdown::Shutdown;
+use crate::storage::s3::{Credentials, sign};
+use crate::storage::{LocalStore, MediaStorage};
+use crate::tenant::Tenant;
 use crate::{api, database};
 
 // Used to initialize environment only once
 static INIT: Once = Once::new();
 
-/// Initialize test environment
+// Schemas left by earlier runs against Postgres are dropped before the first test creates its own
+static STALE_SCHEMAS_DROPPED: OnceCell<()> = OnceCell::const_new();
+
+/// Prefix of the schemas created for tests that run against Postgres
+const TEST_SCHEMA_PREFIX: &str = "catalog_test_";
+
+/// Initialize test environment, returning a connection to a fresh database of the test's own
+///
+/// Tests need no database server and never see each other's data, so they can run in parallel.
+/// See [`isolated_database`] for running them against Postgres.
 pub async fn initialize() -> DatabaseConnection {
     // Load environment variables
     dotenv().ok();
@@ -39,107 +67,452 @@ pub async fn initialize() -> DatabaseConnection {
             .try_init();
     });
 
-    // Get test configuration
-    let config = Config::from_env().expect("Failed to load configuration");
-
-    // Create database connection using sea-orm
-    let mut opt = ConnectOptions::new(&config.database_url);
+    // Create database connection using sea-orm; the pool's last connection keeps an in-memory
+    // database alive, so it is never closed
+    let mut opt = isolated_database().await;
     opt.max_connections(5)
         .min_connections(1)
         .connect_timeout(Duration::from_secs(3))
-        .idle_timeout(Duration::from_secs(60));
+        .sqlx_logging(false);
 
     let db = Database::connect(opt)
         .await
         .expect("Failed to create database connection");
 
+    // Bring the test database schema up to date
+    Migrator::new(db.clone())
+        .up()
+        .await
+        .expect("Failed to run database migrations");
+
     db
 }
 
+/// Connection options for a new, empty database used by a single test
+///
+/// That is an in-memory SQLite database, or when `TEST_DATABASE_URL` names a Postgres database, a
+/// new schema in it. Test schemas are only dropped by the next run, so two runs against the same
+/// database must not overlap.
+pub async fn isolated_database() -> ConnectOptions {
+    let Ok(url) = env::var("TEST_DATABASE_URL") else {
+        return ConnectOptions::new(sqlite_memory_url());
+    };
+
+    let admin = Database::connect(&url)
+        .await
+        .expect("Failed to connect to TEST_DATABASE_URL");
+    STALE_SCHEMAS_DROPPED.get_or_init(|| drop_test_schemas(&admin)).await;
+    let schema = format!("{}{}", TEST_SCHEMA_PREFIX, Uuid::new_v4().simple());
+    admin
+        .execute_unprepared(&format!("CREATE SCHEMA {}", schema))
+        .await
+        .expect("Failed to create test schema");
+    let _ = admin.close().await;
+
+    let mut opt = ConnectOptions::new(url);
+    opt.set_schema_search_path(schema);
+    opt
+}
+
+/// Drop the schemas created by earlier test runs
+async fn drop_test_schemas(admin: &DatabaseConnection) {
+    let schemas = admin
+        .query_all_raw(Statement::from_string(
+            DbBackend::Postgres,
+            format!(
+                "SELECT nspname FROM pg_namespace WHERE starts_with(nspname, '{}')",
+                TEST_SCHEMA_PREFIX
+            ),
+        ))
+        .await
+        .expect("Failed to list test schemas");
+
+    for row in schemas {
+        let schema: String = row.try_get("", "nspname").unwrap();
+        admin
+            .execute_unprepared(&format!("DROP SCHEMA {} CASCADE", schema))
+            .await
+            .expect("Failed to drop test schema");
+    }
+}
+
+/// URL of a new, empty in-memory SQLite database shared by every connection that opens it
+pub fn sqlite_memory_url() -> String {
+    format!(
+        "sqlite:file:catalog-{}?mode=memory&cache=shared",
+        Uuid::new_v4().simple()
+    )
+}
+
 /// Create a test application
 pub fn create_test_app(db_conn: DatabaseConnection) -> Router {
-    // Use the API routes function directly with the DatabaseConnection
-    // This matches how it's used in the main application
-    Router::new().nest("/api", api::routes(db_conn))
+    create_test_app_with_cache(db_conn, test_cache())
 }
 
-/// Create a test category
-pub async fn create_test_category(app: &Router) -> CategoryResponse {
-    let request_body = CreateCategoryRequest {
-        name: "Test Category".to_string(),
-        description: Some("A test category".to_string()),
+/// Create a test application sharing the given response cache
+pub fn create_test_app_with_cache(db_conn: DatabaseConnection, cache: CatalogCache) -> Router {
+    create_test_app_with(&Config::default(), db_conn, cache, test_storage())
+}
+
+/// Create a test application with explicit configuration, cache and media storage
+///
+/// Requests are made as an editor, so products are visible whatever their status.
+pub fn create_test_app_with(
+    config: &Config,
+    db_conn: DatabaseConnection,
+    cache: CatalogCache,
+    storage: MediaStorage,
+) -> Router {
+    create_tenant_test_app_with(config, db_conn, cache, storage, Tenant::default())
+}
+
+/// Create a test application whose requests are made as an editor of `tenant`
+pub fn create_tenant_test_app(db_conn: DatabaseConnection, tenant: &str) -> Router {
+    let tenant = tenant.parse().expect("Invalid test tenant");
+    create_tenant_test_app_with(&Config::default(), db_conn, test_cache(), test_storage(), tenant)
+}
+
+fn create_tenant_test_app_with(
+    config: &Config,
+    db_conn: DatabaseConnection,
+    cache: CatalogCache,
+    storage: MediaStorage,
+    tenant: Tenant,
+) -> Router {
+    let editor = Editor {
+        api_key_id: 0,
+        name: "test editor".to_string(),
+        tenant,
     };
+    let rate_limiter = test_rate_limiter(&config.rate_limit);
+    build_test_app(config, db_conn, cache, storage, rate_limiter).layer(Extension(editor))
+}
+
+/// Create a test application whose requests are anonymous unless they present an API key
+pub fn create_public_test_app(db_conn: DatabaseConnection) -> Router {
+    create_public_test_app_with(&Config::default(), db_conn)
+}
+
+/// Create an anonymous test application sharing the given response cache
+pub fn create_public_test_app_with_cache(db_conn: DatabaseConnection, cache: CatalogCache) -> Router {
+    let config = Config::default();
+    let rate_limiter = test_rate_limiter(&config.rate_limit);
+    build_test_app(&config, db_conn, cache, test_storage(), rate_limiter)
+}
+
+/// Create an anonymous test application with explicit configuration
+pub fn create_public_test_app_with(config: &Config, db_conn: DatabaseConnection) -> Router {
+    create_public_test_app_with_rate_limiter(config, db_conn, test_rate_limiter(&config.rate_limit))
+}
+
+/// Create an anonymous test application sharing the given rate limiter
+pub fn create_public_test_app_with_rate_limiter(
+    config: &Config,
+    db_conn: DatabaseConnection,
+    rate_limiter: RateLimiter,
+) -> Router {
+    build_test_app(config, db_conn, test_cache(), test_storage(), rate_limiter)
+}
+
+/// Build the same router as the main application
+fn build_test_app(
+    config: &Config,
+    db_conn: DatabaseConnection,
+    cache: CatalogCache,
+    storage: MediaStorage,
+    rate_limiter: RateLimiter,
+) -> Router {
+    let health = Health::new(db_conn.clone(), Duration::from_secs(1), Shutdown::new());
+    health.mark_schema_ready();
+    crate::app(config, db_conn, cache, storage, rate_limiter, health)
+}
+
+/// Create an in-memory response cache for a single test
+pub fn test_cache() -> CatalogCache {
+    CatalogCache::new(Arc::new(MemoryCache::new(100)), Duration::from_secs(60))
+}
+
+/// Create an in-memory rate limiter for a single test, honouring `enabled`
+pub fn test_rate_limiter(config: &RateLimitConfig) -> RateLimiter {
+    if config.enabled {
+        RateLimiter::new(Arc::new(MemoryRateLimitStore::new(config.capacity)), config)
+    } else {
+        RateLimiter::disabled()
+    }
+}
+
+/// Create media storage in a fresh temporary directory for a single test
+pub fn test_storage() -> MediaStorage {
+    let root = std::env::temp_dir().join(format!("catalog-media-{}", Uuid::new_v4()));
+    Arc::new(LocalStore::new(&root).expect("Failed to create test media directory"))
+}
+
+/// A test application with typed JSON helpers around `oneshot`
+#[derive(Clone)]
+pub struct TestApp {
+    router: Router,
+}
+
+impl TestApp {
+    /// An application whose requests are made as an editor
+    pub fn new(db_conn: DatabaseConnection) -> Self {
+        Self::from(create_test_app(db_conn))
+    }
+
+    /// An application whose requests are anonymous unless they present an API key
+    pub fn public(db_conn: DatabaseConnection) -> Self {
+        Self::from(create_public_test_app(db_conn))
+    }
+
+    pub async fn get(&self, uri: &str) -> TestResponse {
+        self.send(Method::GET, uri, None).await
+    }
+
+    pub async fn post(&self, uri: &str, body: &impl Serialize) -> TestResponse {
+        self.send(Method::POST, uri, Some(serde_json::to_vec(body).unwrap()))
+            .await
+    }
+
+    pub async fn put(&self, uri: &str, body: &impl Serialize) -> TestResponse {
+        self.send(Method::PUT, uri, Some(serde_json::to_vec(body).unwrap()))
+            .await
+    }
 
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("POST")
-                .uri("/api/categories")
+    pub async fn delete(&self, uri: &str) -> TestResponse {
+        self.send(Method::DELETE, uri, None).await
+    }
+
+    /// Send a request with an optional JSON body
+    pub async fn send(&self, method: Method, uri: &str, body: Option<Vec<u8>>) -> TestResponse {
+        let request = Request::builder().method(method).uri(uri);
+        let request = match body {
+            Some(body) => request
                 .header("Content-Type", "application/json")
-                .body(Body::from(serde_json::to_string(&request_body).unwrap()))
-                .unwrap(),
-        )
-        .await
-        .unwrap();
+                .body(Body::from(body)),
+            None => request.body(Body::empty()),
+        };
+
+        let response = self.router.clone().oneshot(request.unwrap()).await.unwrap();
+        TestResponse {
+            status: response.status(),
+            headers: response.headers().clone(),
+            body: to_bytes(response.into_body()).await.unwrap(),
+        }
+    }
+}
 
-    assert_eq!(response.status(), StatusCode::OK);
+impl From<Router> for TestApp {
+    fn from(router: Router) -> Self {
+        Self { router }
+    }
+}
+
+/// A response received by a [`TestApp`]
+pub struct TestResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
 
-    let body = to_bytes(response.into_body()).await.unwrap();
-    let category: CategoryResponse = serde_json::from_slice(&body).unwrap();
+impl TestResponse {
+    /// Parse the body as JSON
+    pub fn json<T: DeserializeOwned>(&self) -> T {
+        serde_json::from_slice(&self.body)
+            .unwrap_or_else(|e| panic!("Invalid JSON response ({}): {}", e, String::from_utf8_lossy(&self.body)))
+    }
 
-    category
+    /// Assert the request succeeded and parse the body as JSON
+    pub fn ok<T: DeserializeOwned>(&self) -> T {
+        assert_eq!(self.status, StatusCode::OK, "{}", String::from_utf8_lossy(&self.body));
+        self.json()
+    }
+}
+
+/// Create a test category
+pub async fn create_test_category(app: &Router) -> CategoryResponse {
+    CategoryFixture::new()
+        .description("A test category")
+        .create(&TestApp::from(app.clone()))
+        .await
 }
 
 /// Create a test product
 pub async fn create_test_product(app: &Router, category_ids: Vec<i32>) -> ProductResponse {
-    let request_body = CreateProductRequest {
-        name: "Test Product".to_string(),
-        description: Some("A test product".to_string()),
-        price: BigDecimal::from_str("19.99").unwrap(),
-        category_ids,
-        sku: Some("TEST-SKU-123".to_string()),
-    };
+    ProductFixture::new()
+        .description("A test product")
+        .sku("TEST-SKU-123")
+        .in_categories(&category_ids)
+        .create(&TestApp::from(app.clone()))
+        .await
+}
 
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("POST")
-                .uri("/api/products")
-                .header("Content-Type", "application/json")
-                .body(Body::from(serde_json::to_string(&request_body).unwrap()))
-                .unwrap(),
+/// Start a minimal Redis protocol stand-in on a local port and return its URL
+///
+/// Supports the handful of commands the application issues (GET, SET, PSETEX, DEL, PING) and
+/// acknowledges anything else with `+OK`. Expiry is not enforced.
+pub async fn spawn_redis_stand_in() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let store: Arc<Mutex<HashMap<String, String>>> = Arc::default();
+
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            let store = store.clone();
+            tokio::spawn(async move {
+                let (reader, mut writer) = socket.into_split();
+                let mut reader = BufReader::new(reader);
+                while let Some(command) = read_resp_command(&mut reader).await {
+                    let reply = handle_resp_command(&store, &command);
+                    if writer.write_all(reply.as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    format!("redis://{}/", addr)
+}
+
+async fn read_resp_command(reader: &mut BufReader<OwnedReadHalf>) -> Option<Vec<String>> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await.ok()?;
+    let count: usize = line.trim_end().strip_prefix('*')?.parse().ok()?;
+
+    let mut args = Vec::with_capacity(count);
+    for _ in 0..count {
+        line.clear();
+        reader.read_line(&mut line).await.ok()?;
+        let len: usize = line.trim_end().strip_prefix('$')?.parse().ok()?;
+        let mut buf = vec![0; len + 2];
+        reader.read_exact(&mut buf).await.ok()?;
+        buf.truncate(len);
+        args.push(String::from_utf8(buf).ok()?);
+    }
+    Some(args)
+}
+
+fn handle_resp_command(store: &Mutex<HashMap<String, String>>, command: &[String]) -> String {
+    let mut store = store.lock().unwrap();
+    match command.first().map(|c| c.to_ascii_uppercase()).as_deref() {
+        Some("GET") => match store.get(&command[1]) {
+            Some(value) => format!("${}\r\n{}\r\n", value.len(), value),
+            None => "$-1\r\n".to_string(),
+        },
+        Some("SET") => {
+            store.insert(command[1].clone(), command[2].clone());
+            "+OK\r\n".to_string()
+        }
+        Some("PSETEX") => {
+            store.insert(command[1].clone(), command[3].clone());
+            "+OK\r\n".to_string()
+        }
+        Some("DEL") => {
+            let removed = command[1..].iter().filter(|key| store.remove(*key).is_some()).count();
+            format!(":{}\r\n", removed)
+        }
+        Some("PING") => "+PONG\r\n".to_string(),
+        _ => "+OK\r\n".to_string(),
+    }
+}
+
+/// Start an OTLP/HTTP collector stand-in on a local port
+///
+/// Returns the collector base URL and the bodies of every export request it has received.
+pub async fn spawn_otlp_collector_stand_in() -> (String, Arc<Mutex<Vec<String>>>) {
+    let received: Arc<Mutex<Vec<String>>> = Arc::default();
+
+    let collector = Router::new()
+        .route(
+            "/v1/traces",
+            post(
+                |State(received): State<Arc<Mutex<Vec<String>>>>, body: String| async move {
+                    received.lock().unwrap().push(body);
+                    StatusCode::OK
+                },
+            ),
         )
-        .await
-        .unwrap();
+        .with_state(received.clone());
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = axum::Server::from_tcp(listener)
+        .unwrap()
+        .serve(collector.into_make_service());
+    tokio::spawn(server);
+
+    (format!("http://{}", addr), received)
+}
+
+/// Start an S3-compatible object store stand-in on a local port
+///
+/// Serves path-style `PUT`, `GET` and `DELETE` object requests, rejecting any whose SigV4
+/// signature does not match `credentials`. Returns the endpoint URL and the stored objects.
+pub async fn spawn_s3_stand_in(credentials: Credentials) -> (String, Arc<Mutex<HashMap<String, Vec<u8>>>>) {
+    let objects: Arc<Mutex<HashMap<String, Vec<u8>>>> = Arc::default();
 
-    assert_eq!(response.status(), StatusCode::OK);
+    let state = (Arc::new(credentials), objects.clone());
+    let stand_in = Router::new().route("/*path", any(handle_s3_request)).with_state(state);
 
-    let body = to_bytes(response.into_body()).await.unwrap();
-    let product: ProductResponse = serde_json::from_slice(&body).unwrap();
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = axum::Server::from_tcp(listener)
+        .unwrap()
+        .serve(stand_in.into_make_service());
+    tokio::spawn(server);
 
-    product
+    (format!("http://{}", addr), objects)
 }
 
-/// Clean up test data
-pub async fn cleanup_test_data(db: &DatabaseConnection) {
-    // Delete all data in the correct order to respect foreign key constraints
-    // First delete the product_categories (junction table)
-    let _ = ProductCategory::delete_many()
-        .exec(db)
-        .await
-        .expect("Failed to delete product categories");
+type S3StandInState = (Arc<Credentials>, Arc<Mutex<HashMap<String, Vec<u8>>>>);
 
-    // Then delete products
-    let _ = Product::delete_many()
-        .exec(db)
-        .await
-        .expect("Failed to delete products");
+async fn handle_s3_request(
+    State((credentials, objects)): State<S3StandInState>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+    };
 
-    // Finally delete categories
-    let _ = Category::delete_many()
-        .exec(db)
-        .await
-        .expect("Failed to delete categories");
+    // Recompute the signature over the headers the client says it signed
+    let payload_hash = header("x-amz-content-sha256");
+    let now = NaiveDateTime::parse_from_str(header("x-amz-date"), "%Y%m%dT%H%M%SZ")
+        .map(|date| date.and_utc())
+        .unwrap_or_default();
+    let signed: Vec<(&str, &str)> = header("authorization")
+        .split("SignedHeaders=")
+        .nth(1)
+        .and_then(|rest| rest.split(',').next())
+        .unwrap_or_default()
+        .split(';')
+        .map(|name| (name, header(name)))
+        .collect();
+    let expected = sign(&credentials, method.as_str(), uri.path(), &signed, payload_hash, now);
+    if header("authorization") != expected || payload_hash != hex::encode(Sha256::digest(&body)) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    let mut objects = objects.lock().unwrap();
+    let key = uri.path().to_string();
+    match method {
+        Method::PUT => {
+            objects.insert(key, body.to_vec());
+            StatusCode::OK.into_response()
+        }
+        Method::GET => match objects.get(&key) {
+            Some(object) => object.clone().into_response(),
+            None => StatusCode::NOT_FOUND.into_response(),
+        },
+        Method::DELETE => {
+            objects.remove(&key);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        _ => StatusCode::METHOD_NOT_ALLOWED.into_response(),
+    }
 }
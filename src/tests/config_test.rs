@@ -0,0 +1,215 @@
+use std::env;
+use std::path::PathBuf;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use super::common::{initialize, test_cache, test_rate_limiter, test_storage};
+use crate::config::{Config, ConfigArgs};
+use crate::health::Health;
+use crate::shutdown::Shutdown;
+
+/// Write `contents` to a fresh TOML file in the temp directory
+fn write_config_file(contents: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("catalog-config-{}.toml", Uuid::new_v4()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn test_config_layers_file_env_and_cli() {
+    let path = write_config_file(
+        r#"
+        [server]
+        port = 4000
+
+        [database]
+        max_connections = 20
+
+        [cache]
+        backend = "none"
+
+        [cors]
+        allowed_origins = ["https://shop.example"]
+
+        [metrics]
+        sample_interval_secs = 15
+        "#,
+    );
+
+    // The file overrides defaults, and env overrides the file
+    let args = ConfigArgs {
+        config: Some(path.clone()),
+        ..Default::default()
+    };
+    let config = Config::load(&args).unwrap();
+    assert_eq!(config.database.max_connections, 20);
+    assert_eq!(config.database.min_connections, 1);
+    assert_eq!(config.cors.allowed_origins, vec!["https://shop.example"]);
+    let expected_port = env::var("SERVER_PORT").map_or(4000, |port| port.parse().unwrap());
+    assert_eq!(config.server.port, expected_port);
+    if env::var("CACHE_BACKEND").is_err() {
+        assert_eq!(config.cache.backend, "none");
+    }
+    if env::var("METRICS_SAMPLE_INTERVAL_SECS").is_err() {
+        assert_eq!(config.metrics.sample_interval_secs, 15);
+    }
+
+    // CLI flags override everything
+    let args = ConfigArgs {
+        config: Some(path.clone()),
+        port: Some(5000),
+        database_url: Some("postgres://cli:secret@db:5432/catalog".to_string()),
+        ..Default::default()
+    };
+    let config = Config::load(&args).unwrap();
+    assert_eq!(config.server.port, 5000);
+    assert_eq!(config.database.url, "postgres://cli:secret@db:5432/catalog");
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_config_rejects_missing_file() {
+    let args = ConfigArgs {
+        config: Some(env::temp_dir().join(format!("missing-{}.toml", Uuid::new_v4()))),
+        ..Default::default()
+    };
+
+    assert!(Config::load(&args).is_err());
+}
+
+#[test]
+fn test_config_validation_reports_every_problem() {
+    let mut config = Config::default();
+    config.database.max_connections = 0;
+    config.cache.backend = "redis".to_string();
+    config.telemetry.log_format = "xml".to_string();
+    config.cors.allowed_origins = vec!["shop.example".to_string()];
+    config.localization.default_locale = "es".parse().unwrap();
+    config.rate_limit.window_secs = 0;
+    config.metrics.sample_interval_secs = 0;
+
+    let message = config.validate().unwrap_err().to_string();
+
+    assert!(message.contains("database.max_connections"));
+    assert!(message.contains("database.min_connections"));
+    assert!(message.contains("cache.redis_url"));
+    assert!(message.contains("telemetry.log_format"));
+    assert!(message.contains("cors.allowed_origins"));
+    assert!(message.contains("localization.supported_locales"));
+    assert!(message.contains("rate_limit.window_secs"));
+    assert!(message.contains("metrics.sample_interval_secs"));
+    assert!(Config::default().validate().is_ok());
+}
+
+#[test]
+fn test_config_file_replaces_derivative_list() {
+    let path = write_config_file(
+        r#"
+        [[storage.derivatives]]
+        name = "square"
+        width = 300
+        format = "jpeg"
+        "#,
+    );
+    let args = ConfigArgs {
+        config: Some(path.clone()),
+        ..Default::default()
+    };
+    let config = Config::load(&args).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    let names: Vec<&str> = config
+        .storage
+        .derivatives
+        .iter()
+        .map(|derivative| derivative.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["square"]);
+
+    let mut config = Config::default();
+    config.storage.derivatives[1].name = "thumb".to_string();
+    config.storage.derivatives[1].format = "avif".to_string();
+    let error = config.validate().unwrap_err().to_string();
+    assert!(error.contains("name thumb is used twice"), "{}", error);
+    assert!(
+        error.contains("format must be webp, jpeg or png, got avif"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_config_redacts_secrets() {
+    let mut config = Config::default();
+    config.database.url = "postgres://catalog:s3cret@db:5432/catalog".to_string();
+    config.cache.redis_url = Some("redis://:hunter2@cache:6379".to_string());
+    config.storage.s3_secret_access_key = Some("swordfish".to_string());
+
+    let printed = toml::to_string_pretty(&config.redacted()).unwrap();
+
+    assert!(printed.contains("postgres://catalog:********@db:5432/catalog"));
+    assert!(printed.contains("redis://:********@cache:6379"));
+    assert!(!printed.contains("s3cret"));
+    assert!(!printed.contains("hunter2"));
+    assert!(!printed.contains("swordfish"));
+}
+
+#[tokio::test]
+async fn test_feature_toggles_and_cors() {
+    // Initialize test environment
+    let pool = initialize().await;
+    let mut config = Config::default();
+    config.features.metrics = false;
+    config.features.cache_stats = false;
+    config.cors.allowed_origins = vec!["https://shop.example".to_string()];
+
+    let health = Health::new(
+        pool.clone(),
+        std::time::Duration::from_millis(config.health.db_timeout_ms),
+        Shutdown::new(),
+    );
+    health.mark_schema_ready();
+    let rate_limiter = test_rate_limiter(&config.rate_limit);
+    let app = crate::app(
+        &config,
+        pool.clone(),
+        test_cache(),
+        test_storage(),
+        rate_limiter,
+        health,
+    );
+
+    // Disabled endpoints are not routed
+    for uri in ["/metrics", "/api/cache/stats"] {
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{}", uri);
+    }
+
+    // Allowed origins get CORS headers
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/categories")
+                .header("Origin", "https://shop.example")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["access-control-allow-origin"],
+        "https://shop.example"
+    );
+    let exposed = response.headers()["access-control-expose-headers"].to_str().unwrap();
+    assert!(exposed.contains("ratelimit-remaining"), "{}", exposed);
+}
@@ -0,0 +1,78 @@
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ConnAcquireErr, DbErr, Set};
+use validator::Validate;
+
+use super::common::initialize;
+use crate::entity::{CategoryActiveModel, ProductCategoryActiveModel};
+use crate::error::ApiError;
+use crate::models::product::CreateProductRequest;
+use crate::tenant::Tenant;
+
+fn status(error: ApiError) -> StatusCode {
+    error.into_response().status()
+}
+
+fn category(name: &str) -> CategoryActiveModel {
+    CategoryActiveModel {
+        name: Set(name.to_string()),
+        tenant_id: Set(Tenant::default().to_string()),
+        created_at: Set(Utc::now().into()),
+        updated_at: Set(Utc::now().into()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_constraint_violations_are_client_errors() {
+    let db = initialize().await;
+
+    // A value that must be unique is already taken
+    category("Lamps").insert(&db).await.unwrap();
+    let error = category("Lamps").insert(&db).await.unwrap_err();
+    assert_eq!(status(ApiError::Database(error)), StatusCode::CONFLICT);
+
+    // A referenced row doesn't exist
+    let link = ProductCategoryActiveModel {
+        product_id: Set(i32::MAX),
+        category_id: Set(i32::MAX),
+        tenant_id: Set(Tenant::default().to_string()),
+    };
+    let error = link.insert(&db).await.unwrap_err();
+    assert_eq!(status(ApiError::Database(error)), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn test_other_database_errors_are_server_errors() {
+    let busy = DbErr::ConnectionAcquire(ConnAcquireErr::Timeout);
+    assert_eq!(status(ApiError::Database(busy)), StatusCode::SERVICE_UNAVAILABLE);
+
+    let failed = DbErr::Custom("disk full".to_string());
+    assert_eq!(status(ApiError::Database(failed)), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[test]
+fn test_validation_errors_list_every_field() {
+    let request = CreateProductRequest {
+        name: String::new(),
+        description: None,
+        price: "10.00 USD".parse().unwrap(),
+        sku: None,
+        category_ids: Vec::new(),
+        attributes: Default::default(),
+        tags: Vec::new(),
+    };
+    let error = ApiError::from(request.validate().unwrap_err());
+
+    // Fields are listed in name order so the message is stable
+    match &error {
+        ApiError::BadRequest(message) => assert_eq!(
+            message,
+            "category_ids: At least one category ID must be provided; \
+             name: Product name cannot be empty and must be less than 256 characters"
+        ),
+        other => panic!("expected a bad request, got {:?}", other),
+    }
+    assert_eq!(status(error), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,238 @@
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+use super::common::{create_test_app, create_test_app_with, initialize, test_cache, test_storage};
+use crate::config::Config;
+
+/// Send a request with an optional JSON body and return the status and parsed JSON response
+async fn send(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Content-Type", "application/json");
+    let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+
+    let response = app.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+    let status = response.status();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+/// Create a small catalog and return the IDs of its `Lamps` and `Outdoor` categories
+///
+/// | Product | Price  | Categories      | color |
+/// |---------|--------|-----------------|-------|
+/// | FACET-1 | 5.00   | Lamps           | red   |
+/// | FACET-2 | 30.00  | Lamps, Outdoor  | blue  |
+/// | FACET-3 | 45.00  | Lamps           | red   |
+/// | FACET-4 | 300.00 | Outdoor         | -     |
+async fn create_catalog(app: &Router) -> (i64, i64) {
+    let mut ids = Vec::new();
+    for name in ["Lamps", "Outdoor"] {
+        let (status, category) = send(app, "POST", "/api/categories", Some(json!({ "name": name }))).await;
+        assert_eq!(status, StatusCode::OK);
+        ids.push(category["id"].as_i64().unwrap());
+    }
+    let (lamps, outdoor) = (ids[0], ids[1]);
+
+    let (status, _) = send(
+        app,
+        "POST",
+        &format!("/api/categories/{}/attributes", lamps),
+        Some(json!({ "name": "color", "type": "enum", "values": ["red", "blue"] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    for (sku, price, category_ids, attributes) in [
+        ("FACET-1", "5.00", vec![lamps], json!({ "color": "red" })),
+        ("FACET-2", "30.00", vec![lamps, outdoor], json!({ "color": "blue" })),
+        ("FACET-3", "45.00", vec![lamps], json!({ "color": "red" })),
+        ("FACET-4", "300.00", vec![outdoor], json!({})),
+    ] {
+        let body = json!({
+            "name": sku,
+            "price": price,
+            "sku": sku,
+            "category_ids": category_ids,
+            "attributes": attributes,
+        });
+        let (status, _) = send(app, "POST", "/api/products", Some(body)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    (lamps, outdoor)
+}
+
+fn price_counts(facets: &Value) -> Vec<i64> {
+    facets["price_range"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|bucket| bucket["count"].as_i64().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_facets_count_the_whole_filtered_set() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+
+    let (lamps, outdoor) = create_catalog(&app).await;
+
+    let (status, list) = send(
+        &app,
+        "GET",
+        "/api/products?page_size=1&facets=category,price_range,attr.color",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(list["products"].as_array().unwrap().len(), 1);
+    assert_eq!(list["total"], 4);
+
+    let facets = &list["facets"];
+    assert_eq!(
+        facets["category"],
+        json!([
+            { "id": lamps, "name": "Lamps", "count": 3 },
+            { "id": outdoor, "name": "Outdoor", "count": 2 },
+        ])
+    );
+    // Default buckets: under 10, 10-25, 25-50, 50-100, 100-250, 250 and over
+    assert_eq!(price_counts(facets), vec![1, 0, 2, 0, 0, 1]);
+    assert_eq!(facets["price_range"][0]["currency"], "USD");
+    assert_eq!(facets["price_range"][0]["min"], Value::Null);
+    assert_eq!(facets["price_range"][0]["max"], "10");
+    assert_eq!(facets["price_range"][5]["min"], "250");
+    assert_eq!(facets["price_range"][5]["max"], Value::Null);
+    assert_eq!(
+        facets["attributes"]["color"],
+        json!([{ "value": "red", "count": 2 }, { "value": "blue", "count": 1 }])
+    );
+
+    // Facets follow the category and attribute filters
+    let (_, list) = send(
+        &app,
+        "GET",
+        &format!("/api/products?category_id={}&facets=category,price_range", outdoor),
+        None,
+    )
+    .await;
+    assert_eq!(
+        list["facets"]["category"],
+        json!([
+            { "id": outdoor, "name": "Outdoor", "count": 2 },
+            { "id": lamps, "name": "Lamps", "count": 1 },
+        ])
+    );
+    assert_eq!(price_counts(&list["facets"]), vec![0, 0, 1, 0, 0, 1]);
+    assert!(list["facets"].get("attributes").is_none());
+
+    let (_, list) = send(&app, "GET", "/api/products?attr.color=red&facets=category", None).await;
+    assert_eq!(list["total"], 2);
+    assert_eq!(
+        list["facets"]["category"],
+        json!([{ "id": lamps, "name": "Lamps", "count": 2 }])
+    );
+}
+
+#[tokio::test]
+async fn test_facets_are_only_returned_when_requested() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+
+    create_catalog(&app).await;
+
+    let (status, list) = send(&app, "GET", "/api/products", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(list.get("facets").is_none());
+
+    // Unknown attributes simply have no values
+    let (_, list) = send(&app, "GET", "/api/products?facets=attr.voltage", None).await;
+    assert_eq!(list["facets"], json!({ "attributes": { "voltage": [] } }));
+
+    for facets in ["brand", "attr.", "category,price"] {
+        let (status, error) = send(&app, "GET", &format!("/api/products?facets={}", facets), None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", facets);
+        assert!(
+            error["error"]["message"].as_str().unwrap().contains("unknown facet"),
+            "{}",
+            error
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_price_buckets_are_configurable() {
+    let db = initialize().await;
+    let mut config = Config::default();
+    config.facets.price_buckets = vec![20.0, 99.99];
+    let app = create_test_app_with(&config, db.clone(), test_cache(), test_storage());
+
+    create_catalog(&app).await;
+
+    let (_, list) = send(&app, "GET", "/api/products?facets=price_range", None).await;
+    assert_eq!(
+        list["facets"]["price_range"],
+        json!([
+            { "currency": "USD", "min": null, "max": "20", "count": 1 },
+            { "currency": "USD", "min": "20", "max": "99.99", "count": 2 },
+            { "currency": "USD", "min": "99.99", "max": null, "count": 1 },
+        ])
+    );
+
+    let mut config = Config::default();
+    config.facets.price_buckets = vec![50.0, 10.0];
+    let error = config.validate().unwrap_err().to_string();
+    assert!(
+        error.contains("facets.price_buckets must be strictly ascending"),
+        "{}",
+        error
+    );
+}
+
+#[tokio::test]
+async fn test_price_ranges_are_counted_per_currency() {
+    let db = initialize().await;
+    let mut config = Config::default();
+    config.facets.price_buckets = vec![20.0];
+    let app = create_test_app_with(&config, db.clone(), test_cache(), test_storage());
+
+    let (_, category) = send(&app, "POST", "/api/categories", Some(json!({ "name": "Lamps" }))).await;
+    for (sku, price, currency) in [
+        ("FX-1", "5.00", "USD"),
+        ("FX-2", "30.00", "EUR"),
+        ("FX-3", "40.00", "USD"),
+    ] {
+        let body = json!({
+            "name": sku,
+            "price": price,
+            "currency": currency,
+            "sku": sku,
+            "category_ids": [category["id"]],
+        });
+        let (status, _) = send(&app, "POST", "/api/products", Some(body)).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    let (_, list) = send(&app, "GET", "/api/products?facets=price_range", None).await;
+    assert_eq!(
+        list["facets"]["price_range"],
+        json!([
+            { "currency": "EUR", "min": null, "max": "20", "count": 0 },
+            { "currency": "EUR", "min": "20", "max": null, "count": 1 },
+            { "currency": "USD", "min": null, "max": "20", "count": 1 },
+            { "currency": "USD", "min": "20", "max": null, "count": 1 },
+        ])
+    );
+
+    // Without matching products there are no currencies to bucket
+    let (_, list) = send(&app, "GET", "/api/products?tag=none&facets=price_range", None).await;
+    assert_eq!(list["total"], 0);
+    assert_eq!(list["facets"]["price_range"], json!([]));
+}
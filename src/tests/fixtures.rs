@@ -0,0 +1,119 @@
+use serde_json::Value;
+
+use super::common::TestApp;
+use crate::models::category::{CategoryResponse, CreateCategoryRequest};
+use crate::models::money::{self, Currency, Money};
+use crate::models::product::{CreateProductRequest, ProductResponse};
+
+/// Builder for a product created through the API
+///
+/// Starts from a valid product without a SKU, so any number can be created; a category still has
+/// to be given with [`ProductFixture::in_categories`] unless the request is meant to fail.
+pub struct ProductFixture {
+    request: CreateProductRequest,
+}
+
+impl ProductFixture {
+    pub fn new() -> Self {
+        Self {
+            request: CreateProductRequest {
+                name: "Test Product".to_string(),
+                description: None,
+                price: "19.99 USD".parse().unwrap(),
+                sku: None,
+                category_ids: Vec::new(),
+                attributes: Default::default(),
+                tags: Vec::new(),
+            },
+        }
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.request.name = name.to_string();
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.request.description = Some(description.to_string());
+        self
+    }
+
+    /// Set the price from its decimal string, e.g. `"12.50"`
+    pub fn price(mut self, price: &str) -> Self {
+        let amount = money::parse_amount(price).expect("Invalid fixture price");
+        self.request.price = Money::new(amount, self.request.price.currency()).unwrap();
+        self
+    }
+
+    pub fn currency(mut self, currency: &str) -> Self {
+        let currency: Currency = currency.parse().expect("Invalid fixture currency");
+        self.request.price = self.request.price.with_currency(currency);
+        self
+    }
+
+    pub fn sku(mut self, sku: &str) -> Self {
+        self.request.sku = Some(sku.to_string());
+        self
+    }
+
+    pub fn in_categories(mut self, category_ids: &[i32]) -> Self {
+        self.request.category_ids = category_ids.to_vec();
+        self
+    }
+
+    pub fn attribute(mut self, name: &str, value: Value) -> Self {
+        self.request.attributes.insert(name.to_string(), value);
+        self
+    }
+
+    pub fn tags(mut self, tags: &[&str]) -> Self {
+        self.request.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        self
+    }
+
+    /// The request body, for sending it unchanged or checking how it is rejected
+    pub fn request(self) -> CreateProductRequest {
+        self.request
+    }
+
+    /// Create the product, failing the test unless that succeeds
+    pub async fn create(self, app: &TestApp) -> ProductResponse {
+        app.post("/api/products", &self.request).await.ok()
+    }
+}
+
+/// Builder for a category created through the API
+pub struct CategoryFixture {
+    request: CreateCategoryRequest,
+}
+
+impl CategoryFixture {
+    pub fn new() -> Self {
+        Self {
+            request: CreateCategoryRequest {
+                name: "Test Category".to_string(),
+                description: None,
+            },
+        }
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.request.name = name.to_string();
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.request.description = Some(description.to_string());
+        self
+    }
+
+    /// The request body, for sending it unchanged or checking how it is rejected
+    pub fn request(self) -> CreateCategoryRequest {
+        self.request
+    }
+
+    /// Create the category, failing the test unless that succeeds
+    pub async fn create(self, app: &TestApp) -> CategoryResponse {
+        app.post("/api/categories", &self.request).await.ok()
+    }
+}
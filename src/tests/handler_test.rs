@@ -0,0 +1,405 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use chrono::{Duration, Utc};
+
+use super::memory_store::MemoryCatalog;
+use crate::api::{category, product};
+use crate::auth::Editor;
+use crate::error::ApiError;
+use crate::locale::LocaleChain;
+use crate::models::category::{CategoryQueryParams, CreateCategoryRequest, UpdateCategoryRequest};
+use crate::models::lifecycle::{ProductStatus, ScheduleRequest};
+use crate::models::product::{CreateProductRequest, ProductQueryParams, UpdateProductRequest};
+use crate::repository::store::{CategoryStore, MockProductStore, ProductStore};
+use crate::request_context::RequestContext;
+use crate::tenant::Tenant;
+
+fn ctx() -> RequestContext {
+    RequestContext::from_headers(&HeaderMap::new())
+}
+
+fn editor() -> Editor {
+    Editor {
+        api_key_id: 1,
+        name: "test editor".to_string(),
+        tenant: Tenant::default(),
+    }
+}
+
+/// A second tenant, whose rows the default tenant must never see
+fn acme() -> Tenant {
+    Tenant::from_str("acme").unwrap()
+}
+
+/// Status code a handler result would be served with
+fn status<T>(result: Result<T, ApiError>) -> StatusCode {
+    match result {
+        Ok(_) => StatusCode::OK,
+        Err(error) => error.into_response().status(),
+    }
+}
+
+fn product_request(name: &str, sku: Option<&str>, category_id: i32) -> CreateProductRequest {
+    CreateProductRequest {
+        name: name.to_string(),
+        description: None,
+        price: "9.99 USD".parse().unwrap(),
+        sku: sku.map(str::to_string),
+        category_ids: vec![category_id],
+        attributes: Default::default(),
+        tags: Vec::new(),
+    }
+}
+
+fn update_request() -> UpdateProductRequest {
+    UpdateProductRequest {
+        name: None,
+        description: None,
+        price: None,
+        currency: None,
+        sku: None,
+        category_ids: None,
+        attributes: None,
+        tags: None,
+    }
+}
+
+fn list_params() -> ProductQueryParams {
+    serde_json::from_value(serde_json::json!({})).unwrap()
+}
+
+async fn create_category(catalog: &MemoryCatalog, tenant: Tenant, name: &str) -> i32 {
+    let request = CreateCategoryRequest {
+        name: name.to_string(),
+        description: None,
+    };
+    let Json(category) = category::create_category(ctx(), tenant, editor(), State(categories(catalog)), Json(request))
+        .await
+        .unwrap();
+    category.id
+}
+
+async fn create_product(catalog: &MemoryCatalog, tenant: Tenant, request: CreateProductRequest) -> i32 {
+    let Json(product) = product::create_product(ctx(), tenant, editor(), State(products(catalog)), Json(request))
+        .await
+        .unwrap();
+    product.id
+}
+
+fn products(catalog: &MemoryCatalog) -> Arc<dyn ProductStore> {
+    Arc::new(catalog.clone())
+}
+
+fn categories(catalog: &MemoryCatalog) -> Arc<dyn CategoryStore> {
+    Arc::new(catalog.clone())
+}
+
+#[tokio::test]
+async fn test_missing_products_and_categories_are_not_found() {
+    let catalog = MemoryCatalog::new();
+    let tenant = Tenant::default();
+
+    let result = product::get_product(
+        ctx(),
+        tenant.clone(),
+        LocaleChain::default(),
+        Some(editor()),
+        State(products(&catalog)),
+        Path(1),
+    )
+    .await;
+    assert_eq!(status(result), StatusCode::NOT_FOUND);
+
+    let result = product::update_product(
+        ctx(),
+        tenant.clone(),
+        editor(),
+        State(products(&catalog)),
+        Path(1),
+        Json(update_request()),
+    )
+    .await;
+    assert_eq!(status(result), StatusCode::NOT_FOUND);
+
+    let result = product::delete_product(ctx(), tenant.clone(), editor(), State(products(&catalog)), Path(1)).await;
+    assert_eq!(status(result), StatusCode::NOT_FOUND);
+
+    let result = product::publish_product(ctx(), tenant.clone(), editor(), State(products(&catalog)), Path(1)).await;
+    assert_eq!(status(result), StatusCode::NOT_FOUND);
+
+    let result = category::get_category_products(
+        ctx(),
+        tenant.clone(),
+        LocaleChain::default(),
+        None,
+        State(categories(&catalog)),
+        Path(1),
+    )
+    .await;
+    assert_eq!(status(result), StatusCode::NOT_FOUND);
+
+    // Another tenant's rows are just as missing
+    let category_id = create_category(&catalog, tenant.clone(), "Shoes").await;
+    let product_id = create_product(&catalog, tenant, product_request("Boot", None, category_id)).await;
+    let result = product::get_product(
+        ctx(),
+        acme(),
+        LocaleChain::default(),
+        Some(editor()),
+        State(products(&catalog)),
+        Path(product_id),
+    )
+    .await;
+    assert_eq!(status(result), StatusCode::NOT_FOUND);
+    let result =
+        category::delete_category(ctx(), acme(), editor(), State(categories(&catalog)), Path(category_id)).await;
+    assert_eq!(status(result), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_anonymous_callers_only_see_published_products() {
+    let catalog = MemoryCatalog::new();
+    let tenant = Tenant::default();
+    let category_id = create_category(&catalog, tenant.clone(), "Shoes").await;
+    let id = create_product(&catalog, tenant.clone(), product_request("Boot", None, category_id)).await;
+
+    let get = |editor: Option<Editor>| {
+        product::get_product(
+            ctx(),
+            tenant.clone(),
+            LocaleChain::default(),
+            editor,
+            State(products(&catalog)),
+            Path(id),
+        )
+    };
+    assert_eq!(status(get(None).await), StatusCode::NOT_FOUND);
+    assert_eq!(status(get(Some(editor())).await), StatusCode::OK);
+
+    let list = |editor: Option<Editor>| {
+        product::list_products(
+            ctx(),
+            tenant.clone(),
+            LocaleChain::default(),
+            editor,
+            State(products(&catalog)),
+            Query(list_params()),
+            Query(Vec::new()),
+        )
+    };
+    assert_eq!(list(None).await.unwrap().total, 0);
+    assert_eq!(list(Some(editor())).await.unwrap().total, 1);
+
+    product::submit_product(ctx(), tenant.clone(), editor(), State(products(&catalog)), Path(id))
+        .await
+        .unwrap();
+    product::publish_product(ctx(), tenant.clone(), editor(), State(products(&catalog)), Path(id))
+        .await
+        .unwrap();
+    let Json(product) = get(None).await.unwrap();
+    assert_eq!(product.lifecycle.status, ProductStatus::Published);
+    assert!(product.lifecycle.published_at.is_some());
+    assert_eq!(list(None).await.unwrap().total, 1);
+}
+
+#[tokio::test]
+async fn test_duplicates_and_invalid_transitions_conflict() {
+    let catalog = MemoryCatalog::new();
+    let tenant = Tenant::default();
+    let category_id = create_category(&catalog, tenant.clone(), "Shoes").await;
+    let id = create_product(
+        &catalog,
+        tenant.clone(),
+        product_request("Boot", Some("BOOT-1"), category_id),
+    )
+    .await;
+
+    // SKUs and category names are unique within a tenant only
+    let result = product::create_product(
+        ctx(),
+        tenant.clone(),
+        editor(),
+        State(products(&catalog)),
+        Json(product_request("Other boot", Some("BOOT-1"), category_id)),
+    )
+    .await;
+    assert_eq!(status(result), StatusCode::CONFLICT);
+    let other_category = create_category(&catalog, acme(), "Shoes").await;
+    create_product(
+        &catalog,
+        acme(),
+        product_request("Boot", Some("BOOT-1"), other_category),
+    )
+    .await;
+
+    let second = create_category(&catalog, tenant.clone(), "Hats").await;
+    let rename = UpdateCategoryRequest {
+        name: Some("Shoes".to_string()),
+        description: None,
+    };
+    let result = category::update_category(
+        ctx(),
+        tenant.clone(),
+        editor(),
+        State(categories(&catalog)),
+        Path(second),
+        Json(rename),
+    )
+    .await;
+    assert_eq!(status(result), StatusCode::CONFLICT);
+
+    // Workflow steps only apply from their source statuses
+    let result = product::publish_product(ctx(), tenant.clone(), editor(), State(products(&catalog)), Path(id)).await;
+    assert_eq!(status(result), StatusCode::CONFLICT);
+    let result = product::restore_product(ctx(), tenant.clone(), editor(), State(products(&catalog)), Path(id)).await;
+    assert_eq!(status(result), StatusCode::CONFLICT);
+
+    let schedule = ScheduleRequest {
+        publish_at: Some((Utc::now() + Duration::hours(1)).into()),
+        unpublish_at: None,
+    };
+    let result = product::schedule_product(
+        ctx(),
+        tenant,
+        editor(),
+        State(products(&catalog)),
+        Path(id),
+        Json(schedule),
+    )
+    .await;
+    assert_eq!(status(result), StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_invalid_requests_are_rejected() {
+    let catalog = MemoryCatalog::new();
+    let tenant = Tenant::default();
+    let category_id = create_category(&catalog, tenant.clone(), "Shoes").await;
+    let id = create_product(&catalog, tenant.clone(), product_request("Boot", None, category_id)).await;
+
+    let create = |request: CreateProductRequest| {
+        product::create_product(
+            ctx(),
+            tenant.clone(),
+            editor(),
+            State(products(&catalog)),
+            Json(request),
+        )
+    };
+    assert_eq!(
+        status(create(product_request("", None, category_id)).await),
+        StatusCode::BAD_REQUEST
+    );
+
+    // Prices built in code skip the checks made while reading a request body
+    let mut request = product_request("Boot", None, category_id);
+    request.price = "0 USD".parse().unwrap();
+    assert_eq!(status(create(request).await), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let mut request = product_request("Boot", None, category_id);
+    request.category_ids.clear();
+    assert_eq!(status(create(request).await), StatusCode::BAD_REQUEST);
+
+    // Categories must exist in the product's own tenant
+    let other_category = create_category(&catalog, acme(), "Shoes").await;
+    assert_eq!(
+        status(create(product_request("Boot", None, other_category)).await),
+        StatusCode::BAD_REQUEST
+    );
+
+    let mut update = update_request();
+    update.name = Some(String::new());
+    let result = product::update_product(
+        ctx(),
+        tenant.clone(),
+        editor(),
+        State(products(&catalog)),
+        Path(id),
+        Json(update),
+    )
+    .await;
+    assert_eq!(status(result), StatusCode::BAD_REQUEST);
+
+    let publish_at = Utc::now() + Duration::hours(2);
+    let schedule = ScheduleRequest {
+        publish_at: Some(publish_at.into()),
+        unpublish_at: Some((publish_at - Duration::hours(1)).into()),
+    };
+    let result = product::schedule_product(
+        ctx(),
+        tenant.clone(),
+        editor(),
+        State(products(&catalog)),
+        Path(id),
+        Json(schedule),
+    )
+    .await;
+    assert_eq!(status(result), StatusCode::BAD_REQUEST);
+
+    let request = CreateCategoryRequest {
+        name: String::new(),
+        description: None,
+    };
+    let result = category::create_category(
+        ctx(),
+        tenant.clone(),
+        editor(),
+        State(categories(&catalog)),
+        Json(request),
+    )
+    .await;
+    assert_eq!(status(result), StatusCode::BAD_REQUEST);
+
+    // Nothing was stored by the rejected requests
+    let Json(list) = category::list_categories(
+        ctx(),
+        tenant,
+        LocaleChain::default(),
+        Some(editor()),
+        State(categories(&catalog)),
+        Query(CategoryQueryParams {
+            include_product_count: Some(true),
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(list.categories.len(), 1);
+    assert_eq!(list.categories[0].product_count, Some(1));
+}
+
+#[tokio::test]
+async fn test_handlers_use_the_requests_tenant_and_validate_first() {
+    let mut store = MockProductStore::new();
+    store
+        .expect_for_tenant()
+        .withf(|tenant| tenant.as_str() == "acme")
+        .times(2)
+        .returning(|_| {
+            let mut scoped = MockProductStore::new();
+            scoped
+                .expect_delete_product()
+                .returning(|_| Err(ApiError::Conflict("Product is locked".to_string())));
+            Arc::new(scoped)
+        });
+    let store: Arc<dyn ProductStore> = Arc::new(store);
+
+    // Store errors are passed through unchanged
+    let result = product::delete_product(ctx(), acme(), editor(), State(store.clone()), Path(1)).await;
+    assert_eq!(status(result), StatusCode::CONFLICT);
+
+    // An invalid request never reaches the store, whose mock has no create expectation
+    let result = product::create_product(
+        ctx(),
+        acme(),
+        editor(),
+        State(store),
+        Json(product_request("", None, 1)),
+    )
+    .await;
+    assert_eq!(status(result), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use serde_json::Value;
+use tower::ServiceExt;
+
+use super::common::{create_test_app, initialize, test_cache, test_rate_limiter, test_storage};
+use crate::config::Config;
+use crate::health::Health;
+use crate::shutdown::Shutdown;
+
+async fn get_health(app: &Router, uri: &str) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(Request::builder().method("GET").uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn test_liveness_probe() {
+    // Initialize test environment
+    let pool = initialize().await;
+    let app = create_test_app(pool.clone());
+
+    let (status, body) = get_health(&app, "/health/live").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ok");
+    assert_eq!(body["build"]["version"], env!("CARGO_PKG_VERSION"));
+    assert!(body["build"]["git_sha"].is_string());
+    assert!(body["build"]["uptime_secs"].is_u64());
+}
+
+#[tokio::test]
+async fn test_readiness_probe() {
+    // Initialize test environment
+    let pool = initialize().await;
+    let app = create_test_app(pool.clone());
+
+    let (status, body) = get_health(&app, "/health/ready").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ok");
+    assert_eq!(body["checks"]["database"]["status"], "ok");
+    assert!(body["checks"]["database"]["latency_ms"].is_u64());
+    assert_eq!(body["checks"]["database"]["pool"]["max"], 5);
+    assert_eq!(body["checks"]["schema"]["status"], "ok");
+}
+
+#[tokio::test]
+async fn test_readiness_fails_before_schema_sync() {
+    // Initialize test environment without marking the schema as ready
+    let pool = initialize().await;
+    let health = Health::new(pool.clone(), Duration::from_secs(1), Shutdown::new());
+    let app = crate::app(
+        &Config::default(),
+        pool.clone(),
+        test_cache(),
+        test_storage(),
+        test_rate_limiter(&Config::default().rate_limit),
+        health.clone(),
+    );
+
+    let (status, body) = get_health(&app, "/health/ready").await;
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["status"], "unavailable");
+    assert_eq!(body["checks"]["database"]["status"], "ok");
+    assert_eq!(body["checks"]["schema"]["status"], "unavailable");
+
+    // Liveness is unaffected
+    let (status, _) = get_health(&app, "/health/live").await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Once the schema is in place the service becomes ready
+    health.mark_schema_ready();
+    let (status, _) = get_health(&app, "/health/ready").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_readiness_fails_once_shutdown_begins() {
+    // Initialize test environment
+    let pool = initialize().await;
+    let shutdown = Shutdown::new();
+    let health = Health::new(pool.clone(), Duration::from_secs(1), shutdown.clone());
+    health.mark_schema_ready();
+    let config = Config::default();
+    let rate_limiter = test_rate_limiter(&config.rate_limit);
+    let app = crate::app(
+        &config,
+        pool.clone(),
+        test_cache(),
+        test_storage(),
+        rate_limiter,
+        health,
+    );
+
+    let (status, body) = get_health(&app, "/health/ready").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["checks"]["server"]["status"], "ok");
+
+    // Readiness flips as soon as shutdown is triggered
+    shutdown.trigger();
+    let (status, body) = get_health(&app, "/health/ready").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["status"], "unavailable");
+    assert_eq!(body["checks"]["server"]["status"], "unavailable");
+    assert_eq!(body["checks"]["database"]["status"], "ok");
+
+    // Liveness is unaffected while draining
+    let (status, _) = get_health(&app, "/health/live").await;
+    assert_eq!(status, StatusCode::OK);
+}
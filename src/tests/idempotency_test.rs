@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{HeaderMap, Method, Request, StatusCode, Uri};
+use serde_json::{Value, json};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use super::common::{
+    create_public_test_app, create_test_app, create_test_app_with, initialize, test_cache, test_storage,
+};
+use crate::config::Config;
+use crate::idempotency::request_hash;
+use crate::repository::IdempotencyRepository;
+use crate::repository::idempotency::Claim;
+use crate::tenant::Tenant;
+
+/// Send a JSON request with an optional `Idempotency-Key`, returning the status, headers and
+/// parsed JSON response
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    key: Option<&str>,
+    body: &Value,
+) -> (StatusCode, HeaderMap, Value) {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Content-Type", "application/json");
+    if let Some(key) = key {
+        request = request.header("Idempotency-Key", key);
+    }
+
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::from(body.to_string())).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let headers = response.headers().clone();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+    (status, headers, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+async fn create_category(app: &Router) -> i64 {
+    let (status, _, category) = send(app, "POST", "/api/categories", None, &json!({ "name": "Furniture" })).await;
+    assert_eq!(status, StatusCode::OK);
+    category["id"].as_i64().unwrap()
+}
+
+fn product(name: &str, price: &str, category_id: i64) -> Value {
+    json!({ "name": name, "price": price, "category_ids": [category_id] })
+}
+
+async fn product_count(app: &Router) -> u64 {
+    let (_, _, list) = send(app, "GET", "/api/products", None, &Value::Null).await;
+    list["total"].as_u64().unwrap()
+}
+
+#[tokio::test]
+async fn test_retries_replay_the_original_response() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+
+    let category_id = create_category(&app).await;
+    let key = Uuid::new_v4().to_string();
+    let body = product("No SKU", "10.00", category_id);
+
+    let (status, headers, created) = send(&app, "POST", "/api/products", Some(&key), &body).await;
+    assert_eq!(status, StatusCode::OK, "{}", created);
+    assert!(headers.get("idempotent-replayed").is_none());
+
+    let (status, headers, replayed) = send(&app, "POST", "/api/products", Some(&key), &body).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers["idempotent-replayed"], "true");
+    assert_eq!(headers["content-type"], "application/json");
+    assert_eq!(replayed, created);
+    assert_eq!(product_count(&app).await, 1);
+
+    // Other keys, and requests without one, create new products
+    let (_, _, other) = send(&app, "POST", "/api/products", Some(&Uuid::new_v4().to_string()), &body).await;
+    assert_ne!(other["id"], created["id"]);
+    send(&app, "POST", "/api/products", None, &body).await;
+    assert_eq!(product_count(&app).await, 3);
+
+    // Client errors are replayed too, so a bad request isn't silently retried into a different one
+    let key = Uuid::new_v4().to_string();
+    let invalid = json!({ "name": "", "price": "10.00" });
+    let (status, _, _) = send(&app, "POST", "/api/categories", Some(&key), &invalid).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, headers, _) = send(&app, "POST", "/api/categories", Some(&key), &invalid).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(headers["idempotent-replayed"], "true");
+}
+
+#[tokio::test]
+async fn test_reused_keys_and_in_flight_requests_are_rejected() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+
+    let category_id = create_category(&app).await;
+    let key = Uuid::new_v4().to_string();
+    let body = product("Lamp", "20.00", category_id);
+    let (status, _, _) = send(&app, "POST", "/api/products", Some(&key), &body).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // The same key with a different body or endpoint
+    let other = product("Lamp", "25.00", category_id);
+    let (status, _, error) = send(&app, "POST", "/api/products", Some(&key), &other).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(error["error"]["status"], 422);
+    let (status, _, _) = send(&app, "POST", "/api/categories", Some(&key), &body).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    // A request still running under the key, as seen by a concurrent retry
+    let key = Uuid::new_v4().to_string();
+    let keys = IdempotencyRepository::new(db.clone(), &Config::default().idempotency, Duration::from_secs(30))
+        .for_tenant(&Tenant::default());
+    let hash = request_hash(
+        &Method::POST,
+        &Uri::from_static("/products"),
+        body.to_string().as_bytes(),
+    );
+    assert_eq!(keys.claim("key:0", &key, &hash).await.unwrap(), Claim::Acquired);
+    let (status, _, error) = send(&app, "POST", "/api/products", Some(&key), &body).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{}", error);
+    keys.release("key:0", &key).await.unwrap();
+    let (status, _, _) = send(&app, "POST", "/api/products", Some(&key), &body).await;
+    assert_eq!(status, StatusCode::OK);
+
+    for invalid in ["k".repeat(256), "two words".to_string(), "tab\tkey".to_string()] {
+        let (status, _, _) = send(&app, "POST", "/api/products", Some(&invalid), &body).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{:?}", invalid);
+    }
+
+    // Keys are scoped to an API key, so anonymous requests can't use them
+    let public = create_public_test_app(db.clone());
+    let (status, _, error) = send(&public, "POST", "/api/products", Some(&key), &body).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(error["error"]["message"].as_str().unwrap().contains("Idempotency-Key"));
+}
+
+#[tokio::test]
+async fn test_keys_expire_after_the_ttl() {
+    let db = initialize().await;
+    let mut config = Config::default();
+    config.idempotency.ttl_secs = 1;
+    let app = create_test_app_with(&config, db.clone(), test_cache(), test_storage());
+
+    let category_id = create_category(&app).await;
+    let key = Uuid::new_v4().to_string();
+    let body = product("Expiring", "5.00", category_id);
+    let (_, _, first) = send(&app, "POST", "/api/products", Some(&key), &body).await;
+    let (_, _, replayed) = send(&app, "POST", "/api/products", Some(&key), &body).await;
+    assert_eq!(replayed["id"], first["id"]);
+
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    let keys = IdempotencyRepository::new(db.clone(), &config.idempotency, Duration::from_secs(30));
+    assert!(keys.purge_expired().await.unwrap() >= 1);
+
+    let (status, headers, second) = send(&app, "POST", "/api/products", Some(&key), &body).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(headers.get("idempotent-replayed").is_none());
+    assert_ne!(second["id"], first["id"]);
+}
@@ -0,0 +1,205 @@
+use std::io::{Read, Write};
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::http::{HeaderMap, Request, StatusCode, header};
+use axum::routing::get;
+use axum::{Router, middleware};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use sea_orm::{Database, TransactionTrait};
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+use super::common::{
+    create_public_test_app, create_test_app_with, initialize, isolated_database, test_cache, test_storage,
+};
+use crate::config::Config;
+use crate::layers;
+use crate::migration::Migrator;
+
+/// Send a request with the given headers and raw body, returning the status, headers and
+/// undecoded response body
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    headers: &[(&str, &str)],
+    body: Vec<u8>,
+) -> (StatusCode, HeaderMap, Vec<u8>) {
+    let mut request = Request::builder().method(method).uri(uri);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::from(body)).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let headers = response.headers().clone();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+    (status, headers, bytes.to_vec())
+}
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn content_encoding(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::CONTENT_ENCODING)
+        .map(|value| value.to_str().unwrap())
+}
+
+/// The error envelope of a response body
+fn error(body: &[u8]) -> Value {
+    serde_json::from_slice::<Value>(body).unwrap()["error"].clone()
+}
+
+#[tokio::test]
+async fn test_responses_are_compressed_as_negotiated() {
+    let db = initialize().await;
+    let mut config = Config::default();
+    config.compression.min_size_bytes = 16;
+    let app = create_test_app_with(&config, db.clone(), test_cache(), test_storage());
+
+    let (status, headers, body) = send(&app, "GET", "/api/products", &[("Accept-Encoding", "gzip")], vec![]).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_encoding(&headers), Some("gzip"));
+    let mut decoded = String::new();
+    GzDecoder::new(body.as_slice()).read_to_string(&mut decoded).unwrap();
+    let list: Value = serde_json::from_str(&decoded).unwrap();
+    assert!(list["products"].is_array());
+
+    for (accept_encoding, expected) in [
+        ("br", Some("br")),
+        ("zstd, gzip;q=0.5", Some("zstd")),
+        ("identity", None),
+        ("", None),
+    ] {
+        let (_, headers, _) = send(
+            &app,
+            "GET",
+            "/api/products",
+            &[("Accept-Encoding", accept_encoding)],
+            vec![],
+        )
+        .await;
+        assert_eq!(content_encoding(&headers), expected, "{}", accept_encoding);
+    }
+
+    // Only configured algorithms are offered, and small responses are left alone
+    config.compression.algorithms = vec!["gzip".to_string()];
+    config.compression.min_size_bytes = u16::MAX;
+    let app = create_test_app_with(&config, db.clone(), test_cache(), test_storage());
+    let (_, headers, _) = send(&app, "GET", "/api/products", &[("Accept-Encoding", "gzip, br")], vec![]).await;
+    assert_eq!(content_encoding(&headers), None);
+    config.compression.min_size_bytes = 16;
+    let app = create_test_app_with(&config, db.clone(), test_cache(), test_storage());
+    let (_, headers, _) = send(&app, "GET", "/api/products", &[("Accept-Encoding", "br")], vec![]).await;
+    assert_eq!(content_encoding(&headers), None);
+}
+
+#[tokio::test]
+async fn test_request_bodies_are_decompressed_and_limited() {
+    let db = initialize().await;
+    let mut config = Config::default();
+    config.limits.max_body_bytes = 1024;
+    let app = create_test_app_with(&config, db.clone(), test_cache(), test_storage());
+    let json = [("Content-Type", "application/json")];
+    let gzipped_json = [("Content-Type", "application/json"), ("Content-Encoding", "gzip")];
+
+    let body = json!({ "name": "Compressed", "description": "Sent gzipped" }).to_string();
+    let (status, _, category) = send(&app, "POST", "/api/categories", &gzipped_json, gzip(body.as_bytes())).await;
+    assert_eq!(status, StatusCode::OK);
+    let category: Value = serde_json::from_slice(&category).unwrap();
+    assert_eq!(category["name"], "Compressed");
+
+    // Oversized bodies are refused in the error envelope, however well they compress
+    let body = json!({ "name": "Large", "description": "x".repeat(4096) }).to_string();
+    let (status, _, response) = send(&app, "POST", "/api/categories", &json, body.clone().into_bytes()).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(error(&response)["status"], 413);
+    let compressed = gzip(body.as_bytes());
+    assert!(compressed.len() < 1024);
+    let (status, _, response) = send(&app, "POST", "/api/categories", &gzipped_json, compressed).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(error(&response)["status"], 413);
+
+    // Unknown encodings and content types are refused in the error envelope too
+    let unknown = [("Content-Type", "application/json"), ("Content-Encoding", "compress")];
+    let (status, _, response) = send(&app, "POST", "/api/categories", &unknown, b"{}".to_vec()).await;
+    assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_eq!(error(&response)["status"], 415);
+    let (status, _, response) = send(&app, "POST", "/api/categories", &[], b"{}".to_vec()).await;
+    assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert!(
+        error(&response)["message"]
+            .as_str()
+            .unwrap()
+            .contains("application/json")
+    );
+
+    // Without decompression, encoded bodies reach the handler untouched
+    config.compression.decompress_requests = false;
+    let app = create_test_app_with(&config, db.clone(), test_cache(), test_storage());
+    let body = json!({ "name": "Plain" }).to_string();
+    let (status, _, _) = send(&app, "POST", "/api/categories", &gzipped_json, gzip(body.as_bytes())).await;
+    assert!(
+        status.is_client_error() && status != StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        "{}",
+        status
+    );
+}
+
+#[tokio::test]
+async fn test_slow_requests_time_out() {
+    let app = Router::new()
+        .route(
+            "/slow",
+            get(|| async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                "done"
+            }),
+        )
+        .route("/fast", get(|| async { "done" }))
+        .layer(middleware::from_fn_with_state(
+            Duration::from_millis(50),
+            layers::enforce_timeout,
+        ));
+
+    let (status, _, body) = send(&app, "GET", "/slow", &[], vec![]).await;
+    assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+    assert_eq!(error(&body)["status"], 504);
+    let (status, _, _) = send(&app, "GET", "/fast", &[], vec![]).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_exhausted_database_pool_is_unavailable() {
+    initialize().await;
+    let mut options = isolated_database().await;
+    options
+        .max_connections(1)
+        .min_connections(1)
+        .acquire_timeout(Duration::from_millis(200));
+    let db = Database::connect(options).await.unwrap();
+    Migrator::new(db.clone()).up().await.unwrap();
+    let app = create_public_test_app(db.clone());
+
+    // Hold the only connection for the duration of the request
+    let transaction = db.begin().await.unwrap();
+    let (status, _, body) = send(&app, "GET", "/api/products", &[], vec![]).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(error(&body)["status"], 503);
+    transaction.rollback().await.unwrap();
+
+    let (status, _, _) = send(&app, "GET", "/api/products", &[], vec![]).await;
+    assert_eq!(status, StatusCode::OK);
+}
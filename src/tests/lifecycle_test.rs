@@ -0,0 +1,305 @@
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use chrono::{Duration, Utc};
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+use super::common::{
+    create_public_test_app, create_test_app, create_test_app_with, create_test_category, initialize, test_cache,
+    test_storage,
+};
+use crate::config::Config;
+use crate::models::api_key::CreateApiKeyRequest;
+use crate::repository::{ApiKeyRepository, ProductRepository};
+use crate::tenant::Tenant;
+
+/// Send a request with an optional JSON body and return the status and parsed JSON response
+async fn send(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    send_with_key(app, method, uri, None, body).await
+}
+
+/// Like [`send`], presenting `key` as a bearer token when given
+async fn send_with_key(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    key: Option<&str>,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Content-Type", "application/json");
+    if let Some(key) = key {
+        request = request.header("Authorization", format!("Bearer {}", key));
+    }
+    let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+
+    let response = app.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+    let status = response.status();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+async fn create_product(app: &Router, sku: &str, category_id: i32) -> i64 {
+    let body = json!({
+        "name": sku,
+        "price": "10.00",
+        "sku": sku,
+        "category_ids": [category_id],
+    });
+    let (status, product) = send(app, "POST", "/api/products", Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{}", product);
+    assert_eq!(product["status"], "draft");
+    product["id"].as_i64().unwrap()
+}
+
+/// Apply a transition as an editor and return the updated product
+async fn transition(app: &Router, id: i64, action: &str) -> (StatusCode, Value) {
+    send(app, "POST", &format!("/api/products/{}/{}", id, action), None).await
+}
+
+fn listed_skus(list: &Value) -> Vec<&str> {
+    let mut skus: Vec<&str> = list["products"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|product| product["sku"].as_str().unwrap())
+        .collect();
+    skus.sort();
+    skus
+}
+
+#[tokio::test]
+async fn test_anonymous_clients_only_see_published_products() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+    let public = create_public_test_app(db.clone());
+
+    let category = create_test_category(&app).await;
+    let live = create_product(&app, "LIVE-1", category.id).await;
+    let draft = create_product(&app, "DRAFT-1", category.id).await;
+    transition(&app, live, "submit").await;
+    transition(&app, live, "publish").await;
+
+    // Editors see everything and may filter by status
+    let (_, list) = send(&app, "GET", "/api/products", None).await;
+    assert_eq!(listed_skus(&list), vec!["DRAFT-1", "LIVE-1"]);
+    let (status, list) = send(&app, "GET", "/api/products?status=draft", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(listed_skus(&list), vec!["DRAFT-1"]);
+    let (status, _) = send(&app, "GET", "/api/products?status=live", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Anonymous clients only see the published product, whatever they ask for
+    for uri in ["/api/products", "/api/products?status=draft"] {
+        let (status, list) = send(&public, "GET", uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(listed_skus(&list), vec!["LIVE-1"], "{}", uri);
+        assert_eq!(list["total"], 1, "{}", uri);
+    }
+
+    let (status, product) = send(&public, "GET", &format!("/api/products/{}", live), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(product["status"], "published");
+    let (status, _) = send(&public, "GET", &format!("/api/products/{}", draft), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let uri = format!("/api/categories/{}/products", category.id);
+    let (_, products) = send(&public, "GET", &uri, None).await;
+    assert_eq!(products.as_array().unwrap().len(), 1);
+    let (_, products) = send(&app, "GET", &uri, None).await;
+    assert_eq!(products.as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_transitions_follow_the_workflow() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+
+    let category = create_test_category(&app).await;
+    let id = create_product(&app, "FLOW-1", category.id).await;
+
+    // Drafts must be reviewed before they are published
+    let (status, error) = transition(&app, id, "publish").await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert!(
+        error["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("Cannot publish a product that is draft"),
+        "{}",
+        error
+    );
+
+    let (status, product) = transition(&app, id, "submit").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(product["status"], "in_review");
+    let (_, product) = transition(&app, id, "reject").await;
+    assert_eq!(product["status"], "draft");
+
+    transition(&app, id, "submit").await;
+    let (status, product) = transition(&app, id, "publish").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(product["status"], "published");
+    assert!(product["published_at"].is_string());
+    assert_eq!(product["archived_at"], Value::Null);
+    let (status, _) = transition(&app, id, "submit").await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (_, product) = transition(&app, id, "archive").await;
+    assert_eq!(product["status"], "archived");
+    assert!(product["archived_at"].is_string());
+    let (status, _) = transition(&app, id, "archive").await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (_, product) = transition(&app, id, "restore").await;
+    assert_eq!(product["status"], "draft");
+    assert_eq!(product["archived_at"], Value::Null);
+    assert!(product["published_at"].is_string());
+
+    // Status can't be changed through a plain update
+    let uri = format!("/api/products/{}", id);
+    let (status, product) = send(&app, "PUT", &uri, Some(json!({ "status": "published" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(product["status"], "draft");
+
+    let (status, _) = transition(&app, 999999, "submit").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_editors_authenticate_with_api_keys() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+    let public = create_public_test_app(db.clone());
+
+    let category = create_test_category(&app).await;
+    let id = create_product(&app, "AUTH-1", category.id).await;
+    let submit = format!("/api/products/{}/submit", id);
+    let product = format!("/api/products/{}", id);
+
+    let (status, _) = send(&public, "POST", &submit, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = send_with_key(&public, "GET", &product, Some("pcat_unknown"), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let api_keys = ApiKeyRepository::new(db.clone());
+    let created = api_keys
+        .create_api_key(CreateApiKeyRequest {
+            name: "lifecycle test".to_string(),
+            tenant: Tenant::default(),
+        })
+        .await
+        .unwrap();
+    let key = Some(created.secret.as_str());
+
+    let (status, _) = send_with_key(&public, "GET", &product, key, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, submitted) = send_with_key(&public, "POST", &submit, key, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(submitted["status"], "in_review");
+
+    api_keys.revoke_api_key(created.api_key.id).await.unwrap();
+    let (status, _) = send_with_key(&public, "GET", &product, key, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_anonymous_callers_cannot_edit_the_catalog() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+    let public = create_public_test_app(db.clone());
+
+    let category = create_test_category(&app).await;
+    let id = create_product(&app, "ANON-1", category.id).await;
+    let product = format!("/api/products/{}", id);
+    let category = format!("/api/categories/{}", category.id);
+
+    let requests = [
+        ("POST", "/api/products".to_string(), json!({"name": "Anon", "price": "1.00", "category_ids": []})),
+        ("PUT", product.clone(), json!({})),
+        ("DELETE", product.clone(), Value::Null),
+        ("POST", "/api/categories".to_string(), json!({"name": "Anon"})),
+        ("PUT", category.clone(), json!({})),
+        ("DELETE", category.clone(), Value::Null),
+        ("POST", format!("{}/attributes", category), json!({"name": "size", "type": "text"})),
+        ("DELETE", format!("{}/attributes/1", category), Value::Null),
+        ("PUT", "/api/tags/1".to_string(), json!({"name": "anon"})),
+        ("POST", "/api/tags/1/merge".to_string(), json!({"target_id": 2})),
+        ("DELETE", format!("{}/media/1", product), Value::Null),
+        ("PUT", format!("{}/translations/fr", product), json!({"name": "Anon"})),
+        ("DELETE", format!("{}/translations/fr", product), Value::Null),
+        ("PUT", format!("{}/translations/fr", category), json!({"name": "Anon"})),
+        ("DELETE", format!("{}/translations/fr", category), Value::Null),
+    ];
+    for (method, uri, body) in requests {
+        let (status, _) = send(&public, method, &uri, Some(body)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED, "{} {}", method, uri);
+    }
+
+    // The draft is untouched and still hidden from anonymous callers
+    let (status, _) = send(&public, "GET", &product, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, draft) = send(&app, "GET", &product, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(draft["name"], "ANON-1");
+}
+
+#[tokio::test]
+async fn test_scheduled_publishing() {
+    let db = initialize().await;
+    let config = Config::default();
+    let cache = test_cache();
+    let app = create_test_app_with(&config, db.clone(), cache.clone(), test_storage());
+    let products = ProductRepository::new(db.clone(), cache, test_storage(), &config.facets);
+
+    let category = create_test_category(&app).await;
+    let id = create_product(&app, "SCHED-1", category.id).await;
+    let schedule = format!("/api/products/{}/schedule", id);
+    let uri = format!("/api/products/{}", id);
+
+    let now = Utc::now();
+    let publish_at = now + Duration::hours(1);
+    let unpublish_at = now + Duration::hours(2);
+
+    // Only reviewed products can be scheduled for publishing
+    let body = json!({ "publish_at": publish_at.to_rfc3339() });
+    let (status, _) = send(&app, "POST", &schedule, Some(body.clone())).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    transition(&app, id, "submit").await;
+    let backwards = json!({ "publish_at": unpublish_at.to_rfc3339(), "unpublish_at": publish_at.to_rfc3339() });
+    let (status, _) = send(&app, "POST", &schedule, Some(backwards)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let body = json!({ "publish_at": publish_at.to_rfc3339(), "unpublish_at": unpublish_at.to_rfc3339() });
+    let (status, product) = send(&app, "POST", &schedule, Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{}", product);
+    assert!(product["publish_at"].is_string());
+    assert!(product["unpublish_at"].is_string());
+
+    // Nothing is due yet
+    assert!(products.apply_due_schedules(now.into()).await.unwrap().is_empty());
+
+    let changed = products
+        .apply_due_schedules((publish_at + Duration::minutes(1)).into())
+        .await
+        .unwrap();
+    assert_eq!(changed, vec![id as i32]);
+    let (_, product) = send(&app, "GET", &uri, None).await;
+    assert_eq!(product["status"], "published");
+    assert_eq!(product["publish_at"], Value::Null);
+    assert!(product["unpublish_at"].is_string());
+
+    products
+        .apply_due_schedules((unpublish_at + Duration::minutes(1)).into())
+        .await
+        .unwrap();
+    let (_, product) = send(&app, "GET", &uri, None).await;
+    assert_eq!(product["status"], "archived");
+    assert_eq!(product["unpublish_at"], Value::Null);
+    assert!(product["archived_at"].is_string());
+}
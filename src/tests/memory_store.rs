@@ -0,0 +1,512 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, Utc};
+
+use crate::error::ApiError;
+use crate::locale::LocaleChain;
+use crate::models::attribute::AttributeValues;
+use crate::models::category::{
+    CategoryListResponse, CategoryQueryParams, CategoryResponse, CategoryWithProductsResponse, CreateCategoryRequest,
+    UpdateCategoryRequest,
+};
+use crate::models::lifecycle::{ProductLifecycle, ProductStatus, ScheduleRequest, Transition};
+use crate::models::money::Money;
+use crate::models::product::{
+    CategoryBrief, CreateProductRequest, ProductListResponse, ProductQueryParams, ProductResponse, UpdateProductRequest,
+};
+use crate::repository::attribute::check_attributes;
+use crate::repository::check_price;
+use crate::repository::store::{CategoryStore, ProductStore};
+use crate::repository::tag::normalize_tag;
+use crate::tenant::Tenant;
+
+/// In-memory stand-in for the product and category repositories
+///
+/// Follows the database's rules: tenants never see each other's rows, SKUs and category names are
+/// unique within a tenant, and products only link categories of their own tenant. Categories
+/// define no attributes, so any attribute value is rejected. Translations are not kept, and
+/// listing products by full-text search, attribute or facet fails.
+#[derive(Clone, Default)]
+pub struct MemoryCatalog {
+    rows: Arc<Mutex<Rows>>,
+    tenant: Tenant,
+}
+
+#[derive(Default)]
+struct Rows {
+    next_id: i32,
+    categories: BTreeMap<i32, CategoryRow>,
+    products: BTreeMap<i32, ProductRow>,
+}
+
+struct CategoryRow {
+    tenant: Tenant,
+    name: String,
+    description: Option<String>,
+    created_at: DateTime<FixedOffset>,
+    updated_at: DateTime<FixedOffset>,
+}
+
+struct ProductRow {
+    tenant: Tenant,
+    name: String,
+    description: Option<String>,
+    price: Money,
+    sku: Option<String>,
+    lifecycle: ProductLifecycle,
+    category_ids: Vec<i32>,
+    tags: Vec<String>,
+    created_at: DateTime<FixedOffset>,
+    updated_at: DateTime<FixedOffset>,
+}
+
+impl MemoryCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn rows(&self) -> MutexGuard<'_, Rows> {
+        self.rows.lock().unwrap()
+    }
+}
+
+impl Rows {
+    fn next_id(&mut self) -> i32 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    fn category(&self, tenant: &Tenant, id: i32) -> Result<&CategoryRow, ApiError> {
+        self.categories
+            .get(&id)
+            .filter(|category| category.tenant == *tenant)
+            .ok_or_else(|| ApiError::not_found_simple("Category not found"))
+    }
+
+    fn product(&self, tenant: &Tenant, id: i32) -> Result<&ProductRow, ApiError> {
+        self.products
+            .get(&id)
+            .filter(|product| product.tenant == *tenant)
+            .ok_or_else(|| ApiError::not_found_simple("Product not found"))
+    }
+
+    fn product_mut(&mut self, tenant: &Tenant, id: i32) -> Result<&mut ProductRow, ApiError> {
+        self.products
+            .get_mut(&id)
+            .filter(|product| product.tenant == *tenant)
+            .ok_or_else(|| ApiError::not_found_simple("Product not found"))
+    }
+
+    /// Fail like the `(tenant_id, sku)` unique index
+    fn check_sku(&self, tenant: &Tenant, id: Option<i32>, sku: Option<&str>) -> Result<(), ApiError> {
+        let taken = self.products.iter().any(|(other_id, product)| {
+            Some(*other_id) != id && product.tenant == *tenant && sku.is_some() && product.sku.as_deref() == sku
+        });
+        if taken {
+            return Err(ApiError::Conflict(format!(
+                "Key (tenant_id, sku)=({}, {}) already exists",
+                tenant,
+                sku.unwrap_or_default()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Fail like the `(tenant_id, name)` unique index
+    fn check_category_name(&self, tenant: &Tenant, id: Option<i32>, name: &str) -> Result<(), ApiError> {
+        let taken = self
+            .categories
+            .iter()
+            .any(|(other_id, category)| Some(*other_id) != id && category.tenant == *tenant && category.name == name);
+        if taken {
+            return Err(ApiError::Conflict(format!(
+                "Key (tenant_id, name)=({}, {}) already exists",
+                tenant, name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Fail like the product_categories foreign key, which includes the tenant
+    fn check_categories(&self, tenant: &Tenant, category_ids: &[i32]) -> Result<(), ApiError> {
+        if category_ids.iter().any(|id| self.category(tenant, *id).is_err()) {
+            return Err(ApiError::bad_request("category_ids: every category must exist"));
+        }
+        Ok(())
+    }
+
+    fn product_response(&self, id: i32, product: &ProductRow) -> ProductResponse {
+        ProductResponse {
+            id,
+            name: product.name.clone(),
+            description: product.description.clone(),
+            price: product.price,
+            sku: product.sku.clone(),
+            lifecycle: product.lifecycle.clone(),
+            categories: product
+                .category_ids
+                .iter()
+                .filter_map(|id| {
+                    self.categories.get(id).map(|category| CategoryBrief {
+                        id: *id,
+                        name: category.name.clone(),
+                    })
+                })
+                .collect(),
+            attributes: AttributeValues::new(),
+            tags: product.tags.clone(),
+            media: Vec::new(),
+            created_at: product.created_at,
+            updated_at: product.updated_at,
+        }
+    }
+}
+
+fn category_response(id: i32, category: &CategoryRow) -> CategoryResponse {
+    CategoryResponse {
+        id,
+        name: category.name.clone(),
+        description: category.description.clone(),
+        created_at: category.created_at,
+        updated_at: category.updated_at,
+    }
+}
+
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut tags: Vec<String> = tags.iter().map(|tag| normalize_tag(tag)).collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+#[async_trait]
+impl ProductStore for MemoryCatalog {
+    fn for_tenant(&self, tenant: &Tenant) -> Arc<dyn ProductStore> {
+        Arc::new(Self {
+            rows: self.rows.clone(),
+            tenant: tenant.clone(),
+        })
+    }
+
+    fn for_locale(&self, _locales: &LocaleChain) -> Arc<dyn ProductStore> {
+        Arc::new(self.clone())
+    }
+
+    async fn list_products(&self, params: ProductQueryParams) -> Result<ProductListResponse, ApiError> {
+        let searching = params.q.as_deref().is_some_and(|q| !q.trim().is_empty());
+        if searching || !params.attributes.is_empty() || params.facets.is_some() {
+            return Err(ApiError::bad_request(
+                "Search, attribute filters and facets need a database",
+            ));
+        }
+
+        let rows = self.rows();
+        let matching: Vec<(&i32, &ProductRow)> = rows
+            .products
+            .iter()
+            .filter(|(_, product)| product.tenant == self.tenant)
+            .filter(|(_, product)| match (params.include_unpublished, params.status) {
+                (false, _) => product.lifecycle.status == ProductStatus::Published,
+                (true, Some(status)) => product.lifecycle.status == status,
+                (true, None) => true,
+            })
+            .filter(|(_, product)| {
+                params
+                    .category_id
+                    .is_none_or(|category_id| product.category_ids.contains(&category_id))
+            })
+            .filter(|(_, product)| params.tags.iter().all(|tag| product.tags.contains(&normalize_tag(tag))))
+            .collect();
+
+        Ok(ProductListResponse {
+            total: matching.len() as i64,
+            products: matching
+                .into_iter()
+                .skip(params.offset() as usize)
+                .take(params.page_size() as usize)
+                .map(|(id, product)| rows.product_response(*id, product))
+                .collect(),
+            page: params.page(),
+            page_size: params.page_size(),
+            facets: None,
+        })
+    }
+
+    async fn get_product(&self, id: i32) -> Result<ProductResponse, ApiError> {
+        let rows = self.rows();
+        let product = rows.product(&self.tenant, id)?;
+        Ok(rows.product_response(id, product))
+    }
+
+    async fn create_product(&self, req: CreateProductRequest) -> Result<ProductResponse, ApiError> {
+        check_price(req.price)?;
+        let mut rows = self.rows();
+        rows.check_sku(&self.tenant, None, req.sku.as_deref())?;
+        rows.check_categories(&self.tenant, &req.category_ids)?;
+        check_attributes(&[], &req.attributes)?;
+
+        let now: DateTime<FixedOffset> = Utc::now().into();
+        let id = rows.next_id();
+        let product = ProductRow {
+            tenant: self.tenant.clone(),
+            name: req.name,
+            description: req.description,
+            price: req.price,
+            sku: req.sku,
+            lifecycle: ProductLifecycle {
+                status: ProductStatus::Draft,
+                published_at: None,
+                archived_at: None,
+                publish_at: None,
+                unpublish_at: None,
+            },
+            category_ids: req.category_ids,
+            tags: normalize_tags(&req.tags),
+            created_at: now,
+            updated_at: now,
+        };
+        let response = rows.product_response(id, &product);
+        rows.products.insert(id, product);
+
+        Ok(response)
+    }
+
+    async fn update_product(&self, id: i32, req: UpdateProductRequest) -> Result<ProductResponse, ApiError> {
+        let mut rows = self.rows();
+        rows.product(&self.tenant, id)?;
+        if let Some(sku) = &req.sku {
+            rows.check_sku(&self.tenant, Some(id), Some(sku))?;
+        }
+        if let Some(category_ids) = &req.category_ids {
+            rows.check_categories(&self.tenant, category_ids)?;
+        }
+        if let Some(attributes) = &req.attributes {
+            check_attributes(&[], attributes)?;
+        }
+
+        let product = rows.product_mut(&self.tenant, id)?;
+        if let Some(name) = req.name {
+            product.name = name;
+        }
+        if let Some(description) = req.description {
+            product.description = Some(description);
+        }
+        product.price = Money::price(
+            req.price.unwrap_or(product.price.amount()),
+            req.currency.unwrap_or(product.price.currency()),
+        )
+        .map_err(ApiError::Validation)?;
+        if let Some(sku) = req.sku {
+            product.sku = Some(sku);
+        }
+        if let Some(category_ids) = req.category_ids {
+            product.category_ids = category_ids;
+        }
+        if let Some(tags) = req.tags {
+            product.tags = normalize_tags(&tags);
+        }
+        product.updated_at = Utc::now().into();
+
+        let product = rows.product(&self.tenant, id)?;
+        Ok(rows.product_response(id, product))
+    }
+
+    async fn delete_product(&self, id: i32) -> Result<(), ApiError> {
+        let mut rows = self.rows();
+        rows.product(&self.tenant, id)?;
+        rows.products.remove(&id);
+        Ok(())
+    }
+
+    async fn transition_product(&self, id: i32, transition: Transition) -> Result<ProductResponse, ApiError> {
+        let mut rows = self.rows();
+        let product = rows.product_mut(&self.tenant, id)?;
+
+        let status = product.lifecycle.status;
+        if !transition.sources().contains(&status) {
+            let allowed: Vec<&str> = transition.sources().iter().map(|s| s.as_str()).collect();
+            return Err(ApiError::Conflict(format!(
+                "Cannot {} a product that is {}; allowed from {}",
+                transition.as_str(),
+                status,
+                allowed.join(", ")
+            )));
+        }
+
+        let now: DateTime<FixedOffset> = Utc::now().into();
+        let lifecycle = &mut product.lifecycle;
+        lifecycle.status = transition.target();
+        match transition {
+            Transition::Submit => {}
+            Transition::Reject => lifecycle.publish_at = None,
+            Transition::Publish => {
+                lifecycle.published_at = Some(now);
+                lifecycle.publish_at = None;
+            }
+            Transition::Archive => {
+                lifecycle.archived_at = Some(now);
+                lifecycle.publish_at = None;
+                lifecycle.unpublish_at = None;
+            }
+            Transition::Restore => lifecycle.archived_at = None,
+        }
+        product.updated_at = now;
+
+        let product = rows.product(&self.tenant, id)?;
+        Ok(rows.product_response(id, product))
+    }
+
+    async fn schedule_product(&self, id: i32, req: ScheduleRequest) -> Result<ProductResponse, ApiError> {
+        if let (Some(publish_at), Some(unpublish_at)) = (req.publish_at, req.unpublish_at)
+            && unpublish_at <= publish_at
+        {
+            return Err(ApiError::bad_request("unpublish_at: must be after publish_at"));
+        }
+
+        let mut rows = self.rows();
+        let product = rows.product_mut(&self.tenant, id)?;
+
+        let status = product.lifecycle.status;
+        if req.publish_at.is_some() && status != ProductStatus::InReview {
+            return Err(ApiError::Conflict(format!(
+                "Cannot schedule publishing of a product that is {}; submit it for review first",
+                status
+            )));
+        }
+        if req.unpublish_at.is_some() && !matches!(status, ProductStatus::InReview | ProductStatus::Published) {
+            return Err(ApiError::Conflict(format!(
+                "Cannot schedule unpublishing of a product that is {}",
+                status
+            )));
+        }
+
+        product.lifecycle.publish_at = req.publish_at;
+        product.lifecycle.unpublish_at = req.unpublish_at;
+        product.updated_at = Utc::now().into();
+
+        let product = rows.product(&self.tenant, id)?;
+        Ok(rows.product_response(id, product))
+    }
+}
+
+#[async_trait]
+impl CategoryStore for MemoryCatalog {
+    fn for_tenant(&self, tenant: &Tenant) -> Arc<dyn CategoryStore> {
+        Arc::new(Self {
+            rows: self.rows.clone(),
+            tenant: tenant.clone(),
+        })
+    }
+
+    fn for_locale(&self, _locales: &LocaleChain) -> Arc<dyn CategoryStore> {
+        Arc::new(self.clone())
+    }
+
+    async fn list_categories(
+        &self,
+        params: CategoryQueryParams,
+        include_unpublished: bool,
+    ) -> Result<CategoryListResponse, ApiError> {
+        let rows = self.rows();
+        let mut categories: Vec<CategoryWithProductsResponse> = rows
+            .categories
+            .iter()
+            .filter(|(_, category)| category.tenant == self.tenant)
+            .map(|(id, category)| {
+                let product_count = if params.include_product_count() {
+                    rows.products
+                        .values()
+                        .filter(|product| product.category_ids.contains(id))
+                        .filter(|product| include_unpublished || product.lifecycle.status == ProductStatus::Published)
+                        .count() as i64
+                } else {
+                    0
+                };
+                CategoryWithProductsResponse {
+                    id: *id,
+                    name: category.name.clone(),
+                    description: category.description.clone(),
+                    product_count: Some(product_count),
+                    created_at: category.created_at,
+                    updated_at: category.updated_at,
+                }
+            })
+            .collect();
+        categories.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(CategoryListResponse { categories })
+    }
+
+    async fn get_category(&self, id: i32) -> Result<CategoryResponse, ApiError> {
+        let rows = self.rows();
+        Ok(category_response(id, rows.category(&self.tenant, id)?))
+    }
+
+    async fn create_category(&self, req: CreateCategoryRequest) -> Result<CategoryResponse, ApiError> {
+        let mut rows = self.rows();
+        rows.check_category_name(&self.tenant, None, &req.name)?;
+
+        let now: DateTime<FixedOffset> = Utc::now().into();
+        let id = rows.next_id();
+        let category = CategoryRow {
+            tenant: self.tenant.clone(),
+            name: req.name,
+            description: req.description,
+            created_at: now,
+            updated_at: now,
+        };
+        let response = category_response(id, &category);
+        rows.categories.insert(id, category);
+
+        Ok(response)
+    }
+
+    async fn update_category(&self, id: i32, req: UpdateCategoryRequest) -> Result<CategoryResponse, ApiError> {
+        let mut rows = self.rows();
+        rows.category(&self.tenant, id)?;
+        if let Some(name) = &req.name {
+            rows.check_category_name(&self.tenant, Some(id), name)?;
+        }
+
+        let category = rows.categories.get_mut(&id).expect("category was just found");
+        if let Some(name) = req.name {
+            category.name = name;
+        }
+        if let Some(description) = req.description {
+            category.description = Some(description);
+        }
+        category.updated_at = Utc::now().into();
+
+        Ok(category_response(id, category))
+    }
+
+    async fn delete_category(&self, id: i32) -> Result<(), ApiError> {
+        let mut rows = self.rows();
+        rows.category(&self.tenant, id)?;
+
+        rows.categories.remove(&id);
+        for product in rows.products.values_mut() {
+            product.category_ids.retain(|category_id| *category_id != id);
+        }
+        Ok(())
+    }
+
+    async fn get_products_by_category(
+        &self,
+        category_id: i32,
+        include_unpublished: bool,
+    ) -> Result<Vec<ProductResponse>, ApiError> {
+        let rows = self.rows();
+        rows.category(&self.tenant, category_id)?;
+
+        Ok(rows
+            .products
+            .iter()
+            .filter(|(_, product)| product.category_ids.contains(&category_id))
+            .filter(|(_, product)| include_unpublished || product.lifecycle.status == ProductStatus::Published)
+            .map(|(id, product)| rows.product_response(*id, product))
+            .collect())
+    }
+}
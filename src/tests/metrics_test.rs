@@ -0,0 +1,57 @@
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use tower::ServiceExt;
+
+use super::common::{create_test_app, create_test_category, create_test_product, initialize};
+use crate::metrics;
+
+#[tokio::test]
+async fn test_metrics_endpoint() {
+    // Initialize test environment
+    let pool = initialize().await;
+    let app = create_test_app(pool.clone());
+
+    // Create test data and generate some traffic, including an error
+    let category = create_test_category(&app).await;
+    create_test_product(&app, vec![category.id]).await;
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/api/products/9999")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // Count the catalog as the sampler would, then scrape the metrics
+    metrics::record_catalog_size(&pool).await.unwrap();
+    let response = app
+        .clone()
+        .oneshot(Request::builder().method("GET").uri("/metrics").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(
+        response.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain")
+    );
+
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+
+    assert!(body.contains(r#"http_requests_total{method="GET",route="/api/products/:id",status="404"}"#));
+    assert!(body.contains(r#"http_request_duration_seconds_bucket{method="POST",route="/api/products""#));
+    assert!(body.contains(r#"api_errors_total{kind="not_found"}"#));
+    assert!(body.contains(r#"db_transaction_duration_seconds_count{operation="create_product"}"#));
+    assert!(body.contains(r#"db_pool_connections{state="max"} 5"#));
+    assert!(body.contains(r#"catalog_items{kind="products"} 1"#));
+    assert!(body.contains(r#"catalog_items{kind="categories"} 1"#));
+}
@@ -0,0 +1,200 @@
+use std::path::PathBuf;
+use std::{env, fs};
+
+use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection};
+use uuid::Uuid;
+
+use super::common::initialize;
+use crate::migration::{self, Migration, MigrationState, Migrator};
+
+const CREATE_WIDGETS: Migration = Migration {
+    version: 1,
+    name: "create_widgets",
+    up: "CREATE TABLE widgets (id INTEGER PRIMARY KEY, name VARCHAR NOT NULL);",
+    down: Some("DROP TABLE widgets;"),
+};
+
+const ADD_WIDGET_COLOR: Migration = Migration {
+    version: 2,
+    name: "add_widget_color",
+    up: "ALTER TABLE widgets ADD COLUMN color VARCHAR; CREATE INDEX widgets_color_idx ON widgets (color);",
+    down: Some("DROP INDEX widgets_color_idx; ALTER TABLE widgets DROP COLUMN color;"),
+};
+
+/// A database created for one test, removed again by `drop_isolated`
+enum Isolated {
+    Schema(String),
+    Directory(PathBuf),
+}
+
+/// Connect to a fresh, empty database so migrations run in isolation from the test database
+///
+/// On Postgres this is a new schema in the test database. On SQLite it's a database file rather
+/// than an in-memory database, whose shared cache fails on lock conflicts instead of waiting.
+async fn isolated_database() -> (DatabaseConnection, Isolated) {
+    let admin = initialize().await;
+    let Ok(url) = env::var("TEST_DATABASE_URL") else {
+        let dir = env::temp_dir().join(format!("migration-test-{}", Uuid::new_v4()));
+        fs::create_dir(&dir).unwrap();
+        let url = format!("sqlite://{}?mode=rwc", dir.join("catalog.db").display());
+        let mut opt = ConnectOptions::new(url);
+        opt.max_connections(4);
+        return (Database::connect(opt).await.unwrap(), Isolated::Directory(dir));
+    };
+
+    let schema = format!("migration_test_{}", Uuid::new_v4().simple());
+    admin
+        .execute_unprepared(&format!("CREATE SCHEMA {}", schema))
+        .await
+        .unwrap();
+
+    let mut opt = ConnectOptions::new(&url);
+    opt.max_connections(4).set_schema_search_path(schema.clone());
+    (Database::connect(opt).await.unwrap(), Isolated::Schema(schema))
+}
+
+async fn drop_isolated(db: DatabaseConnection, isolated: Isolated) {
+    db.close().await.unwrap();
+    match isolated {
+        Isolated::Schema(schema) => {
+            let admin = initialize().await;
+            admin
+                .execute_unprepared(&format!("DROP SCHEMA {} CASCADE", schema))
+                .await
+                .unwrap();
+        }
+        Isolated::Directory(dir) => fs::remove_dir_all(dir).unwrap(),
+    }
+}
+
+fn states(statuses: &[migration::MigrationStatus]) -> Vec<(i64, MigrationState)> {
+    statuses.iter().map(|status| (status.version, status.state)).collect()
+}
+
+#[tokio::test]
+async fn test_migrate_up_status_and_down() {
+    let (db, isolated) = isolated_database().await;
+    let migrator = Migrator::with_migrations(db.clone(), vec![ADD_WIDGET_COLOR, CREATE_WIDGETS]);
+
+    // Everything starts pending, and verification refuses to pass
+    let statuses = migrator.status().await.unwrap();
+    assert_eq!(
+        states(&statuses),
+        vec![(1, MigrationState::Pending), (2, MigrationState::Pending)]
+    );
+    assert!(migrator.verify().await.is_err());
+
+    // Migrations apply in version order, and re-running is a no-op
+    assert_eq!(migrator.up().await.unwrap(), vec![1, 2]);
+    assert!(migrator.up().await.unwrap().is_empty());
+    migrator.verify().await.unwrap();
+    db.execute_unprepared("INSERT INTO widgets (id, name, color) VALUES (1, 'gear', 'red')")
+        .await
+        .unwrap();
+
+    // Down reverts the most recent migration only
+    assert_eq!(migrator.down(1).await.unwrap(), vec![2]);
+    let statuses = migrator.status().await.unwrap();
+    assert_eq!(
+        states(&statuses),
+        vec![(1, MigrationState::Applied), (2, MigrationState::Pending)]
+    );
+    assert!(statuses[0].applied_at.is_some());
+    assert!(db.execute_unprepared("SELECT color FROM widgets").await.is_err());
+
+    assert_eq!(migrator.down(5).await.unwrap(), vec![1]);
+    assert!(migrator.down(1).await.unwrap().is_empty());
+
+    drop_isolated(db, isolated).await;
+}
+
+#[tokio::test]
+async fn test_migrate_detects_modified_and_missing_migrations() {
+    let (db, isolated) = isolated_database().await;
+    Migrator::with_migrations(db.clone(), vec![CREATE_WIDGETS, ADD_WIDGET_COLOR])
+        .up()
+        .await
+        .unwrap();
+
+    // Editing an applied script is detected by its checksum
+    let edited = Migration {
+        up: "CREATE TABLE widgets (id BIGINT PRIMARY KEY);",
+        ..CREATE_WIDGETS
+    };
+    let migrator = Migrator::with_migrations(db.clone(), vec![edited]);
+    let statuses = migrator.status().await.unwrap();
+    assert_eq!(
+        states(&statuses),
+        vec![(1, MigrationState::Modified), (2, MigrationState::Missing)]
+    );
+    let error = migrator.up().await.unwrap_err().to_string();
+    assert!(error.contains("1_create_widgets"), "{}", error);
+    assert!(migrator.verify().await.is_err());
+
+    drop_isolated(db, isolated).await;
+}
+
+#[tokio::test]
+async fn test_migrate_failed_migration_is_rolled_back() {
+    let (db, isolated) = isolated_database().await;
+    let broken = Migration {
+        version: 3,
+        name: "broken",
+        up: "ALTER TABLE widgets ADD COLUMN weight INTEGER; SELECT * FROM no_such_table;",
+        down: None,
+    };
+    let migrator = Migrator::with_migrations(db.clone(), vec![CREATE_WIDGETS, broken]);
+
+    let error = migrator.up().await.unwrap_err().to_string();
+    assert!(error.contains("3_broken"), "{}", error);
+
+    // The earlier migration stays applied, the failed one left nothing behind
+    let statuses = migrator.status().await.unwrap();
+    assert_eq!(
+        states(&statuses),
+        vec![(1, MigrationState::Applied), (3, MigrationState::Pending)]
+    );
+    assert!(db.execute_unprepared("SELECT weight FROM widgets").await.is_err());
+
+    // Reverting past a migration without a down script is refused
+    let irreversible = Migrator::with_migrations(
+        db.clone(),
+        vec![Migration {
+            down: None,
+            ..CREATE_WIDGETS
+        }],
+    );
+    assert!(irreversible.down(1).await.is_err());
+
+    drop_isolated(db, isolated).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_concurrent_migrations_apply_once() {
+    let (db, isolated) = isolated_database().await;
+    let first = Migrator::with_migrations(db.clone(), vec![CREATE_WIDGETS, ADD_WIDGET_COLOR]);
+    let second = Migrator::with_migrations(db.clone(), vec![CREATE_WIDGETS, ADD_WIDGET_COLOR]);
+
+    let (first, second) = tokio::join!(first.up(), second.up());
+    let mut applied = [first.unwrap(), second.unwrap()].concat();
+    applied.sort();
+
+    assert_eq!(applied, vec![1, 2]);
+
+    drop_isolated(db, isolated).await;
+}
+
+#[test]
+fn test_new_migration_files() {
+    let dir = std::env::temp_dir().join(format!("migrations-{}", Uuid::new_v4()));
+
+    let (up, down) = migration::create_migration_files(&dir, "Add product SKU index").unwrap();
+
+    let up_name = up.file_name().unwrap().to_string_lossy().to_string();
+    assert!(up_name.ends_with("_add_product_sku_index.up.sql"), "{}", up_name);
+    assert_eq!(up_name.split('_').next().unwrap().len(), 14);
+    assert!(down.to_string_lossy().ends_with("_add_product_sku_index.down.sql"));
+    assert!(migration::create_migration_files(&dir, "  !!  ").is_err());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -1,3 +1,27 @@
+mod attribute_api_test;
+mod cache_test;
 mod category_api_test;
+mod cli_test;
 mod common;
+mod config_test;
+mod error_test;
+mod facet_test;
+mod fixtures;
+mod handler_test;
+mod health_test;
+mod idempotency_test;
+mod layers_test;
+mod lifecycle_test;
+mod media_test;
+mod memory_store;
+mod metrics_test;
+mod migration_test;
 mod product_api_test;
+mod property_test;
+mod rate_limit_test;
+mod revision_test;
+mod shutdown_test;
+mod tag_api_test;
+mod telemetry_test;
+mod tenant_test;
+mod translation_test;
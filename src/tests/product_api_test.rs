@@ -1,73 +1,31 @@
-use std::str::FromStr;
-
-use axum::body::Body;
-use axum::http::{Request, StatusCode};
-use bigdecimal::BigDecimal;
-use tower::ServiceExt;
-
-// Import from common module
-use super::common::{cleanup_test_data, create_test_app, create_test_category, create_test_product, initialize};
-// Import from crate root using the lib.rs exports
-use crate::{
-    entity::{
-        Category, CategoryActiveModel, CategoryModel, Product, ProductActiveModel, ProductCategory,
-        ProductCategoryModel, ProductModel,
-    },
-    models::{
-        category::{CategoryResponse, CreateCategoryRequest},
-        product::{CategoryBrief, CreateProductRequest, ProductListResponse, ProductResponse, UpdateProductRequest},
-    },
-};
+use axum::http::StatusCode;
+use serde_json::{Value, json};
+
+use super::common::{TestApp, initialize};
+use super::fixtures::{CategoryFixture, ProductFixture};
+use crate::models::product::{ProductListResponse, ProductResponse, UpdateProductRequest};
 
 #[tokio::test]
 async fn test_list_products() {
-    // Initialize test environment
-    let pool = initialize().await;
-    let app = create_test_app(pool.clone());
-
-    // Create test data
-    let category = create_test_category(&app).await;
-    let product1 = create_test_product(&app, vec![category.id]).await;
-
-    // Create a second product for pagination testing
-    let request_body = CreateProductRequest {
-        name: "Second Test Product".to_string(),
-        description: Some("Another test product".to_string()),
-        price: BigDecimal::from_str("29.99").unwrap(),
-        category_ids: vec![category.id],
-        sku: Some("TEST-SKU-456".to_string()),
-    };
-
-    let _ = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("POST")
-                .uri("/api/products")
-                .header("Content-Type", "application/json")
-                .body(Body::from(serde_json::to_string(&request_body).unwrap()))
-                .unwrap(),
-        )
-        .await
-        .unwrap();
+    let app = TestApp::new(initialize().await);
+
+    // Create two products for pagination testing
+    let category = CategoryFixture::new().create(&app).await;
+    ProductFixture::new()
+        .sku("TEST-SKU-123")
+        .in_categories(&[category.id])
+        .create(&app)
+        .await;
+    ProductFixture::new()
+        .name("Second Test Product")
+        .price("29.99")
+        .sku("TEST-SKU-456")
+        .in_categories(&[category.id])
+        .create(&app)
+        .await;
 
     // Test list products with default parameters
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("GET")
-                .uri("/api/products")
-                .body(Body::empty())
-                .unwrap(),
-        )
-        .await
-        .unwrap();
-
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
-    let products: ProductListResponse = serde_json::from_slice(&body).unwrap();
+    let products: ProductListResponse = app.get("/api/products").await.ok();
 
     assert_eq!(products.total, 2);
     assert_eq!(products.products.len(), 2);
@@ -75,410 +33,347 @@ async fn test_list_products() {
     assert!(products.products.iter().any(|p| p.name == "Second Test Product"));
 
     // Test pagination - page 1, limit 1
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("GET")
-                .uri("/api/products?page=1&page_size=1")
-                .body(Body::empty())
-                .unwrap(),
-        )
-        .await
-        .unwrap();
-
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
-    let products: ProductListResponse = serde_json::from_slice(&body).unwrap();
+    let products: ProductListResponse = app.get("/api/products?page=1&page_size=1").await.ok();
 
     assert_eq!(products.total, 2);
     assert_eq!(products.products.len(), 1);
 
-    // Clean up test data
-    cleanup_test_data(&pool).await;
+    // Page sizes outside 1 to 100 are clamped rather than rejected
+    let products: ProductListResponse = app.get("/api/products?page_size=0").await.ok();
+    assert_eq!(products.page_size, 1);
+    assert_eq!(products.products.len(), 1);
+    let products: ProductListResponse = app.get("/api/products?page_size=1000").await.ok();
+    assert_eq!(products.page_size, 100);
+    assert_eq!(products.products.len(), 2);
 }
 
 #[tokio::test]
 async fn test_get_product() {
-    // Initialize test environment
-    let pool = initialize().await;
-    let app = create_test_app(pool.clone());
+    let app = TestApp::new(initialize().await);
 
-    // Create test data
-    let category = create_test_category(&app).await;
-    let product = create_test_product(&app, vec![category.id]).await;
+    let category = CategoryFixture::new().create(&app).await;
+    let product = ProductFixture::new().in_categories(&[category.id]).create(&app).await;
 
     // Test get product
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("GET")
-                .uri(&format!("/api/products/{}", product.id))
-                .body(Body::empty())
-                .unwrap(),
-        )
-        .await
-        .unwrap();
-
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
-    let response_product: ProductResponse = serde_json::from_slice(&body).unwrap();
+    let response_product: ProductResponse = app.get(&format!("/api/products/{}", product.id)).await.ok();
 
     assert_eq!(response_product.id, product.id);
     assert_eq!(response_product.name, "Test Product");
     assert!(response_product.categories.iter().any(|c| c.id == category.id));
 
     // Test get non-existent product
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("GET")
-                .uri("/api/products/9999")
-                .body(Body::empty())
-                .unwrap(),
-        )
-        .await
-        .unwrap();
-
-    assert_eq!(response.status(), StatusCode::NOT_FOUND);
-
-    // Clean up test data
-    cleanup_test_data(&pool).await;
+    let response = app.get("/api/products/9999").await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
 async fn test_create_product() {
-    // Initialize test environment
-    let pool = initialize().await;
-    let app = create_test_app(pool.clone());
+    let app = TestApp::new(initialize().await);
 
-    // Create test category
-    let category = create_test_category(&app).await;
+    let category = CategoryFixture::new().create(&app).await;
 
     // Test create product with valid data
-    let request_body = CreateProductRequest {
-        name: "New Product".to_string(),
-        description: Some("A brand new product".to_string()),
-        price: BigDecimal::from_str("39.99").unwrap(),
-        category_ids: vec![category.id],
-        sku: Some("NEW-SKU-789".to_string()),
-    };
-
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("POST")
-                .uri("/api/products")
-                .header("Content-Type", "application/json")
-                .body(Body::from(serde_json::to_string(&request_body).unwrap()))
-                .unwrap(),
-        )
-        .await
-        .unwrap();
-
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
-    let product: ProductResponse = serde_json::from_slice(&body).unwrap();
+    let product = ProductFixture::new()
+        .name("New Product")
+        .description("A brand new product")
+        .price("39.99")
+        .sku("NEW-SKU-789")
+        .in_categories(&[category.id])
+        .create(&app)
+        .await;
 
     assert_eq!(product.name, "New Product");
     assert_eq!(product.description, Some("A brand new product".to_string()));
 
     // Test create product with invalid data (empty name)
-    let invalid_body = CreateProductRequest {
-        name: "".to_string(), // Empty name, should fail validation
-        description: Some("Invalid product".to_string()),
-        price: BigDecimal::from_str("9.99").unwrap(),
-        category_ids: vec![category.id],
-        sku: Some("INV-SKU".to_string()),
-    };
-
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("POST")
-                .uri("/api/products")
-                .header("Content-Type", "application/json")
-                .body(Body::from(serde_json::to_string(&invalid_body).unwrap()))
-                .unwrap(),
-        )
-        .await
-        .unwrap();
-
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let invalid = ProductFixture::new()
+        .name("")
+        .sku("INV-SKU")
+        .in_categories(&[category.id])
+        .request();
+    let response = app.post("/api/products", &invalid).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
 
     // Test create product with non-existent category
-    let invalid_category_body = CreateProductRequest {
-        name: "Invalid Category Product".to_string(),
-        description: Some("A product with invalid category".to_string()),
-        price: BigDecimal::from_str("19.99").unwrap(),
-        category_ids: vec![9999], // Non-existent category
-        sku: Some("IC-SKU".to_string()),
-    };
-
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("POST")
-                .uri("/api/products")
-                .header("Content-Type", "application/json")
-                .body(Body::from(serde_json::to_string(&invalid_category_body).unwrap()))
-                .unwrap(),
-        )
-        .await
-        .unwrap();
-
-    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
-
-    // Clean up test data
-    cleanup_test_data(&pool).await;
+    let invalid_category = ProductFixture::new().sku("IC-SKU").in_categories(&[9999]).request();
+    let response = app.post("/api/products", &invalid_category).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
 }
 
 #[tokio::test]
 async fn test_update_product() {
-    // Initialize test environment
-    let pool = initialize().await;
-    let app = create_test_app(pool.clone());
+    let app = TestApp::new(initialize().await);
 
-    // Create test data
-    let category = create_test_category(&app).await;
-    let product = create_test_product(&app, vec![category.id]).await;
+    let category = CategoryFixture::new().create(&app).await;
+    let product = ProductFixture::new().in_categories(&[category.id]).create(&app).await;
 
     // Test update product
     let update_body = UpdateProductRequest {
         name: Some("Updated Product".to_string()),
         description: Some("Updated description".to_string()),
-        price: Some(BigDecimal::from_str("49.99").unwrap()),
+        price: Some("49.99".parse().unwrap()),
+        currency: None,
         category_ids: Some(vec![category.id]),
         sku: Some("UPD-SKU-123".to_string()),
+        attributes: None,
+        tags: None,
     };
 
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("PUT")
-                .uri(&format!("/api/products/{}", product.id))
-                .header("Content-Type", "application/json")
-                .body(Body::from(serde_json::to_string(&update_body).unwrap()))
-                .unwrap(),
-        )
+    let updated_product: ProductResponse = app
+        .put(&format!("/api/products/{}", product.id), &update_body)
         .await
-        .unwrap();
-
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
-    let updated_product: ProductResponse = serde_json::from_slice(&body).unwrap();
+        .ok();
 
     assert_eq!(updated_product.id, product.id);
     assert_eq!(updated_product.name, "Updated Product");
     assert_eq!(updated_product.description, Some("Updated description".to_string()));
 
     // Test update non-existent product
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("PUT")
-                .uri("/api/products/9999")
-                .header("Content-Type", "application/json")
-                .body(Body::from(serde_json::to_string(&update_body).unwrap()))
-                .unwrap(),
-        )
-        .await
-        .unwrap();
-
-    assert_eq!(response.status(), StatusCode::NOT_FOUND);
-
-    // Clean up test data
-    cleanup_test_data(&pool).await;
+    let response = app.put("/api/products/9999", &update_body).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
 async fn test_delete_product() {
-    // Initialize test environment
-    let pool = initialize().await;
-    let app = create_test_app(pool.clone());
+    let app = TestApp::new(initialize().await);
 
-    // Create test data
-    let category = create_test_category(&app).await;
-    let product = create_test_product(&app, vec![category.id]).await;
+    let category = CategoryFixture::new().create(&app).await;
+    let product = ProductFixture::new().in_categories(&[category.id]).create(&app).await;
+    let uri = format!("/api/products/{}", product.id);
 
     // Test delete product
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("DELETE")
-                .uri(&format!("/api/products/{}", product.id))
-                .body(Body::empty())
-                .unwrap(),
-        )
-        .await
-        .unwrap();
-
-    assert_eq!(response.status(), StatusCode::OK);
+    let response = app.delete(&uri).await;
+    assert_eq!(response.status, StatusCode::OK);
 
     // Verify product was deleted by trying to get it
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("GET")
-                .uri(&format!("/api/products/{}", product.id))
-                .body(Body::empty())
-                .unwrap(),
-        )
-        .await
-        .unwrap();
-
-    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = app.get(&uri).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
 
     // Test delete non-existent product
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("DELETE")
-                .uri("/api/products/9999")
-                .body(Body::empty())
-                .unwrap(),
-        )
-        .await
-        .unwrap();
-
-    assert_eq!(response.status(), StatusCode::NOT_FOUND);
-
-    // Clean up test data
-    cleanup_test_data(&pool).await;
+    let response = app.delete("/api/products/9999").await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
 async fn test_product_category_many_to_many() {
-    // Initialize test environment
-    let pool = initialize().await;
-    let app = create_test_app(pool.clone());
+    let app = TestApp::new(initialize().await);
 
-    // Create test categories
-    let category1 = create_test_category(&app).await;
-
-    // Create a second category
-    let category2_request = CreateCategoryRequest {
-        name: "Second Category".to_string(),
-        description: Some("Another test category".to_string()),
-    };
-
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("POST")
-                .uri("/api/categories")
-                .header("Content-Type", "application/json")
-                .body(Body::from(serde_json::to_string(&category2_request).unwrap()))
-                .unwrap(),
-        )
-        .await
-        .unwrap();
-
-    assert_eq!(response.status(), StatusCode::OK);
-    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
-    let category2: CategoryResponse = serde_json::from_slice(&body).unwrap();
+    let category1 = CategoryFixture::new().create(&app).await;
+    let category2 = CategoryFixture::new().name("Second Category").create(&app).await;
 
     // Create a product with multiple categories
-    let product_request = CreateProductRequest {
-        name: "Multi-Category Product".to_string(),
-        description: Some("A product with multiple categories".to_string()),
-        price: BigDecimal::from_str("39.99").unwrap(),
-        category_ids: vec![category1.id, category2.id],
-        sku: Some("MULTI-CAT-001".to_string()),
-    };
-
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("POST")
-                .uri("/api/products")
-                .header("Content-Type", "application/json")
-                .body(Body::from(serde_json::to_string(&product_request).unwrap()))
-                .unwrap(),
-        )
-        .await
-        .unwrap();
-
-    assert_eq!(response.status(), StatusCode::OK);
-    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
-    let product: ProductResponse = serde_json::from_slice(&body).unwrap();
+    let product = ProductFixture::new()
+        .name("Multi-Category Product")
+        .sku("MULTI-CAT-001")
+        .in_categories(&[category1.id, category2.id])
+        .create(&app)
+        .await;
 
     // Verify product has both categories
     assert_eq!(product.categories.len(), 2);
     assert!(product.categories.iter().any(|c| c.id == category1.id));
     assert!(product.categories.iter().any(|c| c.id == category2.id));
 
-    // Create a third category
-    let category3_request = CreateCategoryRequest {
-        name: "Third Category".to_string(),
-        description: Some("Yet another test category".to_string()),
-    };
-
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("POST")
-                .uri("/api/categories")
-                .header("Content-Type", "application/json")
-                .body(Body::from(serde_json::to_string(&category3_request).unwrap()))
-                .unwrap(),
-        )
-        .await
-        .unwrap();
-
-    assert_eq!(response.status(), StatusCode::OK);
-    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
-    let category3: CategoryResponse = serde_json::from_slice(&body).unwrap();
+    let category3 = CategoryFixture::new().name("Third Category").create(&app).await;
 
     // Update the product to add the third category and remove the first one
     let update_request = UpdateProductRequest {
         name: None,
         description: None,
         price: None,
+        currency: None,
         category_ids: Some(vec![category2.id, category3.id]),
         sku: None,
+        attributes: None,
+        tags: None,
     };
 
-    let response = app
-        .clone()
-        .oneshot(
-            Request::builder()
-                .method("PUT")
-                .uri(&format!("/api/products/{}", product.id))
-                .header("Content-Type", "application/json")
-                .body(Body::from(serde_json::to_string(&update_request).unwrap()))
-                .unwrap(),
-        )
+    let updated_product: ProductResponse = app
+        .put(&format!("/api/products/{}", product.id), &update_request)
         .await
-        .unwrap();
-
-    assert_eq!(response.status(), StatusCode::OK);
-    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
-    let updated_product: ProductResponse = serde_json::from_slice(&body).unwrap();
+        .ok();
 
     // Verify product has been updated with new categories
     assert_eq!(updated_product.categories.len(), 2);
     assert!(!updated_product.categories.iter().any(|c| c.id == category1.id));
     assert!(updated_product.categories.iter().any(|c| c.id == category2.id));
     assert!(updated_product.categories.iter().any(|c| c.id == category3.id));
+}
 
-    // Clean up test data
-    cleanup_test_data(&pool).await;
+#[tokio::test]
+async fn test_fixtures_build_priced_and_tagged_products() {
+    let app = TestApp::new(initialize().await);
+
+    let lighting = CategoryFixture::new().name("Lighting").create(&app).await;
+    let outdoor = CategoryFixture::new().name("Outdoor").create(&app).await;
+    let product = ProductFixture::new()
+        .name("Garden Lamp")
+        .price("12.50")
+        .in_categories(&[lighting.id, outdoor.id])
+        .tags(&["Solar", "garden"])
+        .create(&app)
+        .await;
+
+    assert_eq!(product.price.to_string(), "12.50 USD");
+    assert_eq!(product.categories.len(), 2);
+    assert_eq!(product.tags, vec!["garden", "solar"]);
+
+    // Fixtures without a SKU never collide, unlike ones sharing one
+    ProductFixture::new().in_categories(&[lighting.id]).create(&app).await;
+    let duplicate = ProductFixture::new().sku("LAMP-1").in_categories(&[lighting.id]);
+    ProductFixture::new()
+        .sku("LAMP-1")
+        .in_categories(&[lighting.id])
+        .create(&app)
+        .await;
+    let response = app.post("/api/products", &duplicate.request()).await;
+    assert_eq!(response.status, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_listed_products_match_fetched_ones() {
+    let app = TestApp::new(initialize().await);
+
+    let lighting = CategoryFixture::new().name("Lighting").create(&app).await;
+    let outdoor = CategoryFixture::new().name("Outdoor").create(&app).await;
+    for category in [lighting.id, outdoor.id] {
+        let attribute = json!({ "name": "color", "type": "enum", "values": ["red", "blue"] });
+        let response = app
+            .post(&format!("/api/categories/{}/attributes", category), &attribute)
+            .await;
+        assert_eq!(response.status, StatusCode::OK);
+    }
+
+    // Related rows are loaded for the whole page at once, so each must end up on its own product
+    let products = [
+        ProductFixture::new()
+            .name("Desk Lamp")
+            .in_categories(&[lighting.id])
+            .attribute("color", json!("red"))
+            .tags(&["desk"]),
+        ProductFixture::new()
+            .name("Garden Lamp")
+            .in_categories(&[lighting.id, outdoor.id])
+            .attribute("color", json!("blue"))
+            .tags(&["garden", "solar"]),
+        ProductFixture::new().name("Bench").in_categories(&[outdoor.id]),
+    ];
+    for product in products {
+        product.create(&app).await;
+    }
+
+    let by_category_id = |product: &mut Value| {
+        let categories = product["categories"].as_array_mut().unwrap();
+        categories.sort_by_key(|category| category["id"].as_i64());
+    };
+    for uri in [
+        "/api/products".to_string(),
+        format!("/api/categories/{}/products", outdoor.id),
+    ] {
+        let list: Value = app.get(&uri).await.ok();
+        let listed = match list.get("products") {
+            Some(products) => products.as_array().unwrap().clone(),
+            None => list.as_array().unwrap().clone(),
+        };
+        assert!(!listed.is_empty());
+        for mut product in listed {
+            let mut fetched: Value = app.get(&format!("/api/products/{}", product["id"])).await.ok();
+            by_category_id(&mut product);
+            by_category_id(&mut fetched);
+            assert_eq!(product, fetched);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_prices_are_exact_and_range_checked() {
+    let app = TestApp::new(initialize().await);
+
+    let category = CategoryFixture::new().create(&app).await;
+    let body = |price: Value| json!({ "name": "Lamp", "price": price, "category_ids": [category.id] });
+
+    // JSON numbers keep their decimal value instead of going through a float
+    let product: Value = app.post("/api/products", &body(json!(19.99))).await.ok();
+    assert_eq!(product["price"], "19.99");
+    assert_eq!(product["currency"], "USD");
+    let product: Value = app.post("/api/products", &body(json!("1.5e1"))).await.ok();
+    assert_eq!(product["price"], "15.00");
+    let product: Value = app.post("/api/products", &body(json!("99999999.99"))).await.ok();
+    assert_eq!(product["price"], "99999999.99");
+
+    // Prices the column can't hold exactly are rejected rather than rounded or truncated, and so
+    // are prices that aren't positive
+    for price in [
+        json!("0"),
+        json!(-5),
+        json!("100000000"),
+        json!("-100000000"),
+        json!(1e40),
+        json!("19.999"),
+        json!(0.001),
+        json!("1e-3"),
+        json!("cheap"),
+    ] {
+        let response = app.post("/api/products", &body(price.clone())).await;
+        assert_eq!(response.status, StatusCode::UNPROCESSABLE_ENTITY, "price {}", price);
+    }
+    let response = app
+        .put(
+            &format!("/api/products/{}", product["id"]),
+            &json!({ "price": "0.005" }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::UNPROCESSABLE_ENTITY);
+    let response = app
+        .put(
+            &format!("/api/products/{}", product["id"]),
+            &json!({ "price": "-1.00" }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    // Trailing zeros beyond two places don't change the amount
+    let response: ProductResponse = app
+        .put(
+            &format!("/api/products/{}", product["id"]),
+            &json!({ "price": "12.3000" }),
+        )
+        .await
+        .ok();
+    assert_eq!(response.price.to_string(), "12.30 USD");
+}
+
+#[tokio::test]
+async fn test_product_currency() {
+    let app = TestApp::new(initialize().await);
+
+    let category = CategoryFixture::new().create(&app).await;
+    let product = ProductFixture::new()
+        .price("15.00")
+        .currency("eur")
+        .in_categories(&[category.id])
+        .create(&app)
+        .await;
+    assert_eq!(product.price.to_string(), "15.00 EUR");
+
+    let uri = format!("/api/products/{}", product.id);
+    let fetched: Value = app.get(&uri).await.ok();
+    assert_eq!(fetched["price"], "15.00");
+    assert_eq!(fetched["currency"], "EUR");
+
+    // Either half of the price can change on its own
+    let updated: ProductResponse = app.put(&uri, &json!({ "currency": "GBP" })).await.ok();
+    assert_eq!(updated.price.to_string(), "15.00 GBP");
+    let updated: ProductResponse = app.put(&uri, &json!({ "price": "12.00" })).await.ok();
+    assert_eq!(updated.price.to_string(), "12.00 GBP");
+
+    for currency in ["EURO", "E1R", ""] {
+        let response = app.put(&uri, &json!({ "currency": currency })).await;
+        assert_eq!(
+            response.status,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "currency {:?}",
+            currency
+        );
+    }
 }
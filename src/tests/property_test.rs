@@ -0,0 +1,341 @@
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::Json;
+use axum::body::Body;
+use axum::extract::{FromRequest, Path, Query, State};
+use axum::http::{HeaderMap, Request, StatusCode, Uri, header};
+use axum::response::IntoResponse;
+use bigdecimal::{BigDecimal, Signed};
+use proptest::prelude::*;
+use proptest::test_runner::{Config, TestRunner};
+use sea_orm::prelude::Decimal;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+use super::common::{TestApp, initialize};
+use super::fixtures::{CategoryFixture, ProductFixture};
+use super::memory_store::MemoryCatalog;
+use crate::api::product;
+use crate::auth::Editor;
+use crate::error::ApiError;
+use crate::locale::LocaleChain;
+use crate::models::category::CreateCategoryRequest;
+use crate::models::product::{CreateProductRequest, ProductQueryParams, ProductResponse, UpdateProductRequest};
+use crate::repository::money_from_columns;
+use crate::repository::store::{CategoryStore, ProductStore};
+use crate::request_context::RequestContext;
+use crate::tenant::Tenant;
+
+fn ctx() -> RequestContext {
+    RequestContext::from_headers(&HeaderMap::new())
+}
+
+fn editor() -> Editor {
+    Editor {
+        api_key_id: 1,
+        name: "test editor".to_string(),
+        tenant: Tenant::default(),
+    }
+}
+
+fn status<T>(result: Result<T, ApiError>) -> StatusCode {
+    match result {
+        Ok(_) => StatusCode::OK,
+        Err(error) => error.into_response().status(),
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+/// Extract `body` the way the `Json` extractor of a handler would
+async fn extract_json<T: DeserializeOwned>(body: &Value) -> Result<T, StatusCode> {
+    let request = Request::builder()
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(serde_json::to_vec(body).unwrap()))
+        .unwrap();
+    match Json::<T>::from_request(request, &()).await {
+        Ok(Json(value)) => Ok(value),
+        Err(rejection) => Err(rejection.into_response().status()),
+    }
+}
+
+/// A catalog holding one category, and its ID
+async fn catalog_with_category() -> (MemoryCatalog, i32) {
+    let catalog = MemoryCatalog::new();
+    let request = CreateCategoryRequest {
+        name: "Shoes".to_string(),
+        description: None,
+    };
+    let category = CategoryStore::create_category(&catalog, request).await.unwrap();
+    (catalog, category.id)
+}
+
+/// Prices the `price` column holds: positive, with two decimal places and at most ten digits
+fn column_price() -> impl Strategy<Value = String> {
+    (1u64..=9_999_999_999).prop_map(|cents| format!("{}.{:02}", cents / 100, cents % 100))
+}
+
+/// Price values as clients send them, valid or not
+fn any_price() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        "-?[0-9]{0,12}(\\.[0-9]{0,4})?".prop_map(Value::from),
+        "[0-9a-z.e+-]{0,8}".prop_map(Value::from),
+        (-1e6..1e6f64).prop_map(Value::from),
+        any::<i64>().prop_map(Value::from),
+    ]
+}
+
+/// Names around the 255 character limit, including empty and multi-byte ones
+fn any_name() -> impl Strategy<Value = String> {
+    prop_oneof![".{0,8}", "[a-zé]{250,260}"]
+}
+
+fn any_sku() -> impl Strategy<Value = String> {
+    "[A-Z0-9-]{0,55}"
+}
+
+/// Tags around the 50 character limit, before trimming
+fn any_tags() -> impl Strategy<Value = Vec<String>> {
+    prop::collection::vec(" ?[a-z]{0,52} ?", 0..4)
+}
+
+/// Category IDs, where `None` stands for the catalog's category and anything else is missing
+fn any_categories() -> impl Strategy<Value = Vec<Option<i32>>> {
+    prop::collection::vec(prop::option::of(1000..2000), 0..3)
+}
+
+fn category_ids(categories: &[Option<i32>], existing: i32) -> Vec<i32> {
+    categories.iter().map(|id| id.unwrap_or(existing)).collect()
+}
+
+fn valid_name(name: &str) -> bool {
+    (1..=255).contains(&name.chars().count())
+}
+
+/// Prices are positive, below 10^8 and have at most two decimal places; numbers are read by their
+/// shortest decimal form
+fn valid_price(price: &Value) -> bool {
+    let price = match price {
+        Value::String(price) => BigDecimal::from_str(price),
+        Value::Number(price) => BigDecimal::from_str(&price.to_string()),
+        _ => return false,
+    };
+    price.is_ok_and(|price| price.is_positive() && price < 100_000_000 && price.with_scale(2) == price)
+}
+
+fn valid_sku(sku: &str) -> bool {
+    sku.chars().count() <= 50
+}
+
+fn valid_categories(categories: &[Option<i32>]) -> bool {
+    !categories.is_empty() && categories.iter().all(Option::is_none)
+}
+
+fn valid_tags(tags: &[String]) -> bool {
+    tags.iter().all(|tag| (1..=50).contains(&tag.trim().chars().count()))
+}
+
+proptest! {
+    #[test]
+    fn test_pagination_stays_in_bounds(page in any::<i64>(), page_size in any::<i64>()) {
+        let uri: Uri = format!("/api/products?page={}&page_size={}", page, page_size).parse().unwrap();
+        let Query(params) = Query::<ProductQueryParams>::try_from_uri(&uri).unwrap();
+
+        prop_assert!(params.page() >= 1);
+        prop_assert!((1..=100).contains(&params.page_size()));
+        prop_assert!(params.offset() >= 0);
+        if page >= 1 && (1..=100).contains(&page_size) {
+            prop_assert_eq!(params.page(), page);
+            prop_assert_eq!(params.page_size(), page_size);
+        }
+    }
+
+    #[test]
+    fn test_query_strings_are_listed_or_rejected(
+        pairs in prop::collection::vec(
+            (
+                prop_oneof![
+                    Just("page".to_string()),
+                    Just("page_size".to_string()),
+                    Just("category_id".to_string()),
+                    Just("status".to_string()),
+                    Just("tag".to_string()),
+                    "[a-z_.]{1,8}",
+                ],
+                prop_oneof!["-?[0-9]{1,20}", "[a-z0-9%+]{0,6}", Just("published".to_string())],
+            ),
+            0..5,
+        )
+    ) {
+        let query: Vec<String> = pairs.iter().map(|(key, value)| format!("{}={}", key, value)).collect();
+        let uri: Uri = format!("/api/products?{}", query.join("&")).parse().unwrap();
+
+        let params = match Query::<ProductQueryParams>::try_from_uri(&uri) {
+            Ok(Query(params)) => params,
+            Err(rejection) => {
+                prop_assert_eq!(rejection.into_response().status(), StatusCode::BAD_REQUEST);
+                return Ok(());
+            }
+        };
+        let pairs = Query::<Vec<(String, String)>>::try_from_uri(&uri).unwrap();
+
+        let catalog = MemoryCatalog::new();
+        let result = block_on(product::list_products(
+            ctx(),
+            Tenant::default(),
+            LocaleChain::default(),
+            Some(editor()),
+            State(Arc::new(catalog) as Arc<dyn ProductStore>),
+            Query(params),
+            pairs,
+        ));
+        match result {
+            Ok(Json(list)) => {
+                prop_assert!(list.page >= 1);
+                prop_assert!((1..=100).contains(&list.page_size));
+            }
+            Err(error) => prop_assert_eq!(error.into_response().status(), StatusCode::BAD_REQUEST),
+        }
+    }
+
+    #[test]
+    fn test_create_requests_are_created_only_when_valid(
+        name in any_name(),
+        price in any_price(),
+        sku in prop::option::of(any_sku()),
+        categories in any_categories(),
+        tags in any_tags(),
+    ) {
+        let valid = valid_name(&name)
+            && valid_price(&price)
+            && sku.as_deref().is_none_or(valid_sku)
+            && valid_categories(&categories)
+            && valid_tags(&tags);
+
+        let status = block_on(async {
+            let (catalog, category_id) = catalog_with_category().await;
+            let body = json!({
+                "name": name,
+                "price": price,
+                "sku": sku,
+                "category_ids": category_ids(&categories, category_id),
+                "tags": tags,
+            });
+            match extract_json::<CreateProductRequest>(&body).await {
+                Ok(request) => status(
+                    product::create_product(ctx(), Tenant::default(), editor(), State(Arc::new(catalog)), Json(request)).await,
+                ),
+                Err(status) => status,
+            }
+        });
+
+        if valid {
+            prop_assert_eq!(status, StatusCode::OK);
+        } else {
+            prop_assert!(status.is_client_error(), "invalid request answered with {}", status);
+        }
+    }
+
+    #[test]
+    fn test_update_requests_are_applied_only_when_valid(
+        name in prop::option::of(any_name()),
+        price in prop::option::of(any_price()),
+        sku in prop::option::of(any_sku()),
+        categories in prop::option::of(any_categories()),
+        tags in prop::option::of(any_tags()),
+    ) {
+        let valid = name.as_deref().is_none_or(valid_name)
+            && price.as_ref().is_none_or(valid_price)
+            && sku.as_deref().is_none_or(valid_sku)
+            && categories.as_deref().is_none_or(valid_categories)
+            && tags.as_deref().is_none_or(valid_tags);
+
+        let status = block_on(async {
+            let (catalog, category_id) = catalog_with_category().await;
+            let created = ProductStore::create_product(
+                &catalog,
+                CreateProductRequest {
+                    name: "Boot".to_string(),
+                    description: None,
+                    price: "10 USD".parse().unwrap(),
+                    sku: None,
+                    category_ids: vec![category_id],
+                    attributes: Default::default(),
+                    tags: Vec::new(),
+                },
+            )
+            .await
+            .unwrap();
+
+            let body = json!({
+                "name": name,
+                "price": price,
+                "sku": sku,
+                "category_ids": categories.map(|categories| category_ids(&categories, category_id)),
+                "tags": tags,
+            });
+            match extract_json::<UpdateProductRequest>(&body).await {
+                Ok(request) => status(
+                    product::update_product(
+                        ctx(),
+                        Tenant::default(),
+                        editor(),
+                        State(Arc::new(catalog)),
+                        Path(created.id),
+                        Json(request),
+                    )
+                    .await,
+                ),
+                Err(status) => status,
+            }
+        });
+
+        if valid {
+            prop_assert_eq!(status, StatusCode::OK);
+        } else {
+            prop_assert!(status.is_client_error(), "invalid request answered with {}", status);
+        }
+    }
+
+    #[test]
+    fn test_column_prices_convert_without_loss(price in column_price()) {
+        let column = Decimal::from_str(&price).unwrap();
+        prop_assert_eq!(money_from_columns(column, "USD").unwrap().amount().to_string(), price);
+    }
+}
+
+#[test]
+fn test_prices_round_trip_through_the_database() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let (app, category_id) = runtime.block_on(async {
+        let app = TestApp::new(initialize().await);
+        let category = CategoryFixture::new().create(&app).await;
+        (app, category.id)
+    });
+
+    let mut runner = TestRunner::new(Config::with_cases(32));
+    runner
+        .run(&column_price(), |price| {
+            runtime.block_on(async {
+                let created = ProductFixture::new()
+                    .price(&price)
+                    .in_categories(&[category_id])
+                    .create(&app)
+                    .await;
+                prop_assert_eq!(created.price.amount().to_string(), price.clone());
+
+                let fetched: ProductResponse = app.get(&format!("/api/products/{}", created.id)).await.ok();
+                prop_assert_eq!(fetched.price.amount().to_string(), price);
+                Ok(())
+            })
+        })
+        .unwrap();
+}
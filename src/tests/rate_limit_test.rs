@@ -0,0 +1,194 @@
+use std::sync::Arc;
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{HeaderMap, Request, StatusCode, header};
+use serde_json::Value;
+use tower::ServiceExt;
+
+use super::common::{create_public_test_app_with, create_public_test_app_with_rate_limiter, initialize};
+use crate::config::Config;
+use crate::models::api_key::CreateApiKeyRequest;
+use crate::rate_limit::{MemoryRateLimitStore, RateLimiter};
+use crate::repository::ApiKeyRepository;
+use crate::tenant::Tenant;
+
+/// Configuration allowing three reads and one write per minute, trusting `X-Forwarded-For`
+fn limited_config() -> Config {
+    let mut config = Config::default();
+    config.rate_limit.read_limit = 3;
+    config.rate_limit.write_limit = 1;
+    config.rate_limit.window_secs = 60;
+    config.rate_limit.trust_forwarded_for = true;
+    config
+}
+
+/// Send a request from `client_ip`, optionally with an API key, returning the status, headers
+/// and parsed JSON response
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    client_ip: &str,
+    key: Option<&str>,
+) -> (StatusCode, HeaderMap, Value) {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("X-Forwarded-For", format!("{}, 10.0.0.1", client_ip));
+    if let Some(key) = key {
+        request = request.header(header::AUTHORIZATION, format!("Bearer {}", key));
+    }
+
+    let response = app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+    let status = response.status();
+    let headers = response.headers().clone();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+    (status, headers, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+fn header_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).map(|value| value.to_str().unwrap())
+}
+
+#[tokio::test]
+async fn test_clients_get_separate_read_and_write_budgets() {
+    let db = initialize().await;
+    let app = create_public_test_app_with(&limited_config(), db.clone());
+
+    for remaining in ["2", "1", "0"] {
+        let (status, headers, _) = send(&app, "GET", "/api/products", "203.0.113.1", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(header_value(&headers, "ratelimit-limit"), Some("3"));
+        assert_eq!(header_value(&headers, "ratelimit-remaining"), Some(remaining));
+        assert_eq!(header_value(&headers, "ratelimit-policy"), Some("3;w=60"));
+        assert!(header_value(&headers, "ratelimit-reset").is_some());
+    }
+
+    // Over budget: a 429 in the usual error envelope, saying when to retry
+    let (status, headers, body) = send(&app, "GET", "/api/products", "203.0.113.1", None).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(body["error"]["status"], 429);
+    assert!(
+        body["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("3 requests per 60s")
+    );
+    let retry_after: u64 = header_value(&headers, "retry-after").unwrap().parse().unwrap();
+    assert!((1..=20).contains(&retry_after), "{}", retry_after);
+    assert_eq!(header_value(&headers, "ratelimit-remaining"), Some("0"));
+
+    // Writes have a budget of their own; the first is let through to the handler, which rejects it
+    let (status, headers, _) = send(&app, "POST", "/api/products", "203.0.113.1", None).await;
+    assert!(
+        status.is_client_error() && status != StatusCode::TOO_MANY_REQUESTS,
+        "{}",
+        status
+    );
+    assert_eq!(header_value(&headers, "ratelimit-limit"), Some("1"));
+    let (status, _, _) = send(&app, "POST", "/api/products", "203.0.113.1", None).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+    // Other clients are unaffected, and health checks are never limited
+    let (status, headers, _) = send(&app, "GET", "/api/products", "203.0.113.2", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(header_value(&headers, "ratelimit-remaining"), Some("2"));
+    let (status, headers, _) = send(&app, "GET", "/health/live", "203.0.113.1", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(header_value(&headers, "ratelimit-limit").is_none());
+}
+
+#[tokio::test]
+async fn test_editors_are_limited_by_api_key() {
+    let db = initialize().await;
+    let app = create_public_test_app_with(&limited_config(), db.clone());
+
+    let api_keys = ApiKeyRepository::new(db.clone());
+    let created = api_keys
+        .create_api_key(CreateApiKeyRequest {
+            name: "rate limit test".to_string(),
+            tenant: Tenant::default(),
+        })
+        .await
+        .unwrap();
+    let key = Some(created.secret.as_str());
+
+    for _ in 0..3 {
+        let (status, _, _) = send(&app, "GET", "/api/products", "198.51.100.1", None).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let (status, _, _) = send(&app, "GET", "/api/products", "198.51.100.1", None).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+    // The key has its own budget wherever it is used from
+    for client_ip in ["198.51.100.1", "198.51.100.2", "198.51.100.3"] {
+        let (status, _, _) = send(&app, "GET", "/api/products", client_ip, key).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let (status, _, _) = send(&app, "GET", "/api/products", "198.51.100.4", key).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+    api_keys.revoke_api_key(created.api_key.id).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_key_guessing_is_limited_by_client_address() {
+    let db = initialize().await;
+    let mut config = limited_config();
+    config.rate_limit.read_limit = 100;
+    config.rate_limit.auth_limit = 2;
+    let app = create_public_test_app_with(&config, db.clone());
+
+    // Unknown keys are rejected until the address has used up its authentication budget
+    for _ in 0..2 {
+        let (status, _, _) = send(&app, "GET", "/api/products", "192.0.2.1", Some("pcat_guess")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+    let (status, headers, _) = send(&app, "GET", "/api/products", "192.0.2.1", Some("pcat_guess")).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(header_value(&headers, "ratelimit-limit"), Some("2"));
+    assert!(header_value(&headers, "retry-after").is_some());
+
+    // Anonymous requests and other addresses keep their own budgets
+    let (status, _, _) = send(&app, "GET", "/api/products", "192.0.2.1", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _, _) = send(&app, "GET", "/api/products", "192.0.2.2", Some("pcat_guess")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_budgets_are_shared_through_the_store() {
+    let db = initialize().await;
+    let config = limited_config();
+    let store = Arc::new(MemoryRateLimitStore::new(config.rate_limit.capacity));
+    let first = create_public_test_app_with_rate_limiter(
+        &config,
+        db.clone(),
+        RateLimiter::new(store.clone(), &config.rate_limit),
+    );
+    let second =
+        create_public_test_app_with_rate_limiter(&config, db.clone(), RateLimiter::new(store, &config.rate_limit));
+
+    for app in [&first, &second, &first] {
+        let (status, _, _) = send(app, "GET", "/api/products", "192.0.2.1", None).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    let (status, _, _) = send(&second, "GET", "/api/products", "192.0.2.1", None).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn test_rate_limiting_can_be_disabled() {
+    let db = initialize().await;
+    let mut config = limited_config();
+    config.rate_limit.enabled = false;
+    let app = create_public_test_app_with(&config, db.clone());
+
+    for _ in 0..5 {
+        let (status, headers, _) = send(&app, "GET", "/api/products", "192.0.2.2", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(header_value(&headers, "ratelimit-limit").is_none());
+    }
+}
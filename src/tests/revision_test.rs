@@ -0,0 +1,253 @@
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use sea_orm::prelude::Decimal;
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+use super::common::{create_public_test_app, create_test_app, initialize, test_cache, test_storage};
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::models::revision::CreateRevisionRequest;
+use crate::repository::ProductRepository;
+
+/// Send a request with an optional JSON body and return the status and parsed JSON response
+async fn send(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Content-Type", "application/json");
+    let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+
+    let response = app.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+    let status = response.status();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+/// Create two categories and a product in the first, returning their IDs
+async fn create_catalog(app: &Router) -> (i64, i64, i64) {
+    let mut ids = Vec::new();
+    for name in ["Lamps", "Lighting"] {
+        let (status, category) = send(app, "POST", "/api/categories", Some(json!({ "name": name }))).await;
+        assert_eq!(status, StatusCode::OK);
+        ids.push(category["id"].as_i64().unwrap());
+    }
+
+    let body = json!({
+        "name": "Desk Lamp",
+        "description": "Adjustable arm",
+        "price": "20.00",
+        "category_ids": [ids[0]],
+    });
+    let (status, product) = send(app, "POST", "/api/products", Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{}", product);
+
+    (product["id"].as_i64().unwrap(), ids[0], ids[1])
+}
+
+fn numbers(revisions: &Value) -> Vec<(i64, &str, bool)> {
+    revisions
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|revision| {
+            (
+                revision["number"].as_i64().unwrap(),
+                revision["status"].as_str().unwrap(),
+                revision["live"].as_bool().unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_updates_are_recorded_as_revisions() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+
+    let (id, lamps, lighting) = create_catalog(&app).await;
+    let uri = format!("/api/products/{}", id);
+    let revisions = format!("/api/products/{}/revisions", id);
+
+    let (status, list) = send(&app, "GET", &revisions, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(numbers(&list), vec![(1, "published", true)]);
+    assert_eq!(list[0]["name"], "Desk Lamp");
+    assert_eq!(list[0]["category_ids"], json!([lamps]));
+
+    send(
+        &app,
+        "PUT",
+        &uri,
+        Some(json!({ "price": "25.00", "category_ids": [lighting, lamps] })),
+    )
+    .await;
+    // Changes outside the revisioned content don't add a revision
+    send(&app, "PUT", &uri, Some(json!({ "tags": ["desk"] }))).await;
+
+    let (_, list) = send(&app, "GET", &revisions, None).await;
+    assert_eq!(numbers(&list), vec![(2, "published", true), (1, "published", false)]);
+
+    let (status, diff) = send(&app, "GET", &format!("{}/diff?from=1&to=2", revisions), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        diff,
+        json!({
+            "from": 1,
+            "to": 2,
+            "changes": [
+                { "field": "price", "from": "20.00", "to": "25.00" },
+                { "field": "category_ids", "from": [lamps], "to": [lamps, lighting] },
+            ]
+        })
+    );
+
+    let (status, _) = send(&app, "GET", &format!("{}/diff?from=1&to=9", revisions), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    send(&app, "PUT", &uri, Some(json!({ "currency": "eur" }))).await;
+    let (_, diff) = send(&app, "GET", &format!("{}/diff?from=2&to=3", revisions), None).await;
+    assert_eq!(
+        diff["changes"],
+        json!([{ "field": "currency", "from": "USD", "to": "EUR" }])
+    );
+}
+
+#[tokio::test]
+async fn test_publish_draft_revision() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+
+    let (id, _, lighting) = create_catalog(&app).await;
+    let uri = format!("/api/products/{}", id);
+    let revisions = format!("/api/products/{}/revisions", id);
+
+    let body = json!({ "name": "Desk Lamp Pro", "category_ids": [lighting], "note": "Rebrand" });
+    let (status, draft) = send(&app, "POST", &revisions, Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{}", draft);
+    assert_eq!(draft["number"], 2);
+    assert_eq!(draft["status"], "draft");
+    assert_eq!(draft["base_number"], 1);
+    assert_eq!(draft["live"], false);
+    assert_eq!(draft["description"], "Adjustable arm");
+    assert_eq!(draft["note"], "Rebrand");
+
+    // The live product is untouched until the draft is published
+    let (_, product) = send(&app, "GET", &uri, None).await;
+    assert_eq!(product["name"], "Desk Lamp");
+
+    let (status, product) = send(&app, "POST", &format!("{}/2/publish", revisions), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", product);
+    assert_eq!(product["name"], "Desk Lamp Pro");
+    assert_eq!(product["categories"][0]["id"], lighting);
+    let (_, product) = send(&app, "GET", &uri, None).await;
+    assert_eq!(product["name"], "Desk Lamp Pro");
+
+    let (_, list) = send(&app, "GET", &revisions, None).await;
+    assert_eq!(numbers(&list), vec![(2, "published", true), (1, "published", false)]);
+    assert!(list[0]["published_at"].is_string());
+
+    let (status, _) = send(&app, "POST", &format!("{}/2/publish", revisions), None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // A draft whose base is no longer live would overwrite newer edits
+    let (_, stale) = send(&app, "POST", &revisions, Some(json!({ "price": "30.00" }))).await;
+    send(&app, "PUT", &uri, Some(json!({ "description": "Now dimmable" }))).await;
+    let (status, error) = send(
+        &app,
+        "POST",
+        &format!("{}/{}/publish", revisions, stale["number"]),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert!(
+        error["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("create a new draft"),
+        "{}",
+        error
+    );
+
+    let (status, _) = send(&app, "POST", &revisions, Some(json!({ "category_ids": [999999] }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(&app, "POST", &revisions, Some(json!({ "name": "" }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_revert_to_earlier_revision() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+
+    let (id, lamps, _) = create_catalog(&app).await;
+    let uri = format!("/api/products/{}", id);
+    let revisions = format!("/api/products/{}/revisions", id);
+
+    send(&app, "PUT", &uri, Some(json!({ "name": "Bad Edit", "price": "1.00" }))).await;
+    let (_, product) = send(&app, "GET", &uri, None).await;
+    assert_eq!(product["name"], "Bad Edit");
+
+    let (status, product) = send(&app, "POST", &format!("{}/1/revert", revisions), None).await;
+    assert_eq!(status, StatusCode::OK, "{}", product);
+    assert_eq!(product["name"], "Desk Lamp");
+    assert_eq!(product["price"], "20.00");
+    assert_eq!(product["categories"][0]["id"], lamps);
+
+    // Reverting adds a revision rather than rewriting history
+    let (_, list) = send(&app, "GET", &revisions, None).await;
+    assert_eq!(
+        numbers(&list),
+        vec![(3, "published", true), (2, "published", false), (1, "published", false)]
+    );
+    assert_eq!(list[0]["note"], "Reverted to revision 1");
+    let (_, diff) = send(&app, "GET", &format!("{}/diff?from=1&to=3", revisions), None).await;
+    assert_eq!(diff["changes"], json!([]));
+
+    let (status, _) = send(&app, "POST", &format!("{}/3/revert", revisions), None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (_, draft) = send(&app, "POST", &revisions, Some(json!({ "price": "22.00" }))).await;
+    let (status, _) = send(&app, "POST", &format!("{}/{}/revert", revisions, draft["number"]), None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_revision_prices_are_checked_before_they_are_written() {
+    let db = initialize().await;
+    let config = Config::default();
+    let app = create_test_app(db.clone());
+    let products = ProductRepository::new(db.clone(), test_cache(), test_storage(), &config.facets);
+
+    let (id, _, _) = create_catalog(&app).await;
+
+    // Requests built in code skip the checks made while reading a request body
+    let request = CreateRevisionRequest {
+        price: Some(Decimal::ZERO),
+        ..Default::default()
+    };
+    let error = products.create_revision(id as i32, request).await.unwrap_err();
+    assert!(matches!(error, ApiError::Validation(_)), "{:?}", error);
+
+    let (_, list) = send(&app, "GET", &format!("/api/products/{}/revisions", id), None).await;
+    assert_eq!(list.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_revisions_require_an_editor() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+    let public = create_public_test_app(db.clone());
+
+    let (id, _, _) = create_catalog(&app).await;
+    let revisions = format!("/api/products/{}/revisions", id);
+
+    let (status, _) = send(&public, "GET", &revisions, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = send(&public, "POST", &revisions, Some(json!({ "price": "1.00" }))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = send(&app, "GET", "/api/products/999999/revisions", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
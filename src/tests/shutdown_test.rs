@@ -0,0 +1,99 @@
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use axum::Router;
+use axum::http::StatusCode;
+use axum::routing::get;
+
+use crate::shutdown::Shutdown;
+
+/// Serve a router whose only route takes `delay` to respond, returning its address and the server task
+fn spawn_slow_server(
+    delay: Duration,
+    shutdown: Shutdown,
+    drain_timeout: Duration,
+) -> (String, tokio::task::JoinHandle<anyhow::Result<()>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = Router::new().route(
+        "/slow",
+        get(move || async move {
+            tokio::time::sleep(delay).await;
+            "done"
+        }),
+    );
+
+    let server = tokio::spawn(crate::serve(listener, app, shutdown, drain_timeout));
+    (format!("http://{}/slow", addr), server)
+}
+
+#[tokio::test]
+async fn test_in_flight_requests_are_drained() {
+    let shutdown = Shutdown::new();
+    let (url, server) = spawn_slow_server(Duration::from_millis(300), shutdown.clone(), Duration::from_secs(5));
+
+    let request = tokio::spawn(async move { hyper::Client::new().get(url.parse().unwrap()).await });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    shutdown.trigger();
+
+    // The in-flight request still completes before the server stops
+    let response = request.await.unwrap().unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    assert_eq!(&body[..], b"done");
+
+    tokio::time::timeout(Duration::from_secs(1), server)
+        .await
+        .expect("server should stop once drained")
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_drain_timeout_stops_the_server() {
+    let shutdown = Shutdown::new();
+    let (url, server) = spawn_slow_server(Duration::from_secs(30), shutdown.clone(), Duration::from_millis(200));
+
+    let request = tokio::spawn(async move { hyper::Client::new().get(url.parse().unwrap()).await });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let started = Instant::now();
+    shutdown.trigger();
+    tokio::time::timeout(Duration::from_secs(2), server)
+        .await
+        .expect("server should stop after the drain timeout")
+        .unwrap()
+        .unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(200));
+    request.abort();
+}
+
+#[tokio::test]
+async fn test_background_workers_stop_on_shutdown() {
+    let shutdown = Shutdown::new();
+    let stopped = Arc::new(AtomicBool::new(false));
+
+    shutdown.spawn_worker("cooperative", {
+        let stopped = stopped.clone();
+        |shutdown| async move {
+            shutdown.wait().await;
+            stopped.store(true, Ordering::SeqCst);
+        }
+    });
+    shutdown.spawn_worker("stuck", |_| std::future::pending());
+
+    assert!(!shutdown.is_triggered());
+    shutdown.trigger();
+    assert!(shutdown.is_triggered());
+
+    // The cooperative worker stops, the stuck one is aborted once the timeout elapses
+    tokio::time::timeout(
+        Duration::from_secs(1),
+        shutdown.join_workers(Duration::from_millis(100)),
+    )
+    .await
+    .expect("joining workers should be bounded by the timeout");
+    assert!(stopped.load(Ordering::SeqCst));
+}
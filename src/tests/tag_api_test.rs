@@ -0,0 +1,246 @@
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+use super::common::{create_public_test_app, create_test_app, create_test_category, initialize};
+
+/// Send a request with an optional JSON body and return the status and parsed JSON response
+async fn send(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Content-Type", "application/json");
+    let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+
+    let response = app.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+    let status = response.status();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+async fn create_product(app: &Router, sku: &str, category_id: i32, tags: Value) -> Value {
+    let body = json!({
+        "name": sku,
+        "price": "10.00",
+        "sku": sku,
+        "category_ids": [category_id],
+        "tags": tags,
+    });
+    let (status, product) = send(app, "POST", "/api/products", Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{}", product);
+    product
+}
+
+fn listed_skus(list: &Value) -> Vec<&str> {
+    let mut skus: Vec<&str> = list["products"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|product| product["sku"].as_str().unwrap())
+        .collect();
+    skus.sort();
+    skus
+}
+
+/// Find a tag's ID by name in `GET /api/tags`
+async fn tag_id(app: &Router, name: &str) -> i64 {
+    let (_, tags) = send(app, "GET", "/api/tags", None).await;
+    tags.as_array()
+        .unwrap()
+        .iter()
+        .find(|tag| tag["name"] == name)
+        .unwrap_or_else(|| panic!("no tag {:?} in {}", name, tags))["id"]
+        .as_i64()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_product_tags() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+
+    let category = create_test_category(&app).await;
+
+    // Tags are trimmed, lowercased, de-duplicated and sorted
+    let product = create_product(
+        &app,
+        "TAG-1",
+        category.id,
+        json!(["New-Arrival", " clearance ", "clearance"]),
+    )
+    .await;
+    assert_eq!(product["tags"], json!(["clearance", "new-arrival"]));
+
+    let uri = format!("/api/products/{}", product["id"]);
+    let (_, fetched) = send(&app, "GET", &uri, None).await;
+    assert_eq!(fetched["tags"], product["tags"]);
+
+    // Updates replace the whole set, and leave it alone when omitted
+    let (status, updated) = send(&app, "PUT", &uri, Some(json!({ "tags": ["summer"] }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["tags"], json!(["summer"]));
+    let (_, updated) = send(&app, "PUT", &uri, Some(json!({ "name": "Tagged" }))).await;
+    assert_eq!(updated["tags"], json!(["summer"]));
+
+    let (status, error) = send(&app, "PUT", &uri, Some(json!({ "tags": ["  "] }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(
+        error["error"]["message"].as_str().unwrap().contains("tags: Tag names"),
+        "{}",
+        error
+    );
+
+    // Tags are listed with usage counts, including ones no product uses any more
+    create_product(&app, "TAG-2", category.id, json!(["summer", "clearance"])).await;
+    let (status, tags) = send(&app, "GET", "/api/tags", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let counts: Vec<(&str, i64)> = tags
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tag| (tag["name"].as_str().unwrap(), tag["product_count"].as_i64().unwrap()))
+        .collect();
+    assert_eq!(counts, vec![("clearance", 1), ("new-arrival", 0), ("summer", 2)]);
+}
+
+#[tokio::test]
+async fn test_filter_products_by_tag() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+
+    let category = create_test_category(&app).await;
+    create_product(&app, "FILTER-A", category.id, json!(["clearance", "summer"])).await;
+    create_product(&app, "FILTER-B", category.id, json!(["clearance"])).await;
+    create_product(&app, "FILTER-C", category.id, json!([])).await;
+
+    for (query, expected) in [
+        ("tag=clearance", vec!["FILTER-A", "FILTER-B"]),
+        ("tag=Clearance", vec!["FILTER-A", "FILTER-B"]),
+        ("tag=clearance&tag=summer", vec!["FILTER-A"]),
+        ("tag=winter", vec![]),
+    ] {
+        let (status, list) = send(&app, "GET", &format!("/api/products?{}", query), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(listed_skus(&list), expected, "{}", query);
+        assert_eq!(list["total"], expected.len(), "{}", query);
+    }
+
+    // Tag filters combine with the category filter
+    let (_, list) = send(
+        &app,
+        "GET",
+        &format!("/api/products?category_id={}&tag=summer", category.id),
+        None,
+    )
+    .await;
+    assert_eq!(listed_skus(&list), vec!["FILTER-A"]);
+}
+
+#[tokio::test]
+async fn test_rename_and_merge_tags() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+
+    let category = create_test_category(&app).await;
+    let both = create_product(&app, "MERGE-1", category.id, json!(["sale", "clearance"])).await;
+    let sale_only = create_product(&app, "MERGE-2", category.id, json!(["sale"])).await;
+
+    // Cache a product so the rename must invalidate it
+    let uri = format!("/api/products/{}", sale_only["id"]);
+    send(&app, "GET", &uri, None).await;
+
+    let sale = tag_id(&app, "sale").await;
+    let (status, renamed) = send(
+        &app,
+        "PUT",
+        &format!("/api/tags/{}", sale),
+        Some(json!({ "name": "Discount" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(renamed, json!({ "id": sale, "name": "discount", "product_count": 2 }));
+    let (_, fetched) = send(&app, "GET", &uri, None).await;
+    assert_eq!(fetched["tags"], json!(["discount"]));
+
+    // Taking another tag's name is a conflict; merging is the way to combine them
+    let clearance = tag_id(&app, "clearance").await;
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/tags/{}", sale),
+        Some(json!({ "name": "clearance" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, merged) = send(
+        &app,
+        "POST",
+        &format!("/api/tags/{}/merge", sale),
+        Some(json!({ "into": clearance })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        merged,
+        json!({ "id": clearance, "name": "clearance", "product_count": 2 })
+    );
+
+    for product in [&both, &sale_only] {
+        let (_, fetched) = send(&app, "GET", &format!("/api/products/{}", product["id"]), None).await;
+        assert_eq!(fetched["tags"], json!(["clearance"]));
+    }
+    let (_, tags) = send(&app, "GET", "/api/tags", None).await;
+    assert_eq!(tags.as_array().unwrap().len(), 1);
+
+    // Merging needs two distinct, existing tags
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/api/tags/{}/merge", clearance),
+        Some(json!({ "into": clearance })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/api/tags/{}/merge", sale),
+        Some(json!({ "into": clearance })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&app, "PUT", "/api/tags/999999", Some(json!({ "name": "x" }))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_anonymous_tag_counts_only_include_published_products() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+    let public = create_public_test_app(db.clone());
+
+    let category = create_test_category(&app).await;
+    let live = create_product(&app, "LIVE-1", category.id, json!(["sale", "new"])).await;
+    create_product(&app, "DRAFT-1", category.id, json!(["sale", "draft-only"])).await;
+    for action in ["submit", "publish"] {
+        let uri = format!("/api/products/{}/{}", live["id"], action);
+        assert_eq!(send(&app, "POST", &uri, None).await.0, StatusCode::OK);
+    }
+
+    // Usage counts by tag name
+    let counts = |tags: Value| -> Value {
+        tags.as_array()
+            .unwrap()
+            .iter()
+            .map(|tag| (tag["name"].as_str().unwrap().to_string(), tag["product_count"].clone()))
+            .collect()
+    };
+    let (_, tags) = send(&app, "GET", "/api/tags", None).await;
+    assert_eq!(counts(tags), json!({"draft-only": 1, "new": 1, "sale": 2}));
+    let (_, tags) = send(&public, "GET", "/api/tags", None).await;
+    assert_eq!(counts(tags), json!({"draft-only": 0, "new": 1, "sale": 1}));
+}
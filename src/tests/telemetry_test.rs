@@ -0,0 +1,92 @@
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::response::Response;
+use opentelemetry::trace::TracerProvider;
+use tower::ServiceExt;
+use tracing_subscriber::layer::SubscriberExt;
+use uuid::Uuid;
+
+use super::common::{create_test_app, initialize, spawn_otlp_collector_stand_in};
+use crate::telemetry;
+
+const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+async fn get_with_headers(app: &Router, headers: &[(&str, &str)]) -> Response {
+    let mut request = Request::builder().method("GET").uri("/api/cache/stats");
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+
+    let response = app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    response
+}
+
+fn header<'a>(response: &'a Response, name: &str) -> &'a str {
+    response.headers()[name].to_str().unwrap()
+}
+
+#[tokio::test]
+async fn test_request_id_propagation() {
+    // Initialize test environment
+    let pool = initialize().await;
+    let app = create_test_app(pool.clone());
+
+    // A client-supplied request ID is echoed back
+    let response = get_with_headers(&app, &[("X-Request-Id", "req-123")]).await;
+    assert_eq!(header(&response, "x-request-id"), "req-123");
+
+    // Otherwise one is generated
+    let response = get_with_headers(&app, &[]).await;
+    assert!(Uuid::parse_str(header(&response, "x-request-id")).is_ok());
+
+    // An invalid request ID is replaced
+    let response = get_with_headers(&app, &[("X-Request-Id", "")]).await;
+    assert!(Uuid::parse_str(header(&response, "x-request-id")).is_ok());
+
+    // An incoming trace is continued with a new span ID
+    let response = get_with_headers(&app, &[("traceparent", TRACEPARENT)]).await;
+    let traceparent = header(&response, "traceparent");
+    assert!(traceparent.starts_with(&format!("00-{}-", TRACE_ID)));
+    assert_ne!(traceparent, TRACEPARENT);
+
+    // A malformed traceparent starts a new trace
+    let response = get_with_headers(&app, &[("traceparent", "00-not-a-trace-01")]).await;
+    let traceparent = header(&response, "traceparent");
+    assert_eq!(traceparent.len(), 55);
+    assert!(!traceparent.contains(TRACE_ID));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_otlp_export_to_collector_stand_in() {
+    // Initialize test environment
+    let pool = initialize().await;
+    let app = create_test_app(pool.clone());
+    let (endpoint, received) = spawn_otlp_collector_stand_in().await;
+
+    // Route this test's spans through an OTLP exporter pointed at the stand-in
+    let provider = telemetry::tracer_provider(&endpoint, "catalog-test").unwrap();
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("catalog-test")));
+    let guard = tracing::subscriber::set_default(subscriber);
+
+    let response = get_with_headers(&app, &[("traceparent", TRACEPARENT)]).await;
+    assert!(header(&response, "traceparent").starts_with(&format!("00-{}-", TRACE_ID)));
+
+    drop(guard);
+    tokio::task::spawn_blocking(move || provider.force_flush())
+        .await
+        .unwrap()
+        .unwrap();
+
+    // The request span and the handler span were exported under the propagated trace
+    let exported = received.lock().unwrap().join("\n");
+    assert!(exported.contains(TRACE_ID));
+    assert!(exported.contains("00f067aa0ba902b7"));
+    assert!(exported.contains("catalog-test"));
+    assert!(exported.contains(r#""request""#));
+    assert!(exported.contains(r#""cache_stats""#));
+}
@@ -0,0 +1,215 @@
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+use super::common::{create_public_test_app, create_public_test_app_with, create_tenant_test_app, initialize};
+use crate::config::Config;
+use crate::models::api_key::CreateApiKeyRequest;
+use crate::repository::ApiKeyRepository;
+
+/// Send a request with an optional JSON body and return the status and parsed JSON response
+async fn send(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    send_with_headers(app, method, uri, &[], body).await
+}
+
+/// Like [`send`], adding the given headers
+async fn send_with_headers(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    headers: &[(&str, &str)],
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Content-Type", "application/json");
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+
+    let response = app.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+    let status = response.status();
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+/// Create a category and a published product in it, returning their IDs
+async fn create_catalog(app: &Router, sku: &str) -> (i64, i64) {
+    let (status, category) = send(app, "POST", "/api/categories", Some(json!({ "name": "Lamps" }))).await;
+    assert_eq!(status, StatusCode::OK, "{}", category);
+    let category_id = category["id"].as_i64().unwrap();
+
+    let body = json!({
+        "name": "Desk Lamp",
+        "price": "20.00",
+        "sku": sku,
+        "category_ids": [category_id],
+        "tags": ["desk"],
+    });
+    let (status, product) = send(app, "POST", "/api/products", Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{}", product);
+    let product_id = product["id"].as_i64().unwrap();
+
+    for action in ["submit", "publish"] {
+        let (status, _) = send(app, "POST", &format!("/api/products/{}/{}", product_id, action), None).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    (category_id, product_id)
+}
+
+#[tokio::test]
+async fn test_catalogs_are_isolated_between_tenants() {
+    let db = initialize().await;
+    let acme = create_tenant_test_app(db.clone(), "acme");
+    let globex = create_tenant_test_app(db.clone(), "globex");
+
+    // Category names and SKUs only need to be unique within a tenant
+    let (acme_category, acme_product) = create_catalog(&acme, "LAMP-1").await;
+    let (globex_category, globex_product) = create_catalog(&globex, "LAMP-1").await;
+    let (status, _) = send(&acme, "POST", "/api/categories", Some(json!({ "name": "Lamps" }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (_, list) = send(&acme, "GET", "/api/products", None).await;
+    assert_eq!(list["total"], 1);
+    assert_eq!(list["products"][0]["id"], acme_product);
+    let (_, list) = send(&globex, "GET", "/api/categories?include_product_count=true", None).await;
+    assert_eq!(list["categories"].as_array().unwrap().len(), 1);
+    assert_eq!(list["categories"][0]["id"], globex_category);
+    let (_, tags) = send(&globex, "GET", "/api/tags", None).await;
+    assert_eq!(tags.as_array().unwrap().len(), 1);
+    assert_eq!(tags[0]["product_count"], 1);
+
+    // Another tenant's rows are reported as missing, whatever the operation
+    let product = format!("/api/products/{}", acme_product);
+    let category = format!("/api/categories/{}", acme_category);
+    for (method, uri, body) in [
+        ("GET", product.clone(), None),
+        ("PUT", product.clone(), Some(json!({ "name": "Stolen" }))),
+        ("POST", format!("{}/archive", product), None),
+        ("GET", format!("{}/revisions", product), None),
+        ("GET", format!("{}/media", product), None),
+        ("DELETE", product.clone(), None),
+        ("GET", category.clone(), None),
+        ("GET", format!("{}/products", category), None),
+        ("GET", format!("{}/attributes", category), None),
+        ("DELETE", category.clone(), None),
+    ] {
+        let (status, _) = send(&globex, method, &uri, body).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{} {}", method, uri);
+    }
+    let (status, list) = send(
+        &globex,
+        "GET",
+        &format!("/api/products?category_id={}", acme_category),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(list["total"], 0);
+
+    // Products can't be linked to another tenant's categories
+    let body = json!({ "name": "Mixed", "price": "1.00", "category_ids": [acme_category] });
+    let (status, _) = send(&globex, "POST", "/api/products", Some(body)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let body = json!({ "category_ids": [globex_category, acme_category] });
+    let (status, _) = send(&globex, "PUT", &format!("/api/products/{}", globex_product), Some(body)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Nothing was changed by the other tenant
+    let (status, product) = send(&acme, "GET", &product, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(product["name"], "Desk Lamp");
+    assert_eq!(product["status"], "published");
+}
+
+#[tokio::test]
+async fn test_anonymous_requests_name_their_tenant() {
+    let db = initialize().await;
+    let acme = create_tenant_test_app(db.clone(), "acme");
+    let mut config = Config::default();
+    config.tenancy.base_domain = Some("catalog.test".to_string());
+    let public = create_public_test_app_with(&config, db.clone());
+
+    let (_, acme_product) = create_catalog(&acme, "LAMP-1").await;
+    let uri = format!("/api/products/{}", acme_product);
+
+    // Without a tenant, requests use the default one
+    let (_, list) = send(&public, "GET", "/api/products", None).await;
+    assert_eq!(list["total"], 0);
+    let (status, _) = send(&public, "GET", &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    for headers in [
+        [("X-Tenant-Id", "acme")],
+        [("Host", "acme.catalog.test")],
+        [("Host", "ACME.catalog.test:8080")],
+    ] {
+        let (_, list) = send_with_headers(&public, "GET", "/api/products", &headers, None).await;
+        assert_eq!(list["total"], 1, "{:?}", headers);
+        let (status, _) = send_with_headers(&public, "GET", &uri, &headers, None).await;
+        assert_eq!(status, StatusCode::OK, "{:?}", headers);
+    }
+
+    // The header takes precedence over the subdomain; other hosts are ignored
+    let headers = [("X-Tenant-Id", "globex"), ("Host", "acme.catalog.test")];
+    let (status, _) = send_with_headers(&public, "GET", &uri, &headers, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let headers = [("Host", "acme.elsewhere.test")];
+    let (status, _) = send_with_headers(&public, "GET", &uri, &headers, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    for tenant in ["Acme", "-acme", "acme corp", ""] {
+        let headers = [("X-Tenant-Id", tenant)];
+        let (status, _) = send_with_headers(&public, "GET", "/api/products", &headers, None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{:?}", tenant);
+    }
+}
+
+#[tokio::test]
+async fn test_api_keys_are_bound_to_their_tenant() {
+    let db = initialize().await;
+    let acme = create_tenant_test_app(db.clone(), "acme");
+    let public = create_public_test_app(db.clone());
+
+    let (category, _) = create_catalog(&acme, "LAMP-1").await;
+    let body = json!({ "name": "Floor Lamp", "price": "80.00", "category_ids": [category] });
+    let (_, draft) = send(&acme, "POST", "/api/products", Some(body)).await;
+    let draft = format!("/api/products/{}", draft["id"]);
+
+    let created = ApiKeyRepository::new(db.clone())
+        .create_api_key(CreateApiKeyRequest {
+            name: "acme editor".to_string(),
+            tenant: "acme".parse().unwrap(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(created.api_key.tenant.as_str(), "acme");
+    let bearer = format!("Bearer {}", created.secret);
+
+    // The key's tenant applies without naming it, and naming it again is allowed
+    for headers in [
+        vec![("Authorization", bearer.as_str())],
+        vec![("Authorization", bearer.as_str()), ("X-Tenant-Id", "acme")],
+    ] {
+        let (status, product) = send_with_headers(&public, "GET", &draft, &headers, None).await;
+        assert_eq!(status, StatusCode::OK, "{:?}", headers);
+        assert_eq!(product["status"], "draft");
+    }
+
+    // A key never reaches another tenant's catalog
+    let headers = [("Authorization", bearer.as_str()), ("X-Tenant-Id", "globex")];
+    let (status, error) = send_with_headers(&public, "GET", "/api/products", &headers, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(error["error"]["status"], 403);
+
+    // Anonymous clients naming the tenant still only see published products
+    let headers = [("X-Tenant-Id", "acme")];
+    let (status, _) = send_with_headers(&public, "GET", &draft, &headers, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,215 @@
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use sea_orm::{ConnectionTrait, DbBackend};
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+use super::common::{create_public_test_app, create_test_app, initialize};
+
+/// Send a request with an optional JSON body and return the status and parsed JSON response
+async fn send(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let (status, _, body) = send_localized(app, method, uri, None, body).await;
+    (status, body)
+}
+
+/// Like [`send`], with an optional `Accept-Language` header, also returning `Content-Language`
+async fn send_localized(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    accept_language: Option<&str>,
+    body: Option<Value>,
+) -> (StatusCode, Option<String>, Value) {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Content-Type", "application/json");
+    if let Some(accept_language) = accept_language {
+        request = request.header(header::ACCEPT_LANGUAGE, accept_language);
+    }
+    let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+
+    let response = app.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+    let status = response.status();
+    let content_language = response
+        .headers()
+        .get(header::CONTENT_LANGUAGE)
+        .map(|value| value.to_str().unwrap().to_string());
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+    (
+        status,
+        content_language,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+/// Create a category and a product in it, returning their IDs
+async fn create_catalog(app: &Router) -> (i64, i64) {
+    let body = json!({ "name": "Footwear", "description": "Shoes and boots" });
+    let (status, category) = send(app, "POST", "/api/categories", Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{}", category);
+    let category_id = category["id"].as_i64().unwrap();
+
+    let body = json!({
+        "name": "Running Shoes",
+        "description": "Light shoes for running",
+        "price": "89.00",
+        "category_ids": [category_id],
+    });
+    let (status, product) = send(app, "POST", "/api/products", Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{}", product);
+
+    (category_id, product["id"].as_i64().unwrap())
+}
+
+#[tokio::test]
+async fn test_content_follows_the_locale_chain() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+
+    let (category_id, product_id) = create_catalog(&app).await;
+    let product = format!("/api/products/{}", product_id);
+    let category = format!("/api/categories/{}", category_id);
+
+    let body = json!({ "name": "Laufschuhe", "description": "Leichte Schuhe zum Laufen" });
+    let (status, translation) = send(&app, "PUT", &format!("{}/translations/de", product), Some(body)).await;
+    assert_eq!(status, StatusCode::OK, "{}", translation);
+    assert_eq!(translation["locale"], "de");
+    let body = json!({ "name": "Chaussures de course" });
+    let (status, _) = send(&app, "PUT", &format!("{}/translations/FR", product), Some(body)).await;
+    assert_eq!(status, StatusCode::OK);
+    let body = json!({ "name": "Schuhe" });
+    let (status, _) = send(&app, "PUT", &format!("{}/translations/de", category), Some(body)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Regional and weighted preferences resolve to the best supported locale
+    for accept_language in ["de", "de-CH, fr;q=0.8", "es, fr;q=0.5, de;q=0.9"] {
+        let (status, content_language, body) = send_localized(&app, "GET", &product, Some(accept_language), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_language.as_deref(), Some("de"), "{}", accept_language);
+        assert_eq!(body["name"], "Laufschuhe");
+        assert_eq!(body["description"], "Leichte Schuhe zum Laufen");
+        assert_eq!(body["categories"][0]["name"], "Schuhe");
+    }
+
+    // Missing text falls back to the default locale; the query parameter beats the header
+    let (_, content_language, body) =
+        send_localized(&app, "GET", &format!("{}?locale=fr", product), Some("de"), None).await;
+    assert_eq!(content_language.as_deref(), Some("fr"));
+    assert_eq!(body["name"], "Chaussures de course");
+    assert_eq!(body["description"], "Light shoes for running");
+    assert_eq!(body["categories"][0]["name"], "Footwear");
+    let (_, content_language, body) = send_localized(&app, "GET", &product, Some("es, *"), None).await;
+    assert_eq!(content_language.as_deref(), Some("en"));
+    assert_eq!(body["name"], "Running Shoes");
+
+    // Lists and categories are translated too
+    let (_, _, list) = send_localized(&app, "GET", "/api/products?locale=de", None, None).await;
+    assert_eq!(list["products"][0]["name"], "Laufschuhe");
+    let (_, _, list) = send_localized(&app, "GET", "/api/categories", Some("de"), None).await;
+    assert_eq!(list["categories"][0]["name"], "Schuhe");
+    assert_eq!(list["categories"][0]["description"], "Shoes and boots");
+    let (_, _, body) = send_localized(&app, "GET", &category, Some("de"), None).await;
+    assert_eq!(body["name"], "Schuhe");
+    let (_, _, list) = send_localized(&app, "GET", &format!("{}/products", category), Some("fr"), None).await;
+    assert_eq!(list[0]["name"], "Chaussures de course");
+
+    // Untranslated requests are unaffected
+    let (_, body) = send(&app, "GET", &product, None).await;
+    assert_eq!(body["name"], "Running Shoes");
+
+    let (status, list) = send(&app, "GET", &format!("{}/translations", product), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let locales: Vec<&str> = list
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|translation| translation["locale"].as_str().unwrap())
+        .collect();
+    assert_eq!(locales, ["de", "fr"]);
+
+    let (status, _) = send(&app, "DELETE", &format!("{}/translations/fr", product), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(&app, "DELETE", &format!("{}/translations/fr", product), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (_, _, body) = send_localized(&app, "GET", &product, Some("fr"), None).await;
+    assert_eq!(body["name"], "Running Shoes");
+}
+
+#[tokio::test]
+async fn test_translations_are_validated() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+    let public = create_public_test_app(db.clone());
+
+    let (category_id, product_id) = create_catalog(&app).await;
+    let product = format!("/api/products/{}", product_id);
+
+    // The default locale is the product itself; other locales must be supported
+    for locale in ["en", "es", "not a locale"] {
+        let uri = format!("{}/translations/{}", product, locale.replace(' ', "%20"));
+        let (status, _) = send(&app, "PUT", &uri, Some(json!({ "name": "Name" }))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", locale);
+    }
+    let uri = format!("/api/categories/{}/translations/de", category_id);
+    let (status, _) = send(&app, "PUT", &uri, Some(json!({ "name": "" }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(
+        &app,
+        "PUT",
+        "/api/products/0/translations/de",
+        Some(json!({ "name": "Name" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Requested locales must be supported, but preferences never fail
+    let (status, _) = send(&app, "GET", &format!("{}?locale=es", product), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _, _) = send_localized(&app, "GET", &product, Some(";;q=x, 12345"), None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Anonymous clients can't read the translations of unpublished products
+    let (status, _) = send(&public, "GET", &format!("{}/translations", product), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_search_uses_each_locales_configuration() {
+    let db = initialize().await;
+    let app = create_test_app(db.clone());
+
+    let (_, product_id) = create_catalog(&app).await;
+    let body = json!({ "name": "Laufschuhe", "description": "Leichte Schuhe zum Laufen" });
+    let uri = format!("/api/products/{}/translations/de", product_id);
+    let (status, _) = send(&app, "PUT", &uri, Some(body)).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, lighting) = send(&app, "POST", "/api/categories", Some(json!({ "name": "Lighting" }))).await;
+    let body = json!({ "name": "Desk Lamp", "price": "20.00", "category_ids": [lighting["id"]] });
+    let (status, _) = send(&app, "POST", "/api/products", Some(body)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Stemming matches other forms of a word: English "run" finds "running", German "Schuh"
+    // finds "Schuhe". SQLite matches substrings instead, so "runs" only finds "running" on Postgres
+    let excluding_lamps = match db.get_database_backend() {
+        DbBackend::Postgres => "/api/products?q=runs%20-lamp",
+        _ => "/api/products?q=run%20-lamp",
+    };
+    for (uri, total) in [
+        ("/api/products?q=run", 1),
+        (excluding_lamps, 1),
+        ("/api/products?q=schuh", 0),
+        ("/api/products?q=schuh&locale=de", 1),
+        ("/api/products?q=laufen&locale=de", 1),
+        ("/api/products?q=running&locale=de", 1),
+        ("/api/products?q=lamp&locale=de", 1),
+        ("/api/products?q=boots", 0),
+        ("/api/products?q=%20", 2),
+    ] {
+        let (status, list) = send(&app, "GET", uri, None).await;
+        assert_eq!(status, StatusCode::OK, "{}", uri);
+        assert_eq!(list["total"], total, "{}", uri);
+    }
+}
@@ -1,12 +1,42 @@
 use anyhow::Result;
-use bigdecimal::{BigDecimal, Signed};
 use validator::ValidationError;
 
-/// Validates that a Decimal value is positive
-pub fn validate_decimal_positive(decimal: &BigDecimal) -> Result<(), ValidationError> {
-    if !decimal.is_positive() {
-        let mut error = ValidationError::new("positive_decimal");
-        error.message = Some(std::borrow::Cow::from("Price must be a positive number"));
+/// Validates that an attribute name is usable as an `attr.<name>` query parameter
+///
+/// Names start with a lowercase letter and contain only lowercase letters, digits and underscores.
+pub fn validate_attribute_name(name: &str) -> Result<(), ValidationError> {
+    let mut chars = name.chars();
+    let valid = name.len() <= 50
+        && chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        let mut error = ValidationError::new("attribute_name");
+        error.message = Some(std::borrow::Cow::from(
+            "Attribute name must start with a lowercase letter, contain only lowercase letters, digits and underscores, and be at most 50 characters",
+        ));
+        return Err(error);
+    }
+    Ok(())
+}
+
+/// Validates product tag names
+///
+/// Tags are stored trimmed and lowercased; each must then be between 1 and 50 characters.
+pub fn validate_tag_names(tags: &[String]) -> Result<(), ValidationError> {
+    if !tags.iter().all(|tag| validate_tag_name(tag).is_ok()) {
+        let mut error = ValidationError::new("tag_names");
+        error.message = Some(std::borrow::Cow::from("Tag names must be between 1 and 50 characters"));
+        return Err(error);
+    }
+    Ok(())
+}
+
+/// Validates a single tag name; see [`validate_tag_names`]
+pub fn validate_tag_name(name: &str) -> Result<(), ValidationError> {
+    let length = name.trim().chars().count();
+    if !(1..=50).contains(&length) {
+        let mut error = ValidationError::new("tag_name");
+        error.message = Some(std::borrow::Cow::from("Tag name must be between 1 and 50 characters"));
         return Err(error);
     }
     Ok(())